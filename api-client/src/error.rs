@@ -0,0 +1,104 @@
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use shared::{ApiResponse, ErrorCode, ValidationErrors};
+use uuid::Uuid;
+
+use crate::Response;
+
+/// Error returned by the client, keyed on the backend's `ErrorCode`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiError {
+    /// Not logged in, or the login has expired
+    Unauthorized(String),
+    Forbidden(String),
+    NotFound(String),
+    /// `fields` is empty when the problem is not tied to a request field
+    Validation { message: String, fields: ValidationErrors },
+    /// `existing_id` is the resource the request clashed with, if known
+    Conflict { message: String, existing_id: Option<Uuid> },
+    Server(String),
+    /// The request failed or the response could not be read
+    Network(String),
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Unauthorized(message)
+            | ApiError::Forbidden(message)
+            | ApiError::NotFound(message)
+            | ApiError::Validation { message, .. }
+            | ApiError::Conflict { message, .. }
+            | ApiError::Server(message)
+            | ApiError::Network(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl ApiError {
+    /// Per-field messages, if the request failed validation.
+    pub fn field_errors(&self) -> Option<&ValidationErrors> {
+        match self {
+            ApiError::Validation { fields, .. } if !fields.is_empty() => Some(fields),
+            _ => None,
+        }
+    }
+}
+
+/// Unwraps a response envelope into its data, or the error it describes.
+/// Failures without an envelope, such as a proxy's 502 page or axum's own
+/// plain-text rejections, are told apart by their status.
+pub(crate) fn parse_api_response<T: DeserializeOwned>(response: &Response) -> Result<T, ApiError> {
+    let api_response: ApiResponse<serde_json::Value> = match serde_json::from_slice(&response.body) {
+        Ok(api_response) => api_response,
+        Err(_) if !response.is_success() => return Err(status_error(response)),
+        Err(e) => return Err(ApiError::Network(format!("Failed to parse API response: {e}"))),
+    };
+
+    if api_response.success {
+        // `()` responses carry `null` data
+        let data = api_response.data.unwrap_or(serde_json::Value::Null);
+        serde_json::from_value(data)
+            .map_err(|e| ApiError::Network(format!("Failed to parse API response: {e}")))
+    } else {
+        let message = api_response.message.unwrap_or_else(|| "Unknown API error".to_string());
+        Err(match api_response.error_code {
+            Some(ErrorCode::Unauthorized) => ApiError::Unauthorized(message),
+            Some(ErrorCode::Forbidden) => ApiError::Forbidden(message),
+            Some(ErrorCode::NotFound) => ApiError::NotFound(message),
+            Some(ErrorCode::Validation) => ApiError::Validation {
+                message,
+                fields: api_response.field_errors.unwrap_or_default(),
+            },
+            Some(ErrorCode::Conflict) => ApiError::Conflict {
+                message,
+                existing_id: api_response.existing_id,
+            },
+            Some(ErrorCode::Internal) | None => ApiError::Server(message),
+        })
+    }
+}
+
+/// The error for a failed response that is not an `ApiResponse`.
+fn status_error(response: &Response) -> ApiError {
+    let status = response.status;
+    let text = String::from_utf8_lossy(&response.body);
+    let text = text.trim();
+    // Plain-text reasons are worth showing; HTML error pages are not
+    let message = if text.is_empty() || text.starts_with('<') || text.len() > 200 {
+        format!("Server answered HTTP {status}")
+    } else {
+        format!("{text} (HTTP {status})")
+    };
+    match status {
+        401 => ApiError::Unauthorized(message),
+        403 => ApiError::Forbidden(message),
+        404 => ApiError::NotFound(message),
+        409 => ApiError::Conflict { message, existing_id: None },
+        400..=499 => ApiError::Validation { message, fields: ValidationErrors::default() },
+        _ => ApiError::Server(message),
+    }
+}
@@ -0,0 +1,519 @@
+//! Typed client for the tracker API, generic over the HTTP transport so it
+//! runs in the browser (`gloo` feature) as well as natively (`reqwest`
+//! feature).
+
+use serde::{de::DeserializeOwned, Serialize};
+use shared::*;
+use uuid::Uuid;
+
+mod error;
+mod transport;
+
+pub use error::ApiError;
+pub use transport::{Method, Request, Response, Transport};
+#[cfg(feature = "gloo")]
+pub use transport::GlooTransport;
+#[cfg(feature = "reqwest")]
+pub use transport::ReqwestTransport;
+
+/// Content type of the JSON Merge Patch bodies sent to `PATCH` endpoints
+const MERGE_PATCH: &str = "application/merge-patch+json";
+
+type Body = Option<(&'static str, String)>;
+
+fn json<B: Serialize>(body: &B) -> Result<Body, ApiError> {
+    serde_json::to_string(body)
+        .map(|body| Some(("application/json", body)))
+        .map_err(|e| ApiError::Network(format!("Failed to serialize request: {e}")))
+}
+
+fn merge_patch<B: Serialize>(body: &B) -> Result<Body, ApiError> {
+    Ok(json(body)?.map(|(_, body)| (MERGE_PATCH, body)))
+}
+
+/// Percent-encodes a query string component.
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// Client for every route of the backend. Session, tag, client, project,
+/// report and invoice requests apply to the chosen workspace, or to the
+/// caller's first one if none is chosen.
+#[derive(Debug, Clone)]
+pub struct ApiClient<T> {
+    transport: T,
+    base_url: String,
+    workspace_id: Option<Uuid>,
+}
+
+impl<T: Transport> ApiClient<T> {
+    /// `base_url` is the server root, e.g. `http://localhost:8080`, or empty
+    /// for the current origin in the browser.
+    pub fn new(base_url: &str, transport: T) -> Self {
+        Self {
+            transport,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            workspace_id: None,
+        }
+    }
+
+    pub fn with_workspace(mut self, workspace_id: Option<Uuid>) -> Self {
+        self.workspace_id = workspace_id;
+        self
+    }
+
+    pub fn workspace_id(&self) -> Option<Uuid> {
+        self.workspace_id
+    }
+
+    fn url(&self, path: &str, query: &[(&str, String)]) -> String {
+        let mut url = format!("{}/api{path}", self.base_url);
+        for (i, (key, value)) in query.iter().enumerate() {
+            url.push(if i == 0 { '?' } else { '&' });
+            url.push_str(key);
+            url.push('=');
+            url.push_str(&encode(value));
+        }
+        url
+    }
+
+    /// URL of `path` scoped to the chosen workspace.
+    fn workspace_url(&self, path: &str, query: &[(&str, String)]) -> String {
+        let mut pairs = Vec::with_capacity(query.len() + 1);
+        if let Some(id) = self.workspace_id {
+            pairs.push(("workspace_id", id.to_string()));
+        }
+        pairs.extend(query.iter().cloned());
+        self.url(path, &pairs)
+    }
+
+    async fn call<R: DeserializeOwned>(&self, method: Method, url: String, body: Body) -> Result<R, ApiError> {
+        let response = self.transport.send(Request { method, url, body }).await?;
+        error::parse_api_response(&response)
+    }
+
+    /// Fetches a non-JSON document; failures still carry the JSON envelope.
+    async fn fetch(&self, url: String) -> Result<Vec<u8>, ApiError> {
+        let response = self.transport.send(Request { method: Method::Get, url, body: None }).await?;
+        if response.is_success() {
+            Ok(response.body)
+        } else {
+            error::parse_api_response::<()>(&response)?;
+            Err(ApiError::Server(format!("Unexpected status {}", response.status)))
+        }
+    }
+
+    // Accounts
+    pub async fn register(&self, req: &RegisterRequest) -> Result<User, ApiError> {
+        self.call(Method::Post, self.url("/auth/register", &[]), json(req)?).await
+    }
+
+    pub async fn login(&self, req: &LoginRequest) -> Result<User, ApiError> {
+        self.call(Method::Post, self.url("/auth/login", &[]), json(req)?).await
+    }
+
+    pub async fn logout(&self) -> Result<(), ApiError> {
+        self.call(Method::Post, self.url("/auth/logout", &[]), None).await
+    }
+
+    pub async fn current_user(&self) -> Result<User, ApiError> {
+        self.call(Method::Get, self.url("/auth/me", &[]), None).await
+    }
+
+    // Workspaces
+    pub async fn get_workspaces(&self) -> Result<Vec<Workspace>, ApiError> {
+        self.call(Method::Get, self.url("/workspaces", &[]), None).await
+    }
+
+    pub async fn create_workspace(&self, req: &WorkspaceRequest) -> Result<Workspace, ApiError> {
+        self.call(Method::Post, self.url("/workspaces", &[]), json(req)?).await
+    }
+
+    pub async fn replace_workspace(&self, id: Uuid, req: &WorkspaceRequest) -> Result<Workspace, ApiError> {
+        self.call(Method::Put, self.url(&format!("/workspaces/{id}"), &[]), json(req)?).await
+    }
+
+    pub async fn delete_workspace(&self, id: Uuid) -> Result<(), ApiError> {
+        self.call(Method::Delete, self.url(&format!("/workspaces/{id}"), &[]), None).await
+    }
+
+    pub async fn get_members(&self, workspace_id: Uuid) -> Result<Vec<WorkspaceMember>, ApiError> {
+        self.call(Method::Get, self.url(&format!("/workspaces/{workspace_id}/members"), &[]), None).await
+    }
+
+    pub async fn add_member(&self, workspace_id: Uuid, req: &AddMemberRequest) -> Result<WorkspaceMember, ApiError> {
+        self.call(Method::Post, self.url(&format!("/workspaces/{workspace_id}/members"), &[]), json(req)?).await
+    }
+
+    pub async fn update_member(
+        &self,
+        workspace_id: Uuid,
+        user_id: Uuid,
+        req: &UpdateMemberRequest,
+    ) -> Result<WorkspaceMember, ApiError> {
+        let url = self.url(&format!("/workspaces/{workspace_id}/members/{user_id}"), &[]);
+        self.call(Method::Patch, url, json(req)?).await
+    }
+
+    pub async fn remove_member(&self, workspace_id: Uuid, user_id: Uuid) -> Result<(), ApiError> {
+        let url = self.url(&format!("/workspaces/{workspace_id}/members/{user_id}"), &[]);
+        self.call(Method::Delete, url, None).await
+    }
+
+    // API tokens
+    pub async fn get_api_tokens(&self) -> Result<Vec<ApiToken>, ApiError> {
+        self.call(Method::Get, self.url("/tokens", &[]), None).await
+    }
+
+    pub async fn create_api_token(&self, req: &CreateApiTokenRequest) -> Result<CreatedApiToken, ApiError> {
+        self.call(Method::Post, self.url("/tokens", &[]), json(req)?).await
+    }
+
+    pub async fn delete_api_token(&self, id: Uuid) -> Result<(), ApiError> {
+        self.call(Method::Delete, self.url(&format!("/tokens/{id}"), &[]), None).await
+    }
+
+    // Sessions
+    pub async fn get_sessions(&self, query: &SessionQuery) -> Result<Page<WorkSessionWithTags>, ApiError> {
+        self.call(Method::Get, self.workspace_url("/sessions", &query.to_query_pairs()), None).await
+    }
+
+    /// Link that downloads every session matching `query` as CSV.
+    pub fn export_sessions_url(&self, query: &SessionQuery) -> String {
+        let pairs: Vec<_> = query
+            .to_query_pairs()
+            .into_iter()
+            .filter(|(key, _)| *key != "cursor" && *key != "limit")
+            .collect();
+        self.workspace_url("/sessions/export.csv", &pairs)
+    }
+
+    pub async fn export_sessions(&self, query: &SessionQuery) -> Result<Vec<u8>, ApiError> {
+        self.fetch(self.export_sessions_url(query)).await
+    }
+
+    pub async fn import_sessions(&self, records: &[ImportSessionRecord], options: &ImportOptions) -> Result<ImportReport, ApiError> {
+        let url = self.workspace_url("/sessions/import", &[("dry_run", options.dry_run.to_string())]);
+        self.call(Method::Post, url, json(&records)?).await
+    }
+
+    /// Imports sessions from CSV in the export layout.
+    pub async fn import_sessions_csv(&self, csv: String, options: &ImportOptions) -> Result<ImportReport, ApiError> {
+        let url = self.workspace_url("/sessions/import", &[("dry_run", options.dry_run.to_string())]);
+        self.call(Method::Post, url, Some(("text/csv", csv))).await
+    }
+
+    pub async fn get_session(&self, id: Uuid) -> Result<WorkSessionWithTags, ApiError> {
+        self.call(Method::Get, self.workspace_url(&format!("/sessions/{id}"), &[]), None).await
+    }
+
+    pub async fn create_session(&self, req: &CreateSessionRequest) -> Result<WorkSession, ApiError> {
+        self.call(Method::Post, self.workspace_url("/sessions", &[]), json(req)?).await
+    }
+
+    pub async fn start_session(&self, req: &StartSessionRequest) -> Result<WorkSession, ApiError> {
+        self.call(Method::Post, self.workspace_url("/sessions/start", &[]), json(req)?).await
+    }
+
+    pub async fn stop_session(&self, id: Uuid) -> Result<WorkSession, ApiError> {
+        self.call(Method::Post, self.workspace_url(&format!("/sessions/{id}/stop"), &[]), None).await
+    }
+
+    pub async fn pause_session(&self, id: Uuid) -> Result<WorkSession, ApiError> {
+        self.call(Method::Post, self.workspace_url(&format!("/sessions/{id}/pause"), &[]), None).await
+    }
+
+    pub async fn resume_session(&self, id: Uuid) -> Result<WorkSession, ApiError> {
+        self.call(Method::Post, self.workspace_url(&format!("/sessions/{id}/resume"), &[]), None).await
+    }
+
+    pub async fn replace_session(&self, id: Uuid, req: &CreateSessionRequest) -> Result<WorkSessionWithTags, ApiError> {
+        self.call(Method::Put, self.workspace_url(&format!("/sessions/{id}"), &[]), json(req)?).await
+    }
+
+    pub async fn update_session(&self, id: Uuid, req: &UpdateSessionRequest) -> Result<WorkSessionWithTags, ApiError> {
+        self.call(Method::Patch, self.workspace_url(&format!("/sessions/{id}"), &[]), merge_patch(req)?).await
+    }
+
+    pub async fn delete_session(&self, id: Uuid) -> Result<(), ApiError> {
+        self.call(Method::Delete, self.workspace_url(&format!("/sessions/{id}"), &[]), None).await
+    }
+
+    // Tags
+    pub async fn get_tags(&self) -> Result<Vec<Tag>, ApiError> {
+        self.call(Method::Get, self.workspace_url("/tags", &[]), None).await
+    }
+
+    pub async fn get_tag(&self, id: Uuid) -> Result<Tag, ApiError> {
+        self.call(Method::Get, self.workspace_url(&format!("/tags/{id}"), &[]), None).await
+    }
+
+    pub async fn create_tag(&self, req: &CreateTagRequest) -> Result<Tag, ApiError> {
+        self.call(Method::Post, self.workspace_url("/tags", &[]), json(req)?).await
+    }
+
+    pub async fn replace_tag(&self, id: Uuid, req: &CreateTagRequest) -> Result<Tag, ApiError> {
+        self.call(Method::Put, self.workspace_url(&format!("/tags/{id}"), &[]), json(req)?).await
+    }
+
+    pub async fn update_tag(&self, id: Uuid, req: &UpdateTagRequest) -> Result<Tag, ApiError> {
+        self.call(Method::Patch, self.workspace_url(&format!("/tags/{id}"), &[]), merge_patch(req)?).await
+    }
+
+    pub async fn delete_tag(&self, id: Uuid) -> Result<(), ApiError> {
+        self.call(Method::Delete, self.workspace_url(&format!("/tags/{id}"), &[]), None).await
+    }
+
+    // Clients
+    pub async fn get_clients(&self) -> Result<Vec<Client>, ApiError> {
+        self.call(Method::Get, self.workspace_url("/clients", &[]), None).await
+    }
+
+    pub async fn get_client(&self, id: Uuid) -> Result<Client, ApiError> {
+        self.call(Method::Get, self.workspace_url(&format!("/clients/{id}"), &[]), None).await
+    }
+
+    pub async fn create_client(&self, req: &CreateClientRequest) -> Result<Client, ApiError> {
+        self.call(Method::Post, self.workspace_url("/clients", &[]), json(req)?).await
+    }
+
+    pub async fn replace_client(&self, id: Uuid, req: &CreateClientRequest) -> Result<Client, ApiError> {
+        self.call(Method::Put, self.workspace_url(&format!("/clients/{id}"), &[]), json(req)?).await
+    }
+
+    pub async fn update_client(&self, id: Uuid, req: &UpdateClientRequest) -> Result<Client, ApiError> {
+        self.call(Method::Patch, self.workspace_url(&format!("/clients/{id}"), &[]), merge_patch(req)?).await
+    }
+
+    pub async fn delete_client(&self, id: Uuid) -> Result<(), ApiError> {
+        self.call(Method::Delete, self.workspace_url(&format!("/clients/{id}"), &[]), None).await
+    }
+
+    // Projects
+    pub async fn get_projects(&self) -> Result<Vec<Project>, ApiError> {
+        self.call(Method::Get, self.workspace_url("/projects", &[]), None).await
+    }
+
+    pub async fn get_project(&self, id: Uuid) -> Result<Project, ApiError> {
+        self.call(Method::Get, self.workspace_url(&format!("/projects/{id}"), &[]), None).await
+    }
+
+    pub async fn create_project(&self, req: &CreateProjectRequest) -> Result<Project, ApiError> {
+        self.call(Method::Post, self.workspace_url("/projects", &[]), json(req)?).await
+    }
+
+    pub async fn replace_project(&self, id: Uuid, req: &CreateProjectRequest) -> Result<Project, ApiError> {
+        self.call(Method::Put, self.workspace_url(&format!("/projects/{id}"), &[]), json(req)?).await
+    }
+
+    pub async fn update_project(&self, id: Uuid, req: &UpdateProjectRequest) -> Result<Project, ApiError> {
+        self.call(Method::Patch, self.workspace_url(&format!("/projects/{id}"), &[]), merge_patch(req)?).await
+    }
+
+    pub async fn delete_project(&self, id: Uuid) -> Result<(), ApiError> {
+        self.call(Method::Delete, self.workspace_url(&format!("/projects/{id}"), &[]), None).await
+    }
+
+    // Reports
+    pub async fn get_report(&self, query: &ReportQuery) -> Result<Report, ApiError> {
+        self.call(Method::Get, self.workspace_url("/reports", &query.to_query_pairs()), None).await
+    }
+
+    // Invoices
+    pub async fn get_invoices(&self) -> Result<Vec<Invoice>, ApiError> {
+        self.call(Method::Get, self.workspace_url("/invoices", &[]), None).await
+    }
+
+    pub async fn get_invoice(&self, id: Uuid) -> Result<Invoice, ApiError> {
+        self.call(Method::Get, self.workspace_url(&format!("/invoices/{id}"), &[]), None).await
+    }
+
+    pub async fn create_invoice(&self, req: &CreateInvoiceRequest) -> Result<Invoice, ApiError> {
+        self.call(Method::Post, self.workspace_url("/invoices", &[]), json(req)?).await
+    }
+
+    pub async fn delete_invoice(&self, id: Uuid) -> Result<(), ApiError> {
+        self.call(Method::Delete, self.workspace_url(&format!("/invoices/{id}"), &[]), None).await
+    }
+
+    /// URL of an invoice rendered as `html` or `pdf`, for opening in the browser.
+    pub fn invoice_url(&self, id: Uuid, format: &str) -> String {
+        self.workspace_url(&format!("/invoices/{id}/{format}"), &[])
+    }
+
+    pub async fn get_invoice_html(&self, id: Uuid) -> Result<String, ApiError> {
+        let html = self.fetch(self.invoice_url(id, "html")).await?;
+        String::from_utf8(html).map_err(|e| ApiError::Network(format!("Invoice is not valid UTF-8: {e}")))
+    }
+
+    pub async fn get_invoice_pdf(&self, id: Uuid) -> Result<Vec<u8>, ApiError> {
+        self.fetch(self.invoice_url(id, "pdf")).await
+    }
+
+    // Webhooks
+    pub async fn get_webhooks(&self) -> Result<Vec<Webhook>, ApiError> {
+        self.call(Method::Get, self.workspace_url("/webhooks", &[]), None).await
+    }
+
+    pub async fn create_webhook(&self, req: &CreateWebhookRequest) -> Result<CreatedWebhook, ApiError> {
+        self.call(Method::Post, self.workspace_url("/webhooks", &[]), json(req)?).await
+    }
+
+    pub async fn delete_webhook(&self, id: Uuid) -> Result<(), ApiError> {
+        self.call(Method::Delete, self.workspace_url(&format!("/webhooks/{id}"), &[]), None).await
+    }
+
+    pub async fn get_webhook_deliveries(&self, id: Uuid) -> Result<Vec<WebhookDelivery>, ApiError> {
+        self.call(Method::Get, self.workspace_url(&format!("/webhooks/{id}/deliveries"), &[]), None).await
+    }
+
+    pub async fn ping_webhook(&self, id: Uuid) -> Result<WebhookDelivery, ApiError> {
+        self.call(Method::Post, self.workspace_url(&format!("/webhooks/{id}/ping"), &[]), None).await
+    }
+
+    // Live updates
+
+    /// URL of the Server-Sent Events stream of the workspace's changes, each
+    /// a `change` event carrying a `LiveEvent`.
+    pub fn events_url(&self) -> String {
+        self.workspace_url("/events", &[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::cell::RefCell;
+
+    /// Answers every request with `response`, keeping the requests sent.
+    struct FakeTransport {
+        response: Response,
+        sent: RefCell<Vec<Request>>,
+    }
+
+    impl Transport for FakeTransport {
+        async fn send(&self, request: Request) -> Result<Response, ApiError> {
+            self.sent.borrow_mut().push(request);
+            Ok(self.response.clone())
+        }
+    }
+
+    fn client(status: u16, body: impl Into<Vec<u8>>) -> ApiClient<FakeTransport> {
+        let transport = FakeTransport {
+            response: Response { status, body: body.into() },
+            sent: RefCell::new(Vec::new()),
+        };
+        ApiClient::new("http://localhost:8080/", transport)
+    }
+
+    fn json_client(status: u16, body: serde_json::Value) -> ApiClient<FakeTransport> {
+        client(status, body.to_string())
+    }
+
+    #[test]
+    fn query_values_are_percent_encoded() {
+        assert_eq!(encode("Az09-_.~"), "Az09-_.~");
+        assert_eq!(encode("2024-05-10T09:00:00+02:00"), "2024-05-10T09%3A00%3A00%2B02%3A00");
+        assert_eq!(encode("a&b=c d/é"), "a%26b%3Dc%20d%2F%C3%A9");
+    }
+
+    #[test]
+    fn workspace_urls_lead_with_the_chosen_workspace() {
+        let query = [("search", "a&b".to_string())];
+        let api = client(200, "");
+        assert_eq!(api.workspace_url("/sessions", &query), "http://localhost:8080/api/sessions?search=a%26b");
+        assert_eq!(api.workspace_url("/tags", &[]), "http://localhost:8080/api/tags");
+
+        let id = Uuid::new_v4();
+        let api = api.with_workspace(Some(id));
+        assert_eq!(
+            api.workspace_url("/sessions", &query),
+            format!("http://localhost:8080/api/sessions?workspace_id={id}&search=a%26b")
+        );
+        assert_eq!(api.workspace_url("/tags", &[]), format!("http://localhost:8080/api/tags?workspace_id={id}"));
+        // Account routes are not scoped
+        assert_eq!(api.url("/auth/me", &[]), "http://localhost:8080/api/auth/me");
+    }
+
+    #[tokio::test]
+    async fn requests_are_sent_to_the_workspace_and_unwrapped() {
+        let id = Uuid::new_v4();
+        let api = client(200, json!({ "success": true, "data": null }).to_string()).with_workspace(Some(id));
+        api.delete_tag(id).await.unwrap();
+
+        let sent = api.transport.sent.borrow();
+        assert_eq!(
+            sent[..],
+            [Request {
+                method: Method::Delete,
+                url: format!("http://localhost:8080/api/tags/{id}?workspace_id={id}"),
+                body: None,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn error_envelopes_map_to_their_codes() {
+        let existing = Uuid::new_v4();
+        let api = json_client(
+            409,
+            json!({ "success": false, "error_code": "conflict", "message": "Tag exists", "existing_id": existing }),
+        );
+        assert_eq!(
+            api.get_tags().await.unwrap_err(),
+            ApiError::Conflict { message: "Tag exists".to_string(), existing_id: Some(existing) }
+        );
+
+        let api = json_client(
+            400,
+            json!({ "success": false, "error_code": "validation", "message": "Invalid", "field_errors": { "name": ["is required"] } }),
+        );
+        let err = api.get_tags().await.unwrap_err();
+        assert_eq!(err.field_errors().and_then(|f| f.get("name")), Some("is required"));
+
+        let api = json_client(403, json!({ "success": false, "error_code": "forbidden", "message": "Viewers cannot make changes" }));
+        assert_eq!(api.get_tags().await.unwrap_err(), ApiError::Forbidden("Viewers cannot make changes".to_string()));
+
+        let api = json_client(500, json!({ "success": false, "message": "Internal server error" }));
+        assert_eq!(api.get_tags().await.unwrap_err(), ApiError::Server("Internal server error".to_string()));
+    }
+
+    #[tokio::test]
+    async fn failures_without_an_envelope_map_by_status() {
+        let cases = [
+            (
+                413,
+                "Failed to buffer the request body: length limit exceeded",
+                ApiError::Validation {
+                    message: "Failed to buffer the request body: length limit exceeded (HTTP 413)".to_string(),
+                    fields: ValidationErrors::default(),
+                },
+            ),
+            (
+                405,
+                "",
+                ApiError::Validation { message: "Server answered HTTP 405".to_string(), fields: ValidationErrors::default() },
+            ),
+            (502, "<html><body>Bad Gateway</body></html>", ApiError::Server("Server answered HTTP 502".to_string())),
+            (401, "Unauthorized", ApiError::Unauthorized("Unauthorized (HTTP 401)".to_string())),
+            (404, "", ApiError::NotFound("Server answered HTTP 404".to_string())),
+        ];
+        for (status, body, expected) in cases {
+            assert_eq!(client(status, body).get_tags().await.unwrap_err(), expected, "{status}");
+            // Documents are fetched the same way
+            assert_eq!(client(status, body).get_invoice_pdf(Uuid::new_v4()).await.unwrap_err(), expected, "{status}");
+        }
+
+        // A success that is not an envelope is still unreadable
+        let err = client(200, "not json").get_tags().await.unwrap_err();
+        assert!(matches!(err, ApiError::Network(message) if message.starts_with("Failed to parse API response")));
+        assert_eq!(client(200, "%PDF").get_invoice_pdf(Uuid::new_v4()).await.unwrap(), b"%PDF");
+    }
+}
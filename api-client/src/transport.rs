@@ -0,0 +1,49 @@
+use crate::ApiError;
+
+#[cfg(feature = "gloo")]
+mod gloo;
+#[cfg(feature = "reqwest")]
+mod reqwest;
+
+#[cfg(feature = "gloo")]
+pub use self::gloo::GlooTransport;
+#[cfg(feature = "reqwest")]
+pub use self::reqwest::ReqwestTransport;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+/// An HTTP request built by [`ApiClient`](crate::ApiClient).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub method: Method,
+    /// Absolute or root-relative URL, including the query string
+    pub url: String,
+    /// Body with its content type
+    pub body: Option<(&'static str, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// Sends requests for an [`ApiClient`](crate::ApiClient) and takes care of
+/// authentication. Failures to get any response are `ApiError::Network`.
+#[allow(async_fn_in_trait)]
+pub trait Transport {
+    async fn send(&self, request: Request) -> Result<Response, ApiError>;
+}
@@ -0,0 +1,43 @@
+use gloo_net::http::RequestBuilder;
+use web_sys::RequestCredentials;
+
+use super::{Method, Request, Response, Transport};
+use crate::ApiError;
+
+/// Sends requests with `fetch`, including the browser's login cookie.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GlooTransport;
+
+impl From<gloo_net::Error> for ApiError {
+    fn from(e: gloo_net::Error) -> Self {
+        ApiError::Network(format!("Request failed: {e}"))
+    }
+}
+
+impl Transport for GlooTransport {
+    async fn send(&self, request: Request) -> Result<Response, ApiError> {
+        let method = match request.method {
+            Method::Get => gloo_net::http::Method::GET,
+            Method::Post => gloo_net::http::Method::POST,
+            Method::Put => gloo_net::http::Method::PUT,
+            Method::Patch => gloo_net::http::Method::PATCH,
+            Method::Delete => gloo_net::http::Method::DELETE,
+        };
+        let builder = RequestBuilder::new(&request.url)
+            .method(method)
+            .credentials(RequestCredentials::Include);
+        let request = match request.body {
+            Some((content_type, body)) => builder.header("Content-Type", content_type).body(body)?,
+            None => builder.build()?,
+        };
+
+        let response = request.send().await?;
+        let status = response.status();
+        let body = response
+            .binary()
+            .await
+            .map_err(|e| ApiError::Network(format!("Failed to get response body: {e}")))?;
+
+        Ok(Response { status, body })
+    }
+}
@@ -0,0 +1,46 @@
+use super::{Method, Request, Response, Transport};
+use crate::ApiError;
+
+/// Sends requests with `reqwest`, authenticated with a personal API token.
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+    token: String,
+}
+
+impl ReqwestTransport {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            token: token.into(),
+        }
+    }
+}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(e: reqwest::Error) -> Self {
+        ApiError::Network(format!("Request failed: {e}"))
+    }
+}
+
+impl Transport for ReqwestTransport {
+    async fn send(&self, request: Request) -> Result<Response, ApiError> {
+        let method = match request.method {
+            Method::Get => reqwest::Method::GET,
+            Method::Post => reqwest::Method::POST,
+            Method::Put => reqwest::Method::PUT,
+            Method::Patch => reqwest::Method::PATCH,
+            Method::Delete => reqwest::Method::DELETE,
+        };
+        let mut builder = self.client.request(method, &request.url).bearer_auth(&self.token);
+        if let Some((content_type, body)) = request.body {
+            builder = builder.header(reqwest::header::CONTENT_TYPE, content_type).body(body);
+        }
+
+        let response = builder.send().await?;
+        let status = response.status().as_u16();
+        let body = response.bytes().await?.to_vec();
+
+        Ok(Response { status, body })
+    }
+}
@@ -0,0 +1,74 @@
+//! A local stand-in for a webhook endpoint. It checks each delivery's
+//! signature and prints it.
+//!
+//! ```sh
+//! WEBHOOK_SECRET=whsec_... cargo run -p work-session-tracker-backend --example webhook_receiver
+//! ```
+//!
+//! Listens on `PORT` (default 9000). Set `FAIL_WITH=500` to answer every
+//! delivery with that status and watch the retries.
+
+use axum::{body::Bytes, http::HeaderMap, http::StatusCode, routing::post, Router};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let secret = std::env::var("WEBHOOK_SECRET").map_err(|_| anyhow::anyhow!("WEBHOOK_SECRET is not set"))?;
+    let fail_with = match std::env::var("FAIL_WITH") {
+        Ok(code) => Some(StatusCode::from_bytes(code.as_bytes())?),
+        Err(_) => None,
+    };
+    let port = std::env::var("PORT").unwrap_or_else(|_| "9000".to_string());
+
+    let app = Router::new().route(
+        "/",
+        post(move |headers: HeaderMap, body: Bytes| async move { receive(&secret, fail_with, &headers, &body) }),
+    );
+    let listener = tokio::net::TcpListener::bind(format!("127.0.0.1:{port}")).await?;
+    println!("Listening on http://127.0.0.1:{port}/");
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
+
+fn receive(secret: &str, fail_with: Option<StatusCode>, headers: &HeaderMap, body: &[u8]) -> StatusCode {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok()).unwrap_or("-");
+    let verified = verify(secret, header("x-wst-signature"), body);
+
+    println!(
+        "{} {} ({})\n{}\n",
+        header("x-wst-event"),
+        header("x-wst-delivery"),
+        if verified { "signature ok" } else { "BAD SIGNATURE" },
+        String::from_utf8_lossy(body)
+    );
+
+    if !verified {
+        StatusCode::UNAUTHORIZED
+    } else {
+        fail_with.unwrap_or(StatusCode::NO_CONTENT)
+    }
+}
+
+/// Checks a `t=<unix time>,v1=<hex HMAC-SHA256 of "{t}.{body}">` signature.
+fn verify(secret: &str, signature: &str, body: &[u8]) -> bool {
+    let mut timestamp = None;
+    let mut expected = None;
+    for part in signature.split(',') {
+        match part.split_once('=') {
+            Some(("t", value)) => timestamp = Some(value),
+            Some(("v1", value)) => expected = hex::decode(value).ok(),
+            _ => {}
+        }
+    }
+    let (Some(timestamp), Some(expected)) = (timestamp, expected) else {
+        return false;
+    };
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
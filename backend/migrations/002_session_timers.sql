@@ -0,0 +1,7 @@
+-- Wall-clock bounds for timed sessions; a started session without an end is running
+ALTER TABLE work_sessions ADD COLUMN started_at TIMESTAMPTZ;
+ALTER TABLE work_sessions ADD COLUMN ended_at TIMESTAMPTZ;
+
+-- Only one session may be running at a time
+CREATE UNIQUE INDEX idx_work_sessions_running ON work_sessions ((TRUE))
+    WHERE started_at IS NOT NULL AND ended_at IS NULL;
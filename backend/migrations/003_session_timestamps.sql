@@ -0,0 +1,13 @@
+-- Backfill sessions recorded before start/end times were tracked: treat the
+-- row's insertion time as the end of the work and count back by its duration
+UPDATE work_sessions
+SET started_at = created_at - make_interval(secs => duration_seconds),
+    ended_at = created_at
+WHERE started_at IS NULL;
+
+-- Every session now has a start; a missing end means it is still running
+ALTER TABLE work_sessions ALTER COLUMN started_at SET NOT NULL;
+ALTER TABLE work_sessions ADD CONSTRAINT chk_work_sessions_bounds
+    CHECK (ended_at IS NULL OR ended_at >= started_at);
+
+CREATE INDEX idx_work_sessions_started_at ON work_sessions(started_at);
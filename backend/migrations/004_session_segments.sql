@@ -0,0 +1,22 @@
+-- Periods of actual work within a session, split by pauses
+CREATE TABLE session_segments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    session_id UUID NOT NULL REFERENCES work_sessions(id) ON DELETE CASCADE,
+    started_at TIMESTAMPTZ NOT NULL,
+    ended_at TIMESTAMPTZ, -- NULL while the segment is in progress
+    CHECK (ended_at IS NULL OR ended_at >= started_at)
+);
+
+CREATE INDEX idx_session_segments_session_id ON session_segments(session_id);
+
+-- A session has at most one segment in progress
+CREATE UNIQUE INDEX idx_session_segments_open ON session_segments(session_id)
+    WHERE ended_at IS NULL;
+
+-- Existing running sessions and sessions that were never interrupted consist
+-- of a single segment spanning the whole session
+INSERT INTO session_segments (session_id, started_at, ended_at)
+SELECT id, started_at, ended_at
+FROM work_sessions
+WHERE ended_at IS NULL
+   OR duration_seconds = EXTRACT(EPOCH FROM (ended_at - started_at))::INTEGER;
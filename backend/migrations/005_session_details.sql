@@ -0,0 +1,35 @@
+-- Sessions together with their tags and segments, so a listing of any size
+-- is loaded in a single query
+CREATE VIEW session_details AS
+SELECT
+    s.id,
+    s.duration_seconds,
+    s.description,
+    s.started_at,
+    s.ended_at,
+    s.created_at,
+    s.updated_at,
+    COALESCE(
+        (SELECT json_agg(json_build_object(
+                    'id', t.id,
+                    'name', t.name,
+                    'color', t.color,
+                    'created_at', t.created_at
+                ) ORDER BY t.name)
+         FROM session_tags st
+         JOIN tags t ON t.id = st.tag_id
+         WHERE st.session_id = s.id),
+        '[]'::json
+    ) AS tags,
+    COALESCE(
+        (SELECT json_agg(json_build_object(
+                    'id', seg.id,
+                    'session_id', seg.session_id,
+                    'started_at', seg.started_at,
+                    'ended_at', seg.ended_at
+                ) ORDER BY seg.started_at)
+         FROM session_segments seg
+         WHERE seg.session_id = s.id),
+        '[]'::json
+    ) AS segments
+FROM work_sessions s;
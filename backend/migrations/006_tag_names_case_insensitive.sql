@@ -0,0 +1,23 @@
+-- Tag names are unique regardless of case. Tags that already differ only by
+-- case are merged into the oldest of them.
+CREATE TEMPORARY TABLE duplicate_tags AS
+SELECT id, keep_id
+FROM (
+    SELECT id, first_value(id) OVER (PARTITION BY lower(name) ORDER BY created_at, id) AS keep_id
+    FROM tags
+) ranked
+WHERE id <> keep_id;
+
+INSERT INTO session_tags (session_id, tag_id)
+SELECT st.session_id, d.keep_id
+FROM session_tags st
+JOIN duplicate_tags d ON d.id = st.tag_id
+ON CONFLICT DO NOTHING;
+
+DELETE FROM tags WHERE id IN (SELECT id FROM duplicate_tags);
+
+DROP TABLE duplicate_tags;
+
+ALTER TABLE tags DROP CONSTRAINT tags_name_key;
+DROP INDEX idx_tags_name;
+CREATE UNIQUE INDEX idx_tags_name_lower ON tags (lower(name));
@@ -0,0 +1,69 @@
+-- User accounts; emails are unique regardless of case
+CREATE TABLE users (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    email VARCHAR(255) NOT NULL,
+    password_hash TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_users_email_lower ON users (lower(email));
+
+-- Logged-in browser sessions. Only a hash of the cookie token is stored.
+CREATE TABLE login_sessions (
+    token_hash BYTEA PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_login_sessions_user_id ON login_sessions(user_id);
+
+-- Sessions and tags belong to a user. Rows recorded before accounts existed
+-- have no owner until the first user registers and takes them over.
+ALTER TABLE work_sessions ADD COLUMN user_id UUID REFERENCES users(id) ON DELETE CASCADE;
+ALTER TABLE tags ADD COLUMN user_id UUID REFERENCES users(id) ON DELETE CASCADE;
+
+CREATE INDEX idx_work_sessions_user_id ON work_sessions(user_id);
+
+-- Tag names and the running session are per user
+DROP INDEX idx_tags_name_lower;
+CREATE UNIQUE INDEX idx_tags_user_name_lower ON tags (user_id, lower(name));
+
+DROP INDEX idx_work_sessions_running;
+CREATE UNIQUE INDEX idx_work_sessions_running ON work_sessions (user_id)
+    WHERE ended_at IS NULL;
+
+CREATE OR REPLACE VIEW session_details AS
+SELECT
+    s.id,
+    s.duration_seconds,
+    s.description,
+    s.started_at,
+    s.ended_at,
+    s.created_at,
+    s.updated_at,
+    COALESCE(
+        (SELECT json_agg(json_build_object(
+                    'id', t.id,
+                    'name', t.name,
+                    'color', t.color,
+                    'created_at', t.created_at
+                ) ORDER BY t.name)
+         FROM session_tags st
+         JOIN tags t ON t.id = st.tag_id
+         WHERE st.session_id = s.id),
+        '[]'::json
+    ) AS tags,
+    COALESCE(
+        (SELECT json_agg(json_build_object(
+                    'id', seg.id,
+                    'session_id', seg.session_id,
+                    'started_at', seg.started_at,
+                    'ended_at', seg.ended_at
+                ) ORDER BY seg.started_at)
+         FROM session_segments seg
+         WHERE seg.session_id = s.id),
+        '[]'::json
+    ) AS segments,
+    s.user_id
+FROM work_sessions s;
@@ -0,0 +1,80 @@
+-- Workspaces own sessions and tags and are shared by their members
+CREATE TABLE workspaces (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE workspace_members (
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role TEXT NOT NULL CHECK (role IN ('owner', 'admin', 'member', 'viewer')),
+    joined_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (workspace_id, user_id)
+);
+
+CREATE INDEX idx_workspace_members_user_id ON workspace_members(user_id);
+
+ALTER TABLE work_sessions ADD COLUMN workspace_id UUID REFERENCES workspaces(id) ON DELETE CASCADE;
+ALTER TABLE tags ADD COLUMN workspace_id UUID REFERENCES workspaces(id) ON DELETE CASCADE;
+
+CREATE INDEX idx_work_sessions_workspace_id ON work_sessions(workspace_id);
+
+-- Every existing user gets a personal workspace holding their data
+CREATE TEMPORARY TABLE personal_workspaces AS
+SELECT id AS user_id, gen_random_uuid() AS workspace_id, created_at FROM users;
+
+INSERT INTO workspaces (id, name, created_at)
+SELECT workspace_id, 'Personal', created_at FROM personal_workspaces;
+
+INSERT INTO workspace_members (workspace_id, user_id, role, joined_at)
+SELECT workspace_id, user_id, 'owner', created_at FROM personal_workspaces;
+
+UPDATE work_sessions s SET workspace_id = p.workspace_id
+FROM personal_workspaces p WHERE p.user_id = s.user_id;
+
+UPDATE tags t SET workspace_id = p.workspace_id
+FROM personal_workspaces p WHERE p.user_id = t.user_id;
+
+DROP TABLE personal_workspaces;
+
+-- Tag names are unique within a workspace. `user_id` on tags only records
+-- who created the tag.
+DROP INDEX idx_tags_user_name_lower;
+CREATE UNIQUE INDEX idx_tags_workspace_name_lower ON tags (workspace_id, lower(name));
+
+CREATE OR REPLACE VIEW session_details AS
+SELECT
+    s.id,
+    s.duration_seconds,
+    s.description,
+    s.started_at,
+    s.ended_at,
+    s.created_at,
+    s.updated_at,
+    COALESCE(
+        (SELECT json_agg(json_build_object(
+                    'id', t.id,
+                    'name', t.name,
+                    'color', t.color,
+                    'created_at', t.created_at
+                ) ORDER BY t.name)
+         FROM session_tags st
+         JOIN tags t ON t.id = st.tag_id
+         WHERE st.session_id = s.id),
+        '[]'::json
+    ) AS tags,
+    COALESCE(
+        (SELECT json_agg(json_build_object(
+                    'id', seg.id,
+                    'session_id', seg.session_id,
+                    'started_at', seg.started_at,
+                    'ended_at', seg.ended_at
+                ) ORDER BY seg.started_at)
+         FROM session_segments seg
+         WHERE seg.session_id = s.id),
+        '[]'::json
+    ) AS segments,
+    s.user_id,
+    s.workspace_id
+FROM work_sessions s;
@@ -0,0 +1,61 @@
+-- Clients and their projects, which sessions can be booked against
+CREATE TABLE clients (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_clients_workspace_name_lower ON clients (workspace_id, lower(name));
+
+CREATE TABLE projects (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    client_id UUID NOT NULL REFERENCES clients(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    hourly_rate NUMERIC(12, 2) CHECK (hourly_rate >= 0),
+    budget_seconds INTEGER CHECK (budget_seconds >= 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_projects_client_name_lower ON projects (client_id, lower(name));
+
+ALTER TABLE work_sessions ADD COLUMN project_id UUID REFERENCES projects(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_work_sessions_project_id ON work_sessions(project_id);
+
+CREATE OR REPLACE VIEW session_details AS
+SELECT
+    s.id,
+    s.duration_seconds,
+    s.description,
+    s.started_at,
+    s.ended_at,
+    s.created_at,
+    s.updated_at,
+    COALESCE(
+        (SELECT json_agg(json_build_object(
+                    'id', t.id,
+                    'name', t.name,
+                    'color', t.color,
+                    'created_at', t.created_at
+                ) ORDER BY t.name)
+         FROM session_tags st
+         JOIN tags t ON t.id = st.tag_id
+         WHERE st.session_id = s.id),
+        '[]'::json
+    ) AS tags,
+    COALESCE(
+        (SELECT json_agg(json_build_object(
+                    'id', seg.id,
+                    'session_id', seg.session_id,
+                    'started_at', seg.started_at,
+                    'ended_at', seg.ended_at
+                ) ORDER BY seg.started_at)
+         FROM session_segments seg
+         WHERE seg.session_id = s.id),
+        '[]'::json
+    ) AS segments,
+    s.user_id,
+    s.workspace_id,
+    s.project_id
+FROM work_sessions s;
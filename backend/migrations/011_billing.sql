@@ -0,0 +1,73 @@
+-- Billable sessions, hourly rates and the currency amounts are shown in
+ALTER TABLE workspaces ADD COLUMN currency VARCHAR(3) NOT NULL DEFAULT 'USD'
+    CHECK (currency ~ '^[A-Z]{3}$');
+
+ALTER TABLE tags ADD COLUMN hourly_rate NUMERIC(12, 2) CHECK (hourly_rate >= 0);
+
+ALTER TABLE work_sessions ADD COLUMN billable BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE work_sessions ADD COLUMN hourly_rate NUMERIC(12, 2) CHECK (hourly_rate >= 0);
+
+-- A session's rate is its own, else its project's, else its highest tag
+-- rate. Only billable sessions earn an amount.
+CREATE VIEW session_billing AS
+SELECT
+    s.id AS session_id,
+    r.hourly_rate,
+    CASE WHEN s.billable THEN ROUND(r.hourly_rate * s.duration_seconds / 3600, 2) END AS amount
+FROM work_sessions s
+LEFT JOIN projects p ON p.id = s.project_id
+CROSS JOIN LATERAL (
+    SELECT COALESCE(
+        s.hourly_rate,
+        p.hourly_rate,
+        (SELECT MAX(t.hourly_rate)
+         FROM session_tags st
+         JOIN tags t ON t.id = st.tag_id
+         WHERE st.session_id = s.id)
+    ) AS hourly_rate
+) r;
+
+CREATE OR REPLACE VIEW session_details AS
+SELECT
+    s.id,
+    s.duration_seconds,
+    s.description,
+    s.started_at,
+    s.ended_at,
+    s.created_at,
+    s.updated_at,
+    COALESCE(
+        (SELECT json_agg(json_build_object(
+                    'id', t.id,
+                    'name', t.name,
+                    'color', t.color,
+                    'hourly_rate', t.hourly_rate::text,
+                    'created_at', t.created_at
+                ) ORDER BY t.name)
+         FROM session_tags st
+         JOIN tags t ON t.id = st.tag_id
+         WHERE st.session_id = s.id),
+        '[]'::json
+    ) AS tags,
+    COALESCE(
+        (SELECT json_agg(json_build_object(
+                    'id', seg.id,
+                    'session_id', seg.session_id,
+                    'started_at', seg.started_at,
+                    'ended_at', seg.ended_at
+                ) ORDER BY seg.started_at)
+         FROM session_segments seg
+         WHERE seg.session_id = s.id),
+        '[]'::json
+    ) AS segments,
+    s.user_id,
+    s.workspace_id,
+    s.project_id,
+    s.billable,
+    s.hourly_rate,
+    b.hourly_rate AS effective_rate,
+    b.amount,
+    w.currency
+FROM work_sessions s
+JOIN session_billing b ON b.session_id = s.id
+JOIN workspaces w ON w.id = s.workspace_id;
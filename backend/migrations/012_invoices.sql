@@ -0,0 +1,35 @@
+-- Invoices snapshot billable sessions into line items
+CREATE TABLE invoices (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    workspace_id UUID NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    number INTEGER NOT NULL,
+    client_id UUID REFERENCES clients(id) ON DELETE SET NULL,
+    client_name VARCHAR(100),
+    tag_id UUID REFERENCES tags(id) ON DELETE SET NULL,
+    from_date DATE NOT NULL,
+    to_date DATE NOT NULL,
+    group_by TEXT NOT NULL CHECK (group_by IN ('tag', 'day')),
+    currency VARCHAR(3) NOT NULL,
+    total NUMERIC(14, 2) NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_invoices_workspace_number ON invoices (workspace_id, number);
+
+CREATE TABLE invoice_lines (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    invoice_id UUID NOT NULL REFERENCES invoices(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    description TEXT NOT NULL,
+    seconds BIGINT NOT NULL,
+    session_count BIGINT NOT NULL,
+    amount NUMERIC(14, 2) NOT NULL
+);
+
+CREATE INDEX idx_invoice_lines_invoice_id ON invoice_lines(invoice_id);
+
+-- Invoiced sessions are locked until their invoice is deleted
+ALTER TABLE work_sessions ADD COLUMN invoice_id UUID REFERENCES invoices(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_work_sessions_invoice_id ON work_sessions(invoice_id);
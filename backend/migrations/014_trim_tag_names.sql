@@ -0,0 +1,28 @@
+-- Tag names are stored trimmed, so names that differ only by surrounding
+-- spaces clash. Tags that already do are merged into the oldest of them.
+CREATE TEMPORARY TABLE duplicate_tags AS
+SELECT id, keep_id
+FROM (
+    SELECT id, first_value(id) OVER (PARTITION BY workspace_id, lower(btrim(name)) ORDER BY created_at, id) AS keep_id
+    FROM tags
+    WHERE workspace_id IS NOT NULL
+) ranked
+WHERE id <> keep_id;
+
+INSERT INTO session_tags (session_id, tag_id)
+SELECT st.session_id, d.keep_id
+FROM session_tags st
+JOIN duplicate_tags d ON d.id = st.tag_id
+ON CONFLICT DO NOTHING;
+
+UPDATE invoices i SET tag_id = d.keep_id
+FROM duplicate_tags d WHERE d.id = i.tag_id;
+
+DELETE FROM tags WHERE id IN (SELECT id FROM duplicate_tags);
+
+DROP TABLE duplicate_tags;
+
+UPDATE tags SET name = btrim(name) WHERE name <> btrim(name);
+
+DROP INDEX idx_tags_workspace_name_lower;
+CREATE UNIQUE INDEX idx_tags_workspace_name_lower ON tags (workspace_id, lower(btrim(name)));
@@ -0,0 +1,30 @@
+-- Client and project names are stored trimmed, like tag names. Names that
+-- clash once trimmed keep their rows, the newer ones renamed with the start
+-- of their id, as merging clients or projects would mix their bookings.
+UPDATE clients c
+SET name = left(btrim(c.name), 89) || ' (' || left(c.id::text, 8) || ')'
+WHERE EXISTS (
+    SELECT 1 FROM clients o
+    WHERE o.workspace_id = c.workspace_id
+      AND lower(btrim(o.name)) = lower(btrim(c.name))
+      AND (o.created_at, o.id) < (c.created_at, c.id)
+);
+
+UPDATE clients SET name = btrim(name) WHERE name <> btrim(name);
+
+UPDATE projects p
+SET name = left(btrim(p.name), 89) || ' (' || left(p.id::text, 8) || ')'
+WHERE EXISTS (
+    SELECT 1 FROM projects o
+    WHERE o.client_id = p.client_id
+      AND lower(btrim(o.name)) = lower(btrim(p.name))
+      AND (o.created_at, o.id) < (p.created_at, p.id)
+);
+
+UPDATE projects SET name = btrim(name) WHERE name <> btrim(name);
+
+DROP INDEX idx_clients_workspace_name_lower;
+CREATE UNIQUE INDEX idx_clients_workspace_name_lower ON clients (workspace_id, lower(btrim(name)));
+
+DROP INDEX idx_projects_client_name_lower;
+CREATE UNIQUE INDEX idx_projects_client_name_lower ON projects (client_id, lower(btrim(name)));
@@ -0,0 +1,212 @@
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::{header, request::Parts, Method},
+    middleware::Next,
+    response::Response,
+};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use chrono::Duration;
+use rand::{distributions::Alphanumeric, rngs::OsRng, Rng};
+use sha2::{Digest, Sha256};
+use shared::{User, Workspace, WorkspaceParam, WorkspaceRole};
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::extract::Query;
+use crate::AppState;
+
+pub const LOGIN_COOKIE: &str = "wst_login";
+/// Prefix of personal access tokens, so they are easy to spot in config files
+pub const API_TOKEN_PREFIX: &str = "wst_";
+
+/// How long a login lasts before the user has to log in again.
+pub fn login_lifetime() -> Duration {
+    Duration::days(30)
+}
+
+pub fn hash_password(password: &str) -> anyhow::Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|e| anyhow::anyhow!("Failed to hash password: {e}"))?;
+    Ok(hash.to_string())
+}
+
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    PasswordHash::new(password_hash)
+        .map(|hash| Argon2::default().verify_password(password.as_bytes(), &hash).is_ok())
+        .unwrap_or(false)
+}
+
+/// A random secret handed to the client. Only its hash is stored.
+pub fn generate_token() -> String {
+    OsRng
+        .sample_iter(&Alphanumeric)
+        .take(43)
+        .map(char::from)
+        .collect()
+}
+
+pub fn generate_api_token() -> String {
+    format!("{API_TOKEN_PREFIX}{}", generate_token())
+}
+
+pub fn hash_token(token: &str) -> Vec<u8> {
+    Sha256::digest(token.as_bytes()).to_vec()
+}
+
+pub fn login_cookie(token: String, secure: bool) -> Cookie<'static> {
+    Cookie::build((LOGIN_COOKIE, token))
+        .path("/")
+        .http_only(true)
+        .secure(secure)
+        .same_site(SameSite::Lax)
+        .max_age(time::Duration::seconds(login_lifetime().num_seconds()))
+        .build()
+}
+
+/// How the caller proved who they are, recorded next to `CurrentUser`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Credential {
+    LoginCookie,
+    ApiToken,
+}
+
+/// Resolves the caller from an `Authorization: Bearer` API token or the
+/// login cookie, for the `CurrentUser` extractor. A bad token is rejected
+/// outright, as are writes made with a read-only token.
+pub async fn authenticate(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let user = if let Some(value) = request.headers().get(header::AUTHORIZATION) {
+        let token = value
+            .to_str()
+            .ok()
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or(ApiError::Unauthorized("Expected an Authorization: Bearer token"))?;
+        let (user, read_only) = state
+            .db
+            .get_token_user(&hash_token(token.trim()))
+            .await?
+            .ok_or(ApiError::Unauthorized("Invalid API token"))?;
+
+        if read_only && !matches!(*request.method(), Method::GET | Method::HEAD) {
+            return Err(ApiError::Forbidden("This API token is read-only"));
+        }
+        Some((user, Credential::ApiToken))
+    } else {
+        match CookieJar::from_headers(request.headers()).get(LOGIN_COOKIE) {
+            Some(cookie) => state
+                .db
+                .get_login_user(&hash_token(cookie.value()))
+                .await?
+                .map(|user| (user, Credential::LoginCookie)),
+            None => None,
+        }
+    };
+
+    if let Some((user, credential)) = user {
+        request.extensions_mut().insert(CurrentUser(user));
+        request.extensions_mut().insert(credential);
+    }
+    Ok(next.run(request).await)
+}
+
+/// The caller, as resolved by `authenticate`. Requests without a valid
+/// login or API token are rejected with 401.
+#[derive(Clone)]
+pub struct CurrentUser(pub User);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for CurrentUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(user) = parts.extensions.get::<CurrentUser>() {
+            return Ok(user.clone());
+        }
+
+        if CookieJar::from_headers(&parts.headers).get(LOGIN_COOKIE).is_some() {
+            Err(ApiError::Unauthorized("Login has expired"))
+        } else {
+            Err(ApiError::Unauthorized("Not logged in"))
+        }
+    }
+}
+
+/// The caller, provided they logged in with the login cookie. API tokens
+/// cannot create or revoke API tokens, so a leaked one cannot mint more.
+pub struct LoggedInUser(pub User);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for LoggedInUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let CurrentUser(user) = CurrentUser::from_request_parts(parts, state).await?;
+        match parts.extensions.get::<Credential>() {
+            Some(Credential::LoginCookie) => Ok(LoggedInUser(user)),
+            _ => Err(ApiError::Forbidden("API tokens can only be managed when logged in")),
+        }
+    }
+}
+
+/// The caller together with the workspace a request applies to, picked by
+/// the `workspace_id` query parameter.
+pub struct Membership {
+    pub user: User,
+    /// Carries the caller's role
+    pub workspace: Workspace,
+}
+
+impl Membership {
+    /// Looks up the caller's membership of `workspace_id`, or of their first
+    /// workspace. Workspaces they do not belong to are reported as missing.
+    pub async fn load(state: &AppState, user: User, workspace_id: Option<Uuid>) -> Result<Self, ApiError> {
+        let workspace = state
+            .db
+            .get_membership(user.id, workspace_id)
+            .await?
+            .ok_or(ApiError::NotFound("Workspace"))?;
+        Ok(Membership { user, workspace })
+    }
+
+    /// Rejects the request unless the caller has at least `role`.
+    pub fn require(&self, role: WorkspaceRole) -> Result<(), ApiError> {
+        if self.workspace.role >= role {
+            return Ok(());
+        }
+        Err(ApiError::Forbidden(match role {
+            WorkspaceRole::Viewer | WorkspaceRole::Member => "Viewers cannot make changes in this workspace",
+            WorkspaceRole::Admin => "Only workspace admins can do this",
+            WorkspaceRole::Owner => "Only workspace owners can do this",
+        }))
+    }
+
+    /// Members may change the sessions they tracked; admins may change any.
+    pub fn require_session_access(&self, owner: Uuid) -> Result<(), ApiError> {
+        if owner == self.user.id {
+            self.require(WorkspaceRole::Member)
+        } else {
+            self.require(WorkspaceRole::Admin)
+        }
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for Membership {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let CurrentUser(user) = CurrentUser::from_request_parts(parts, state).await?;
+        let Query(param) = Query::<WorkspaceParam>::from_request_parts(parts, state).await?;
+        Membership::load(state, user, param.workspace_id).await
+    }
+}
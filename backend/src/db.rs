@@ -1,39 +1,398 @@
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
 use shared::*;
-use sqlx::PgPool;
+use sqlx::{postgres::PgListener, types::Json, PgPool};
 use uuid::Uuid;
 
+/// Input rejected by the database layer, to be reported back to the client
+/// rather than treated as a server failure.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct InvalidInput(pub String);
+
+/// A write that clashes with an existing row, identified by `existing_id`
+/// when the client may know about it.
+#[derive(Debug, thiserror::Error)]
+#[error("{message}")]
+pub struct Conflict {
+    pub message: String,
+    pub existing_id: Option<Uuid>,
+}
+
 pub struct Database {
     pool: PgPool,
 }
 
+/// A row of the `session_details` view, which carries each session's tags
+/// and segments as JSON arrays.
+struct SessionDetailsRow {
+    id: Uuid,
+    duration_seconds: i32,
+    description: Option<String>,
+    started_at: DateTime<Utc>,
+    ended_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    user_id: Uuid,
+    project_id: Option<Uuid>,
+    billable: bool,
+    hourly_rate: Option<Decimal>,
+    effective_rate: Option<Decimal>,
+    amount: Option<Decimal>,
+    currency: String,
+    tags: Json<Vec<Tag>>,
+    segments: Json<Vec<SessionSegment>>,
+}
+
+impl From<SessionDetailsRow> for WorkSessionWithTags {
+    fn from(row: SessionDetailsRow) -> Self {
+        Self {
+            id: row.id,
+            duration_seconds: row.duration_seconds,
+            description: row.description,
+            started_at: row.started_at,
+            ended_at: row.ended_at,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            user_id: row.user_id,
+            project_id: row.project_id,
+            billable: row.billable,
+            hourly_rate: row.hourly_rate,
+            effective_rate: row.effective_rate,
+            amount: row.amount,
+            currency: row.currency,
+            tags: row.tags.0,
+            segments: row.segments.0,
+        }
+    }
+}
+
+/// A workspace joined with one member's role.
+struct WorkspaceRow {
+    id: Uuid,
+    name: String,
+    role: String,
+    currency: String,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<WorkspaceRow> for Workspace {
+    type Error = anyhow::Error;
+
+    fn try_from(row: WorkspaceRow) -> Result<Self> {
+        Ok(Self {
+            id: row.id,
+            name: row.name,
+            role: parse_role(&row.role)?,
+            currency: row.currency,
+            created_at: row.created_at,
+        })
+    }
+}
+
+struct MemberRow {
+    user_id: Uuid,
+    email: String,
+    role: String,
+    joined_at: DateTime<Utc>,
+}
+
+impl TryFrom<MemberRow> for WorkspaceMember {
+    type Error = anyhow::Error;
+
+    fn try_from(row: MemberRow) -> Result<Self> {
+        Ok(Self {
+            user_id: row.user_id,
+            email: row.email,
+            role: parse_role(&row.role)?,
+            joined_at: row.joined_at,
+        })
+    }
+}
+
+struct InvoiceRow {
+    id: Uuid,
+    number: i32,
+    client_id: Option<Uuid>,
+    client_name: Option<String>,
+    tag_id: Option<Uuid>,
+    from_date: NaiveDate,
+    to_date: NaiveDate,
+    group_by: String,
+    currency: String,
+    total: Decimal,
+    created_at: DateTime<Utc>,
+}
+
+struct WebhookRow {
+    id: Uuid,
+    url: String,
+    events: Vec<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<WebhookRow> for Webhook {
+    type Error = anyhow::Error;
+
+    fn try_from(row: WebhookRow) -> Result<Self> {
+        let events = row
+            .events
+            .iter()
+            .map(|event| WebhookEvent::parse(event).ok_or_else(|| anyhow::anyhow!("Unknown webhook event {event:?}")))
+            .collect::<Result<_>>()?;
+        Ok(Webhook {
+            id: row.id,
+            url: row.url,
+            events,
+            created_at: row.created_at,
+        })
+    }
+}
+
+struct DeliveryRow {
+    id: Uuid,
+    event: String,
+    status: String,
+    attempts: i32,
+    next_attempt_at: Option<DateTime<Utc>>,
+    response_status: Option<i32>,
+    last_error: Option<String>,
+    created_at: DateTime<Utc>,
+    delivered_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<DeliveryRow> for WebhookDelivery {
+    type Error = anyhow::Error;
+
+    fn try_from(row: DeliveryRow) -> Result<Self> {
+        Ok(WebhookDelivery {
+            id: row.id,
+            event: WebhookEvent::parse(&row.event)
+                .ok_or_else(|| anyhow::anyhow!("Unknown webhook event {:?}", row.event))?,
+            status: DeliveryStatus::parse(&row.status)
+                .ok_or_else(|| anyhow::anyhow!("Unknown delivery status {:?}", row.status))?,
+            attempts: row.attempts,
+            next_attempt_at: row.next_attempt_at,
+            response_status: row.response_status,
+            last_error: row.last_error,
+            created_at: row.created_at,
+            delivered_at: row.delivered_at,
+        })
+    }
+}
+
+/// A delivery claimed by the worker, with what it needs to send it.
+pub struct DueDelivery {
+    pub id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub event: String,
+    pub payload: serde_json::Value,
+    /// Attempts made before this one
+    pub attempts: i32,
+}
+
+impl InvoiceRow {
+    fn into_invoice(self, lines: Vec<InvoiceLine>) -> Result<Invoice> {
+        Ok(Invoice {
+            id: self.id,
+            number: self.number,
+            client_id: self.client_id,
+            client_name: self.client_name,
+            tag_id: self.tag_id,
+            from: self.from_date,
+            to: self.to_date,
+            group_by: InvoiceGrouping::parse(&self.group_by)
+                .ok_or_else(|| anyhow::anyhow!("Unknown invoice grouping {:?}", self.group_by))?,
+            currency: self.currency,
+            total: self.total,
+            created_at: self.created_at,
+            lines,
+        })
+    }
+}
+
+fn parse_role(role: &str) -> Result<WorkspaceRole> {
+    WorkspaceRole::parse(role).ok_or_else(|| anyhow::anyhow!("Unknown workspace role {role:?}"))
+}
+
 impl Database {
     pub fn new(pool: PgPool) -> Self {
         Self { pool }
     }
 
     // Session operations
-    pub async fn create_session(&self, req: CreateSessionRequest) -> Result<WorkSession> {
+    pub async fn create_session(&self, workspace_id: Uuid, user_id: Uuid, req: CreateSessionRequest) -> Result<WorkSession> {
+        let mut tx = self.pool.begin().await?;
+        let session = self.insert_session(&mut tx, workspace_id, user_id, req).await?;
+        tx.commit().await?;
+        Ok(session)
+    }
+
+    /// Imports sessions in a single transaction, creating any tags that do
+    /// not exist yet. Rows that fail validation are reported by their 1-based
+    /// position; if there are any, or this is a dry run, nothing is committed.
+    pub async fn import_sessions(
+        &self,
+        workspace_id: Uuid,
+        user_id: Uuid,
+        records: Vec<std::result::Result<ImportSessionRecord, String>>,
+        dry_run: bool,
+    ) -> Result<ImportReport> {
+        let mut tx = self.pool.begin().await?;
+        let mut tag_ids: HashMap<String, Uuid> = HashMap::new();
+        let mut created_tags = Vec::new();
+        let mut errors = Vec::new();
+        let mut imported = 0;
+
+        for (index, record) in records.into_iter().enumerate() {
+            let row = index + 1;
+            let record = match record {
+                Ok(record) => record,
+                Err(message) => {
+                    errors.push(ImportRowError { row, message });
+                    continue;
+                }
+            };
+
+            let mut session_tag_ids = Vec::new();
+            for name in &record.tags {
+                let name = name.trim();
+                if name.is_empty() || name.chars().count() > MAX_TAG_NAME_LENGTH {
+                    errors.push(ImportRowError {
+                        row,
+                        message: format!("Invalid tag name: {name:?}"),
+                    });
+                    continue;
+                }
+                let key = name.to_lowercase();
+                if let Some(id) = tag_ids.get(&key) {
+                    session_tag_ids.push(*id);
+                    continue;
+                }
+
+                let existing = sqlx::query_scalar!(
+                    "SELECT id FROM tags WHERE workspace_id = $1 AND lower(btrim(name)) = lower($2)",
+                    workspace_id,
+                    name
+                )
+                .fetch_optional(&mut *tx)
+                .await?;
+                let id = match existing {
+                    Some(id) => id,
+                    None => {
+                        let id = sqlx::query_scalar!(
+                            "INSERT INTO tags (id, workspace_id, user_id, name, created_at) VALUES ($1, $2, $3, $4, $5) RETURNING id",
+                            Uuid::new_v4(),
+                            workspace_id,
+                            user_id,
+                            name,
+                            Utc::now()
+                        )
+                        .fetch_one(&mut *tx)
+                        .await?;
+                        created_tags.push(name.to_string());
+                        id
+                    }
+                };
+                tag_ids.insert(key, id);
+                session_tag_ids.push(id);
+            }
+
+            // A tag may be listed twice
+            session_tag_ids.sort();
+            session_tag_ids.dedup();
+
+            let req = CreateSessionRequest {
+                duration_seconds: record.duration_seconds,
+                description: record.description,
+                started_at: record.started_at,
+                ended_at: record.ended_at,
+                tag_ids: session_tag_ids,
+                project_id: None,
+                billable: false,
+                hourly_rate: None,
+            };
+            if let Err(e) = req.validate() {
+                errors.push(ImportRowError {
+                    row,
+                    message: e.to_string(),
+                });
+                continue;
+            }
+            match self.insert_session(&mut tx, workspace_id, user_id, req).await {
+                Ok(_) => imported += 1,
+                Err(e) if e.is::<InvalidInput>() || e.is::<ValidationErrors>() => errors.push(ImportRowError {
+                    row,
+                    message: e.to_string(),
+                }),
+                Err(e) => return Err(e),
+            }
+        }
+
+        let committed = !dry_run && errors.is_empty();
+        if committed {
+            tx.commit().await?;
+        } else {
+            tx.rollback().await?;
+            if !errors.is_empty() {
+                imported = 0;
+            }
+        }
+
+        Ok(ImportReport {
+            dry_run,
+            committed,
+            imported,
+            created_tags,
+            errors,
+        })
+    }
+
+    /// Starts a new running session. Returns `None` if the user already has
+    /// a session running, in any workspace.
+    pub async fn start_session(&self, workspace_id: Uuid, user_id: Uuid, req: StartSessionRequest) -> Result<Option<WorkSession>> {
         let session_id = Uuid::new_v4();
         let now = Utc::now();
 
         let mut tx = self.pool.begin().await?;
+        self.check_tags_exist(&mut tx, workspace_id, &req.tag_ids).await?;
+        self.check_project_exists(&mut tx, workspace_id, req.project_id).await?;
 
-        // Insert session
-        sqlx::query!(
-            "INSERT INTO work_sessions (id, duration_seconds, description, created_at, updated_at) 
-             VALUES ($1, $2, $3, $4, $5)",
+        let inserted = sqlx::query!(
+            "INSERT INTO work_sessions (id, workspace_id, user_id, duration_seconds, description, started_at, created_at, updated_at, project_id, billable, hourly_rate) 
+             VALUES ($1, $2, $3, 0, $4, $5, $5, $5, $6, $7, $8)",
             session_id,
-            req.duration_seconds,
+            workspace_id,
+            user_id,
             req.description,
             now,
+            req.project_id,
+            req.billable,
+            req.hourly_rate
+        )
+        .execute(&mut *tx)
+        .await;
+
+        match inserted {
+            Err(sqlx::Error::Database(e)) if e.constraint() == Some("idx_work_sessions_running") => {
+                return Ok(None);
+            }
+            other => {
+                other?;
+            }
+        }
+
+        sqlx::query!(
+            "INSERT INTO session_segments (session_id, started_at) VALUES ($1, $2)",
+            session_id,
             now
         )
         .execute(&mut *tx)
         .await?;
 
-        // Insert session tags
         for tag_id in &req.tag_ids {
             sqlx::query!(
                 "INSERT INTO session_tags (session_id, tag_id) VALUES ($1, $2)",
@@ -43,91 +402,363 @@ impl Database {
             .execute(&mut *tx)
             .await?;
         }
+        self.queue_session_event(&mut tx, workspace_id, WebhookEvent::SessionStarted, session_id).await?;
 
         tx.commit().await?;
 
-        Ok(WorkSession {
+        Ok(Some(WorkSession {
             id: session_id,
-            duration_seconds: req.duration_seconds,
+            duration_seconds: 0,
             description: req.description,
+            started_at: now,
+            ended_at: None,
             created_at: now,
             updated_at: now,
-        })
+            project_id: req.project_id,
+            billable: req.billable,
+            hourly_rate: req.hourly_rate,
+        }))
     }
 
-    pub async fn get_session(&self, id: Uuid) -> Result<Option<WorkSessionWithTags>> {
+    /// Stops a running or paused session, closing its current segment and
+    /// setting its duration to the total of its segments. Returns `None` if
+    /// no running session has this id.
+    pub async fn stop_session(&self, workspace_id: Uuid, id: Uuid) -> Result<Option<WorkSession>> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+
+        if !self.lock_unfinished_session(&mut tx, workspace_id, id).await? {
+            return Ok(None);
+        }
+
+        sqlx::query!(
+            "UPDATE session_segments SET ended_at = $2 WHERE session_id = $1 AND ended_at IS NULL",
+            id,
+            now
+        )
+        .execute(&mut *tx)
+        .await?;
+
         let session = sqlx::query_as!(
             WorkSession,
-            "SELECT id, duration_seconds, description, created_at, updated_at 
-             FROM work_sessions WHERE id = $1",
-            id
+            "UPDATE work_sessions 
+             SET ended_at = $2,
+                 duration_seconds = (
+                     SELECT COALESCE(SUM(EXTRACT(EPOCH FROM (seg.ended_at - seg.started_at))), 0)::INTEGER
+                     FROM session_segments seg WHERE seg.session_id = $1
+                 ),
+                 updated_at = $2
+             WHERE id = $1
+             RETURNING id, duration_seconds, description, started_at, ended_at, created_at, updated_at, project_id, billable, hourly_rate",
+            id,
+            now
         )
-        .fetch_optional(&self.pool)
+        .fetch_one(&mut *tx)
         .await?;
 
-        if let Some(session) = session {
-            let tags = self.get_session_tags(id).await?;
-            Ok(Some(WorkSessionWithTags {
-                id: session.id,
-                duration_seconds: session.duration_seconds,
-                description: session.description,
-                created_at: session.created_at,
-                updated_at: session.updated_at,
-                tags,
-            }))
-        } else {
-            Ok(None)
+        self.queue_session_event(&mut tx, workspace_id, WebhookEvent::SessionStopped, id).await?;
+
+        tx.commit().await?;
+        Ok(Some(session))
+    }
+
+    /// Pauses a running session by closing its current segment. The time
+    /// worked so far is kept in `duration_seconds`.
+    pub async fn pause_session(&self, workspace_id: Uuid, id: Uuid) -> Result<Option<WorkSession>> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+
+        if !self.lock_unfinished_session(&mut tx, workspace_id, id).await? {
+            return Ok(None);
         }
+
+        let closed = sqlx::query!(
+            "UPDATE session_segments SET ended_at = $2 WHERE session_id = $1 AND ended_at IS NULL",
+            id,
+            now
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if closed.rows_affected() == 0 {
+            return Err(InvalidInput("Session is already paused".to_string()).into());
+        }
+
+        let session = sqlx::query_as!(
+            WorkSession,
+            "UPDATE work_sessions 
+             SET duration_seconds = (
+                     SELECT COALESCE(SUM(EXTRACT(EPOCH FROM (seg.ended_at - seg.started_at))), 0)::INTEGER
+                     FROM session_segments seg WHERE seg.session_id = $1
+                 ),
+                 updated_at = $2
+             WHERE id = $1
+             RETURNING id, duration_seconds, description, started_at, ended_at, created_at, updated_at, project_id, billable, hourly_rate",
+            id,
+            now
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        self.queue_session_event(&mut tx, workspace_id, WebhookEvent::SessionPaused, id).await?;
+
+        tx.commit().await?;
+        Ok(Some(session))
     }
 
-    pub async fn get_sessions(&self) -> Result<Vec<WorkSessionWithTags>> {
-        let sessions = sqlx::query_as!(
+    /// Resumes a paused session by opening a new segment.
+    pub async fn resume_session(&self, workspace_id: Uuid, id: Uuid) -> Result<Option<WorkSession>> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+
+        if !self.lock_unfinished_session(&mut tx, workspace_id, id).await? {
+            return Ok(None);
+        }
+
+        let inserted = sqlx::query!(
+            "INSERT INTO session_segments (session_id, started_at) VALUES ($1, $2)",
+            id,
+            now
+        )
+        .execute(&mut *tx)
+        .await;
+
+        match inserted {
+            Err(sqlx::Error::Database(e)) if e.constraint() == Some("idx_session_segments_open") => {
+                return Err(InvalidInput("Session is not paused".to_string()).into());
+            }
+            other => {
+                other?;
+            }
+        }
+
+        let session = sqlx::query_as!(
             WorkSession,
-            "SELECT id, duration_seconds, description, created_at, updated_at 
-             FROM work_sessions ORDER BY created_at DESC"
+            "UPDATE work_sessions SET updated_at = $2 WHERE id = $1
+             RETURNING id, duration_seconds, description, started_at, ended_at, created_at, updated_at, project_id, billable, hourly_rate",
+            id,
+            now
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        self.queue_session_event(&mut tx, workspace_id, WebhookEvent::SessionResumed, id).await?;
+
+        tx.commit().await?;
+        Ok(Some(session))
+    }
+
+    pub async fn get_session(&self, workspace_id: Uuid, id: Uuid) -> Result<Option<WorkSessionWithTags>> {
+        let session = sqlx::query_as!(
+            SessionDetailsRow,
+            r#"SELECT id AS "id!", duration_seconds AS "duration_seconds!", description, 
+                      started_at AS "started_at!", ended_at, created_at AS "created_at!", updated_at AS "updated_at!", user_id AS "user_id!", project_id,
+                      billable AS "billable!", hourly_rate, effective_rate, amount, currency AS "currency!",
+                      tags AS "tags!: Json<Vec<Tag>>", segments AS "segments!: Json<Vec<SessionSegment>>"
+               FROM session_details WHERE id = $1 AND workspace_id = $2"#,
+            id,
+            workspace_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(session.map(Into::into))
+    }
+
+    /// The user who tracked a session, or `None` if it does not exist.
+    pub async fn get_session_owner(&self, workspace_id: Uuid, id: Uuid) -> Result<Option<Uuid>> {
+        let owner = sqlx::query_scalar!(
+            r#"SELECT user_id AS "user_id!" FROM work_sessions WHERE id = $1 AND workspace_id = $2"#,
+            id,
+            workspace_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(owner)
+    }
+
+    pub async fn get_sessions(&self, workspace_id: Uuid, query: &SessionQuery) -> Result<Page<WorkSessionWithTags>> {
+        let cursor = match &query.cursor {
+            Some(cursor) => Some(
+                SessionCursor::decode(cursor)
+                    .ok_or_else(|| InvalidInput("Invalid cursor".to_string()))?,
+            ),
+            None => None,
+        };
+        let limit = query
+            .limit
+            .unwrap_or(SessionQuery::DEFAULT_LIMIT)
+            .clamp(1, SessionQuery::MAX_LIMIT);
+        let search = query.search.as_deref().map(escape_like);
+
+        // One extra row tells us whether there is another page
+        let mut sessions = sqlx::query_as!(
+            SessionDetailsRow,
+            r#"SELECT s.id AS "id!", s.duration_seconds AS "duration_seconds!", s.description, 
+                      s.started_at AS "started_at!", s.ended_at, s.created_at AS "created_at!", s.updated_at AS "updated_at!", s.user_id AS "user_id!", s.project_id,
+                      s.billable AS "billable!", s.hourly_rate, s.effective_rate, s.amount, s.currency AS "currency!",
+                      s.tags AS "tags!: Json<Vec<Tag>>", s.segments AS "segments!: Json<Vec<SessionSegment>>"
+             FROM session_details s
+             WHERE s.workspace_id = $13
+               AND ($1::timestamptz IS NULL OR s.started_at >= $1)
+               AND ($2::timestamptz IS NULL OR s.started_at < $2)
+               AND (cardinality($3::uuid[]) = 0 OR CASE
+                       WHEN $4 THEN ARRAY(SELECT st.tag_id FROM session_tags st WHERE st.session_id = s.id) @> $3
+                       ELSE EXISTS (SELECT 1 FROM session_tags st WHERE st.session_id = s.id AND st.tag_id = ANY($3))
+                   END)
+               AND ($5::text IS NULL OR s.description ILIKE '%' || $5 || '%')
+               AND ($6::int IS NULL OR s.duration_seconds >= $6)
+               AND ($7::int IS NULL OR s.duration_seconds <= $7)
+               AND ($12::bool IS NULL OR (s.ended_at IS NULL) = $12)
+               AND ($8::timestamptz IS NULL OR CASE
+                       WHEN $10 THEN (s.started_at, s.id) > ($8, $9::uuid)
+                       ELSE (s.started_at, s.id) < ($8, $9::uuid)
+                   END)
+             ORDER BY
+               CASE WHEN $10 THEN s.started_at END ASC,
+               CASE WHEN $10 THEN s.id END ASC,
+               s.started_at DESC,
+               s.id DESC
+             LIMIT $11"#,
+            query.from,
+            query.to,
+            &query.tag_ids,
+            query.tag_match == TagMatch::All,
+            search,
+            query.min_duration,
+            query.max_duration,
+            cursor.map(|c| c.started_at),
+            cursor.map(|c| c.id),
+            query.order == SortOrder::Asc,
+            limit + 1,
+            query.running,
+            workspace_id
         )
         .fetch_all(&self.pool)
         .await?;
 
-        let mut sessions_with_tags = Vec::new();
-        for session in sessions {
-            let tags = self.get_session_tags(session.id).await?;
-            sessions_with_tags.push(WorkSessionWithTags {
-                id: session.id,
-                duration_seconds: session.duration_seconds,
-                description: session.description,
-                created_at: session.created_at,
-                updated_at: session.updated_at,
-                tags,
-            });
-        }
+        let next_cursor = if sessions.len() as i64 > limit {
+            sessions.truncate(limit as usize);
+            sessions.last().map(|s| {
+                SessionCursor {
+                    started_at: s.started_at,
+                    id: s.id,
+                }
+                .encode()
+            })
+        } else {
+            None
+        };
 
-        Ok(sessions_with_tags)
+        Ok(Page {
+            items: sessions.into_iter().map(Into::into).collect(),
+            next_cursor,
+        })
     }
 
-    pub async fn update_session(&self, id: Uuid, req: UpdateSessionRequest) -> Result<Option<WorkSession>> {
+    /// Applies a merge patch to a session. Returns `None` if it does not exist.
+    pub async fn update_session(&self, workspace_id: Uuid, id: Uuid, req: UpdateSessionRequest) -> Result<Option<WorkSession>> {
         let mut tx = self.pool.begin().await?;
 
+        let current = sqlx::query!(
+            "SELECT started_at, ended_at, duration_seconds FROM work_sessions
+             WHERE id = $1 AND workspace_id = $2 FOR UPDATE",
+            id,
+            workspace_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(current) = current else {
+            return Ok(None);
+        };
+        self.check_not_invoiced(&mut tx, workspace_id, id).await?;
+
+        // A cleared tag list removes every tag
+        let tag_ids = match &req.tag_ids {
+            FieldUpdate::Unchanged => None,
+            FieldUpdate::Clear => Some(Vec::new()),
+            FieldUpdate::Set(tag_ids) => Some(tag_ids.clone()),
+        };
+        if let Some(tag_ids) = &tag_ids {
+            self.check_tags_exist(&mut tx, workspace_id, tag_ids).await?;
+        }
+        self.check_project_exists(&mut tx, workspace_id, req.project_id.value().copied()).await?;
+
+        let req_started_at = req.started_at.value().copied();
+        let req_ended_at = req.ended_at.value().copied();
+        let req_duration = req.duration_seconds.value().copied();
+
+        let times_given = req_started_at.is_some() || req_ended_at.is_some();
+        let (started_at, ended_at, duration_seconds) = match current.ended_at {
+            None if times_given || req_duration.is_some() => {
+                return Err(InvalidInput(
+                    "Stop the running session before changing its times".to_string(),
+                )
+                .into());
+            }
+            None => (current.started_at, None, current.duration_seconds),
+            Some(current_ended_at) => {
+                let (started_at, ended_at, duration_seconds) = if times_given {
+                    resolve_bounds(
+                        Some(req_started_at.unwrap_or(current.started_at)),
+                        Some(req_ended_at.unwrap_or(current_ended_at)),
+                        req_duration,
+                        Utc::now(),
+                    )?
+                } else if req_duration.is_some() {
+                    // A bare duration keeps the start and moves the end
+                    resolve_bounds(Some(current.started_at), None, req_duration, Utc::now())?
+                } else {
+                    (current.started_at, current_ended_at, current.duration_seconds)
+                };
+                (started_at, Some(ended_at), duration_seconds)
+            }
+        };
+        let times_changed = (started_at, ended_at, duration_seconds)
+            != (current.started_at, current.ended_at, current.duration_seconds);
+
         // Update session
         let updated_session = sqlx::query_as!(
             WorkSession,
             "UPDATE work_sessions 
-             SET duration_seconds = COALESCE($2, duration_seconds),
-                 description = COALESCE($3, description),
-                 updated_at = $4
+             SET duration_seconds = $2,
+                 description = CASE WHEN $3 THEN $4 ELSE description END,
+                 started_at = $5,
+                 ended_at = $6,
+                 updated_at = $7,
+                 project_id = CASE WHEN $8 THEN $9 ELSE project_id END,
+                 billable = COALESCE($10, billable),
+                 hourly_rate = CASE WHEN $11 THEN $12 ELSE hourly_rate END
              WHERE id = $1
-             RETURNING id, duration_seconds, description, created_at, updated_at",
+             RETURNING id, duration_seconds, description, started_at, ended_at, created_at, updated_at, project_id, billable, hourly_rate",
             id,
-            req.duration_seconds,
-            req.description,
-            Utc::now()
+            duration_seconds,
+            !req.description.is_unchanged(),
+            req.description.value(),
+            started_at,
+            ended_at,
+            Utc::now(),
+            !req.project_id.is_unchanged(),
+            req.project_id.value(),
+            req.billable.value(),
+            !req.hourly_rate.is_unchanged(),
+            req.hourly_rate.value()
         )
         .fetch_optional(&mut *tx)
         .await?;
 
         if let Some(_session) = &updated_session {
+            // Hand-edited times no longer match the recorded work periods
+            if times_changed {
+                sqlx::query!("DELETE FROM session_segments WHERE session_id = $1", id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+
             // Update tags if provided
-            if let Some(tag_ids) = req.tag_ids {
+            if let Some(tag_ids) = tag_ids {
                 // Delete existing tags
                 sqlx::query!("DELETE FROM session_tags WHERE session_id = $1", id)
                     .execute(&mut *tx)
@@ -144,53 +775,103 @@ impl Database {
                     .await?;
                 }
             }
+            self.queue_session_event(&mut tx, workspace_id, WebhookEvent::SessionUpdated, id).await?;
         }
 
         tx.commit().await?;
         Ok(updated_session)
     }
 
-    pub async fn delete_session(&self, id: Uuid) -> Result<bool> {
+    /// Replaces every editable field of a finished session, as for `PUT`.
+    /// Times follow the rules of [`CreateSessionRequest`].
+    pub async fn replace_session(&self, workspace_id: Uuid, id: Uuid, req: CreateSessionRequest) -> Result<Option<WorkSession>> {
+        let (started_at, ended_at, duration_seconds) =
+            resolve_bounds(req.started_at, req.ended_at, req.duration_seconds, Utc::now())?;
+
+        let update = UpdateSessionRequest {
+            duration_seconds: FieldUpdate::Set(duration_seconds),
+            description: req.description.into(),
+            started_at: FieldUpdate::Set(started_at),
+            ended_at: FieldUpdate::Set(ended_at),
+            tag_ids: FieldUpdate::Set(req.tag_ids),
+            project_id: req.project_id.into(),
+            billable: FieldUpdate::Set(req.billable),
+            hourly_rate: req.hourly_rate.into(),
+        };
+        self.update_session(workspace_id, id, update).await
+    }
+
+    pub async fn delete_session(&self, workspace_id: Uuid, id: Uuid) -> Result<bool> {
         let mut tx = self.pool.begin().await?;
+        self.check_not_invoiced(&mut tx, workspace_id, id).await?;
+
+        // Webhooks report the session as it was, so read it before deleting
+        let event = WebhookEvent::SessionDeleted;
+        let webhook_ids = self.subscribed_webhooks(&mut tx, workspace_id, event).await?;
+        let data = if webhook_ids.is_empty() {
+            None
+        } else {
+            self.session_payload(&mut tx, workspace_id, id).await?
+        };
 
         // Delete session tags first
-        sqlx::query!("DELETE FROM session_tags WHERE session_id = $1", id)
-            .execute(&mut *tx)
-            .await?;
+        sqlx::query!(
+            "DELETE FROM session_tags
+             WHERE session_id IN (SELECT id FROM work_sessions WHERE id = $1 AND workspace_id = $2)",
+            id,
+            workspace_id
+        )
+        .execute(&mut *tx)
+        .await?;
 
         // Delete session
-        let result = sqlx::query!("DELETE FROM work_sessions WHERE id = $1", id)
+        let result = sqlx::query!("DELETE FROM work_sessions WHERE id = $1 AND workspace_id = $2", id, workspace_id)
             .execute(&mut *tx)
             .await?;
 
+        let deleted = result.rows_affected() > 0;
+        if let Some(data) = data.filter(|_| deleted) {
+            self.queue_deliveries(&mut tx, workspace_id, event, &webhook_ids, data).await?;
+        }
+
         tx.commit().await?;
         Ok(result.rows_affected() > 0)
     }
 
     // Tag operations
-    pub async fn create_tag(&self, req: CreateTagRequest) -> Result<Tag> {
+    pub async fn create_tag(&self, workspace_id: Uuid, user_id: Uuid, req: CreateTagRequest) -> Result<Tag> {
         let tag_id = Uuid::new_v4();
         let now = Utc::now();
+        let name = req.name.trim();
 
         let tag = sqlx::query_as!(
             Tag,
-            "INSERT INTO tags (id, name, color, created_at) VALUES ($1, $2, $3, $4) 
-             RETURNING id, name, color, created_at",
+            "INSERT INTO tags (id, workspace_id, user_id, name, color, hourly_rate, created_at) VALUES ($1, $2, $3, $4, $5, $6, $7) 
+             RETURNING id, name, color, hourly_rate, created_at",
             tag_id,
-            req.name,
+            workspace_id,
+            user_id,
+            name,
             req.color,
+            req.hourly_rate,
             now
         )
         .fetch_one(&self.pool)
-        .await?;
+        .await;
 
-        Ok(tag)
+        match tag {
+            Err(sqlx::Error::Database(e)) if e.constraint() == Some("idx_tags_workspace_name_lower") => {
+                Err(self.tag_name_conflict(workspace_id, name).await?.into())
+            }
+            other => Ok(other?),
+        }
     }
 
-    pub async fn get_tags(&self) -> Result<Vec<Tag>> {
+    pub async fn get_tags(&self, workspace_id: Uuid) -> Result<Vec<Tag>> {
         let tags = sqlx::query_as!(
             Tag,
-            "SELECT id, name, color, created_at FROM tags ORDER BY name"
+            "SELECT id, name, color, hourly_rate, created_at FROM tags WHERE workspace_id = $1 ORDER BY name",
+            workspace_id
         )
         .fetch_all(&self.pool)
         .await?;
@@ -198,11 +879,12 @@ impl Database {
         Ok(tags)
     }
 
-    pub async fn get_tag(&self, id: Uuid) -> Result<Option<Tag>> {
+    pub async fn get_tag(&self, workspace_id: Uuid, id: Uuid) -> Result<Option<Tag>> {
         let tag = sqlx::query_as!(
             Tag,
-            "SELECT id, name, color, created_at FROM tags WHERE id = $1",
-            id
+            "SELECT id, name, color, hourly_rate, created_at FROM tags WHERE id = $1 AND workspace_id = $2",
+            id,
+            workspace_id
         )
         .fetch_optional(&self.pool)
         .await?;
@@ -210,34 +892,60 @@ impl Database {
         Ok(tag)
     }
 
-    pub async fn update_tag(&self, id: Uuid, req: UpdateTagRequest) -> Result<Option<Tag>> {
+    pub async fn update_tag(&self, workspace_id: Uuid, id: Uuid, req: UpdateTagRequest) -> Result<Option<Tag>> {
+        let name = req.name.value().map(|name| name.trim());
         let tag = sqlx::query_as!(
             Tag,
             "UPDATE tags 
              SET name = COALESCE($2, name),
-                 color = COALESCE($3, color)
-             WHERE id = $1
-             RETURNING id, name, color, created_at",
+                 color = CASE WHEN $3 THEN $4 ELSE color END,
+                 hourly_rate = CASE WHEN $6 THEN $7 ELSE hourly_rate END
+             WHERE id = $1 AND workspace_id = $5
+             RETURNING id, name, color, hourly_rate, created_at",
             id,
-            req.name,
-            req.color
+            name,
+            !req.color.is_unchanged(),
+            req.color.value(),
+            workspace_id,
+            !req.hourly_rate.is_unchanged(),
+            req.hourly_rate.value()
         )
         .fetch_optional(&self.pool)
-        .await?;
+        .await;
 
-        Ok(tag)
+        match tag {
+            Err(sqlx::Error::Database(e)) if e.constraint() == Some("idx_tags_workspace_name_lower") => {
+                Err(self.tag_name_conflict(workspace_id, name.unwrap_or_default()).await?.into())
+            }
+            other => Ok(other?),
+        }
     }
 
-    pub async fn delete_tag(&self, id: Uuid) -> Result<bool> {
+    /// Replaces a tag's name and color, as for `PUT`.
+    pub async fn replace_tag(&self, workspace_id: Uuid, id: Uuid, req: CreateTagRequest) -> Result<Option<Tag>> {
+        let update = UpdateTagRequest {
+            name: FieldUpdate::Set(req.name),
+            color: req.color.into(),
+            hourly_rate: req.hourly_rate.into(),
+        };
+        self.update_tag(workspace_id, id, update).await
+    }
+
+    pub async fn delete_tag(&self, workspace_id: Uuid, id: Uuid) -> Result<bool> {
         let mut tx = self.pool.begin().await?;
 
         // Delete session tags first
-        sqlx::query!("DELETE FROM session_tags WHERE tag_id = $1", id)
-            .execute(&mut *tx)
-            .await?;
+        sqlx::query!(
+            "DELETE FROM session_tags
+             WHERE tag_id IN (SELECT id FROM tags WHERE id = $1 AND workspace_id = $2)",
+            id,
+            workspace_id
+        )
+        .execute(&mut *tx)
+        .await?;
 
         // Delete tag
-        let result = sqlx::query!("DELETE FROM tags WHERE id = $1", id)
+        let result = sqlx::query!("DELETE FROM tags WHERE id = $1 AND workspace_id = $2", id, workspace_id)
             .execute(&mut *tx)
             .await?;
 
@@ -245,20 +953,2249 @@ impl Database {
         Ok(result.rows_affected() > 0)
     }
 
-    // Helper methods
-    async fn get_session_tags(&self, session_id: Uuid) -> Result<Vec<Tag>> {
-        let tags = sqlx::query_as!(
-            Tag,
-            "SELECT t.id, t.name, t.color, t.created_at 
-             FROM tags t 
-             JOIN session_tags st ON t.id = st.tag_id 
-             WHERE st.session_id = $1
-             ORDER BY t.name",
-            session_id
+    // Client operations
+    pub async fn create_client(&self, workspace_id: Uuid, req: CreateClientRequest) -> Result<Client> {
+        let name = req.name.trim();
+        let client = sqlx::query_as!(
+            Client,
+            "INSERT INTO clients (id, workspace_id, name, created_at) VALUES ($1, $2, $3, $4)
+             RETURNING id, name, created_at",
+            Uuid::new_v4(),
+            workspace_id,
+            name,
+            Utc::now()
+        )
+        .fetch_one(&self.pool)
+        .await;
+
+        match client {
+            Err(sqlx::Error::Database(e)) if e.constraint() == Some("idx_clients_workspace_name_lower") => {
+                Err(self.client_name_conflict(workspace_id, name).await?.into())
+            }
+            other => Ok(other?),
+        }
+    }
+
+    pub async fn get_clients(&self, workspace_id: Uuid) -> Result<Vec<Client>> {
+        let clients = sqlx::query_as!(
+            Client,
+            "SELECT id, name, created_at FROM clients WHERE workspace_id = $1 ORDER BY name",
+            workspace_id
         )
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(tags)
+        Ok(clients)
+    }
+
+    pub async fn get_client(&self, workspace_id: Uuid, id: Uuid) -> Result<Option<Client>> {
+        let client = sqlx::query_as!(
+            Client,
+            "SELECT id, name, created_at FROM clients WHERE id = $1 AND workspace_id = $2",
+            id,
+            workspace_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(client)
+    }
+
+    pub async fn update_client(&self, workspace_id: Uuid, id: Uuid, req: UpdateClientRequest) -> Result<Option<Client>> {
+        let name = req.name.value().map(|name| name.trim());
+        let client = sqlx::query_as!(
+            Client,
+            "UPDATE clients SET name = COALESCE($2, name)
+             WHERE id = $1 AND workspace_id = $3
+             RETURNING id, name, created_at",
+            id,
+            name,
+            workspace_id
+        )
+        .fetch_optional(&self.pool)
+        .await;
+
+        match client {
+            Err(sqlx::Error::Database(e)) if e.constraint() == Some("idx_clients_workspace_name_lower") => {
+                Err(self.client_name_conflict(workspace_id, name.unwrap_or_default()).await?.into())
+            }
+            other => Ok(other?),
+        }
     }
-}
\ No newline at end of file
+
+    /// Replaces a client's name, as for `PUT`.
+    pub async fn replace_client(&self, workspace_id: Uuid, id: Uuid, req: CreateClientRequest) -> Result<Option<Client>> {
+        let update = UpdateClientRequest {
+            name: FieldUpdate::Set(req.name),
+        };
+        self.update_client(workspace_id, id, update).await
+    }
+
+    /// Deletes a client with its projects. Sessions booked against them are
+    /// kept without a project.
+    pub async fn delete_client(&self, workspace_id: Uuid, id: Uuid) -> Result<bool> {
+        let result = sqlx::query!("DELETE FROM clients WHERE id = $1 AND workspace_id = $2", id, workspace_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // Project operations
+    pub async fn create_project(&self, workspace_id: Uuid, req: CreateProjectRequest) -> Result<Project> {
+        let mut tx = self.pool.begin().await?;
+        self.check_client_exists(&mut tx, workspace_id, req.client_id).await?;
+        let name = req.name.trim();
+
+        let project = sqlx::query_as!(
+            Project,
+            "INSERT INTO projects (id, client_id, name, hourly_rate, budget_seconds, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING id, client_id, name, hourly_rate, budget_seconds, created_at",
+            Uuid::new_v4(),
+            req.client_id,
+            name,
+            req.hourly_rate,
+            req.budget_seconds,
+            Utc::now()
+        )
+        .fetch_one(&mut *tx)
+        .await;
+
+        match project {
+            Err(sqlx::Error::Database(e)) if e.constraint() == Some("idx_projects_client_name_lower") => {
+                Err(self.project_name_conflict(req.client_id, name).await?.into())
+            }
+            other => {
+                let project = other?;
+                tx.commit().await?;
+                Ok(project)
+            }
+        }
+    }
+
+    pub async fn get_projects(&self, workspace_id: Uuid) -> Result<Vec<Project>> {
+        let projects = sqlx::query_as!(
+            Project,
+            "SELECT p.id, p.client_id, p.name, p.hourly_rate, p.budget_seconds, p.created_at
+             FROM projects p JOIN clients c ON c.id = p.client_id
+             WHERE c.workspace_id = $1
+             ORDER BY c.name, p.name",
+            workspace_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(projects)
+    }
+
+    pub async fn get_project(&self, workspace_id: Uuid, id: Uuid) -> Result<Option<Project>> {
+        let project = sqlx::query_as!(
+            Project,
+            "SELECT p.id, p.client_id, p.name, p.hourly_rate, p.budget_seconds, p.created_at
+             FROM projects p JOIN clients c ON c.id = p.client_id
+             WHERE p.id = $1 AND c.workspace_id = $2",
+            id,
+            workspace_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(project)
+    }
+
+    pub async fn update_project(&self, workspace_id: Uuid, id: Uuid, req: UpdateProjectRequest) -> Result<Option<Project>> {
+        let mut tx = self.pool.begin().await?;
+        if let Some(client_id) = req.client_id.value() {
+            self.check_client_exists(&mut tx, workspace_id, *client_id).await?;
+        }
+        let name = req.name.value().map(|name| name.trim());
+
+        let project = sqlx::query_as!(
+            Project,
+            "UPDATE projects p
+             SET client_id = COALESCE($2, p.client_id),
+                 name = COALESCE($3, p.name),
+                 hourly_rate = CASE WHEN $4 THEN $5 ELSE p.hourly_rate END,
+                 budget_seconds = CASE WHEN $6 THEN $7 ELSE p.budget_seconds END
+             FROM clients c
+             WHERE p.id = $1 AND c.id = p.client_id AND c.workspace_id = $8
+             RETURNING p.id, p.client_id, p.name, p.hourly_rate, p.budget_seconds, p.created_at",
+            id,
+            req.client_id.value(),
+            name,
+            !req.hourly_rate.is_unchanged(),
+            req.hourly_rate.value(),
+            !req.budget_seconds.is_unchanged(),
+            req.budget_seconds.value(),
+            workspace_id
+        )
+        .fetch_optional(&mut *tx)
+        .await;
+
+        match project {
+            Err(sqlx::Error::Database(e)) if e.constraint() == Some("idx_projects_client_name_lower") => {
+                // The clash may come from a new name, a new client, or both
+                let current = self.get_project(workspace_id, id).await?;
+                let client_id = req.client_id.value().copied().or(current.as_ref().map(|p| p.client_id));
+                let name = name.map(str::to_string).or(current.map(|p| p.name)).unwrap_or_default();
+                Err(self.project_name_conflict(client_id.unwrap_or_default(), &name).await?.into())
+            }
+            other => {
+                let project = other?;
+                tx.commit().await?;
+                Ok(project)
+            }
+        }
+    }
+
+    /// Replaces every field of a project, as for `PUT`.
+    pub async fn replace_project(&self, workspace_id: Uuid, id: Uuid, req: CreateProjectRequest) -> Result<Option<Project>> {
+        let update = UpdateProjectRequest {
+            client_id: FieldUpdate::Set(req.client_id),
+            name: FieldUpdate::Set(req.name),
+            hourly_rate: req.hourly_rate.into(),
+            budget_seconds: req.budget_seconds.into(),
+        };
+        self.update_project(workspace_id, id, update).await
+    }
+
+    /// Deletes a project. Sessions booked against it are kept without one.
+    pub async fn delete_project(&self, workspace_id: Uuid, id: Uuid) -> Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM projects p USING clients c
+             WHERE p.id = $1 AND c.id = p.client_id AND c.workspace_id = $2",
+            id,
+            workspace_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // Account operations
+    /// Creates a user along with their personal workspace. The first user to
+    /// register also takes over the sessions and tags recorded before accounts
+    /// existed.
+    pub async fn create_user(&self, email: &str, password_hash: &str) -> Result<User> {
+        let mut tx = self.pool.begin().await?;
+
+        let inserted = sqlx::query_as!(
+            User,
+            "INSERT INTO users (id, email, password_hash, created_at) VALUES ($1, $2, $3, $4)
+             RETURNING id, email, created_at",
+            Uuid::new_v4(),
+            email,
+            password_hash,
+            Utc::now()
+        )
+        .fetch_one(&mut *tx)
+        .await;
+
+        let user = match inserted {
+            Err(sqlx::Error::Database(e)) if e.constraint() == Some("idx_users_email_lower") => {
+                return Err(Conflict {
+                    message: "An account with this email already exists".to_string(),
+                    existing_id: None,
+                }
+                .into());
+            }
+            other => other?,
+        };
+        let workspace = self.insert_workspace(&mut tx, user.id, "Personal", DEFAULT_CURRENCY).await?;
+
+        let first_user = sqlx::query_scalar!(
+            r#"SELECT NOT EXISTS (SELECT 1 FROM users WHERE id <> $1) AS "first_user!""#,
+            user.id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if first_user {
+            sqlx::query!(
+                "UPDATE work_sessions SET user_id = $1, workspace_id = $2 WHERE user_id IS NULL",
+                user.id,
+                workspace.id
+            )
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query!(
+                "UPDATE tags SET user_id = $1, workspace_id = $2 WHERE user_id IS NULL",
+                user.id,
+                workspace.id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(user)
+    }
+
+    /// Looks up a user by email, ignoring case, along with their password hash.
+    pub async fn get_user_credentials(&self, email: &str) -> Result<Option<(User, String)>> {
+        let row = sqlx::query!(
+            "SELECT id, email, password_hash, created_at FROM users WHERE lower(email) = lower($1)",
+            email
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| {
+            let user = User {
+                id: row.id,
+                email: row.email,
+                created_at: row.created_at,
+            };
+            (user, row.password_hash)
+        }))
+    }
+
+    pub async fn create_login_session(
+        &self,
+        user_id: Uuid,
+        token_hash: &[u8],
+        expires_at: DateTime<Utc>,
+    ) -> Result<()> {
+        // Expired logins are cleared out whenever the user logs in again
+        sqlx::query!(
+            "DELETE FROM login_sessions WHERE user_id = $1 AND expires_at <= NOW()",
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query!(
+            "INSERT INTO login_sessions (token_hash, user_id, created_at, expires_at) VALUES ($1, $2, $3, $4)",
+            token_hash,
+            user_id,
+            Utc::now(),
+            expires_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The user logged in with this token, unless the login has expired.
+    pub async fn get_login_user(&self, token_hash: &[u8]) -> Result<Option<User>> {
+        let user = sqlx::query_as!(
+            User,
+            "SELECT u.id, u.email, u.created_at
+             FROM login_sessions ls
+             JOIN users u ON u.id = ls.user_id
+             WHERE ls.token_hash = $1 AND ls.expires_at > NOW()",
+            token_hash
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    pub async fn delete_login_session(&self, token_hash: &[u8]) -> Result<()> {
+        sqlx::query!("DELETE FROM login_sessions WHERE token_hash = $1", token_hash)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    // Workspace operations
+    pub async fn get_workspaces(&self, user_id: Uuid) -> Result<Vec<Workspace>> {
+        let rows = sqlx::query_as!(
+            WorkspaceRow,
+            "SELECT w.id, w.name, m.role, w.currency, w.created_at
+             FROM workspaces w
+             JOIN workspace_members m ON m.workspace_id = w.id
+             WHERE m.user_id = $1
+             ORDER BY m.joined_at, w.id",
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
+
+    /// The workspace `workspace_id` with the user's role in it, or the first
+    /// workspace they joined if no id is given. Returns `None` if the user is
+    /// not a member.
+    pub async fn get_membership(&self, user_id: Uuid, workspace_id: Option<Uuid>) -> Result<Option<Workspace>> {
+        let row = sqlx::query_as!(
+            WorkspaceRow,
+            "SELECT w.id, w.name, m.role, w.currency, w.created_at
+             FROM workspaces w
+             JOIN workspace_members m ON m.workspace_id = w.id
+             WHERE m.user_id = $1 AND ($2::uuid IS NULL OR w.id = $2)
+             ORDER BY m.joined_at, w.id
+             LIMIT 1",
+            user_id,
+            workspace_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(TryInto::try_into).transpose()
+    }
+
+    /// Creates a workspace owned by `user_id`.
+    pub async fn create_workspace(&self, user_id: Uuid, name: &str, currency: &str) -> Result<Workspace> {
+        let mut tx = self.pool.begin().await?;
+        let workspace = self.insert_workspace(&mut tx, user_id, name, currency).await?;
+        tx.commit().await?;
+        Ok(workspace)
+    }
+
+    pub async fn update_workspace(&self, workspace: &Workspace, name: &str, currency: &str) -> Result<Workspace> {
+        sqlx::query!(
+            "UPDATE workspaces SET name = $2, currency = $3 WHERE id = $1",
+            workspace.id,
+            name,
+            currency
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(Workspace {
+            name: name.to_string(),
+            currency: currency.to_string(),
+            ..workspace.clone()
+        })
+    }
+
+    /// Deletes a workspace with all of its sessions and tags.
+    pub async fn delete_workspace(&self, workspace_id: Uuid) -> Result<()> {
+        sqlx::query!("DELETE FROM workspaces WHERE id = $1", workspace_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_members(&self, workspace_id: Uuid) -> Result<Vec<WorkspaceMember>> {
+        let rows = sqlx::query_as!(
+            MemberRow,
+            "SELECT m.user_id, u.email, m.role, m.joined_at
+             FROM workspace_members m
+             JOIN users u ON u.id = m.user_id
+             WHERE m.workspace_id = $1
+             ORDER BY m.joined_at, u.email",
+            workspace_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
+
+    pub async fn get_member(&self, workspace_id: Uuid, user_id: Uuid) -> Result<Option<WorkspaceMember>> {
+        let row = sqlx::query_as!(
+            MemberRow,
+            "SELECT m.user_id, u.email, m.role, m.joined_at
+             FROM workspace_members m
+             JOIN users u ON u.id = m.user_id
+             WHERE m.workspace_id = $1 AND m.user_id = $2",
+            workspace_id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(TryInto::try_into).transpose()
+    }
+
+    /// Adds the account with this email to a workspace.
+    pub async fn add_member(&self, workspace_id: Uuid, email: &str, role: WorkspaceRole) -> Result<WorkspaceMember> {
+        let user_id = sqlx::query_scalar!("SELECT id FROM users WHERE lower(email) = lower($1)", email)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| ValidationErrors::single("email", "does not belong to an account"))?;
+
+        let inserted = sqlx::query!(
+            "INSERT INTO workspace_members (workspace_id, user_id, role, joined_at) VALUES ($1, $2, $3, $4)",
+            workspace_id,
+            user_id,
+            role.as_str(),
+            Utc::now()
+        )
+        .execute(&self.pool)
+        .await;
+
+        match inserted {
+            Err(sqlx::Error::Database(e)) if e.constraint() == Some("workspace_members_pkey") => {
+                return Err(Conflict {
+                    message: format!("{email} is already a member"),
+                    existing_id: Some(user_id),
+                }
+                .into());
+            }
+            other => {
+                other?;
+            }
+        }
+
+        self.get_member(workspace_id, user_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Member {user_id} vanished after being added"))
+    }
+
+    /// Changes a member's role. Returns `None` if they are not a member.
+    pub async fn update_member_role(
+        &self,
+        workspace_id: Uuid,
+        user_id: Uuid,
+        role: WorkspaceRole,
+    ) -> Result<Option<WorkspaceMember>> {
+        let mut tx = self.pool.begin().await?;
+        if role != WorkspaceRole::Owner {
+            self.check_not_last_owner(&mut tx, workspace_id, user_id).await?;
+        }
+
+        let updated = sqlx::query!(
+            "UPDATE workspace_members SET role = $3 WHERE workspace_id = $1 AND user_id = $2",
+            workspace_id,
+            user_id,
+            role.as_str()
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        if updated.rows_affected() == 0 {
+            return Ok(None);
+        }
+        self.get_member(workspace_id, user_id).await
+    }
+
+    /// Removes a member. Their sessions stay in the workspace.
+    pub async fn remove_member(&self, workspace_id: Uuid, user_id: Uuid) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        self.check_not_last_owner(&mut tx, workspace_id, user_id).await?;
+
+        let result = sqlx::query!(
+            "DELETE FROM workspace_members WHERE workspace_id = $1 AND user_id = $2",
+            workspace_id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // API token operations
+    pub async fn create_api_token(
+        &self,
+        user_id: Uuid,
+        req: &CreateApiTokenRequest,
+        token_hash: &[u8],
+    ) -> Result<ApiToken> {
+        let token = sqlx::query_as!(
+            ApiToken,
+            "INSERT INTO api_tokens (id, user_id, name, token_hash, read_only, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING id, name, read_only, created_at, last_used_at",
+            Uuid::new_v4(),
+            user_id,
+            req.name.trim(),
+            token_hash,
+            req.read_only,
+            Utc::now()
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    pub async fn get_api_tokens(&self, user_id: Uuid) -> Result<Vec<ApiToken>> {
+        let tokens = sqlx::query_as!(
+            ApiToken,
+            "SELECT id, name, read_only, created_at, last_used_at
+             FROM api_tokens WHERE user_id = $1 ORDER BY created_at DESC",
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tokens)
+    }
+
+    pub async fn delete_api_token(&self, user_id: Uuid, id: Uuid) -> Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM api_tokens WHERE id = $1 AND user_id = $2",
+            id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The owner of this token and whether it is read-only. Marks the token
+    /// as used.
+    pub async fn get_token_user(&self, token_hash: &[u8]) -> Result<Option<(User, bool)>> {
+        let row = sqlx::query!(
+            "UPDATE api_tokens t SET last_used_at = NOW()
+             FROM users u
+             WHERE t.token_hash = $1 AND u.id = t.user_id
+             RETURNING u.id, u.email, u.created_at, t.read_only",
+            token_hash
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| {
+            let user = User {
+                id: row.id,
+                email: row.email,
+                created_at: row.created_at,
+            };
+            (user, row.read_only)
+        }))
+    }
+
+    // Report operations
+    pub async fn get_report(&self, workspace: &Workspace, query: ReportQuery) -> Result<Report> {
+        let workspace_id = workspace.id;
+        let currency = workspace.currency.clone();
+        let timezone = query.timezone.unwrap_or_else(|| "UTC".to_string());
+        if query.to < query.from {
+            return Err(InvalidInput("to must not be before from".to_string()).into());
+        }
+
+        self.check_timezone(&timezone).await?;
+
+        let period = query.period.map(|p| p.as_str());
+
+        let rows = sqlx::query_as!(
+            ReportRow,
+            r#"SELECT
+                   CASE WHEN $3::text IS NULL THEN NULL
+                        ELSE date_trunc($3, s.started_at AT TIME ZONE $4)::date
+                   END AS period_start,
+                   CASE WHEN $5 THEN t.id END AS tag_id,
+                   CASE WHEN $5 THEN t.name END AS tag_name,
+                   CASE WHEN $5 THEN t.color END AS tag_color,
+                   SUM(s.duration_seconds)::BIGINT AS "total_seconds!",
+                   COUNT(DISTINCT s.id) AS "session_count!",
+                   COALESCE(SUM(s.duration_seconds) FILTER (WHERE s.billable), 0)::BIGINT AS "billable_seconds!",
+                   COALESCE(SUM(b.amount), 0) AS "amount!"
+               FROM work_sessions s
+               JOIN session_billing b ON b.session_id = s.id
+               LEFT JOIN session_tags st ON $5 AND st.session_id = s.id
+               LEFT JOIN tags t ON t.id = st.tag_id
+               WHERE s.workspace_id = $6
+                 AND s.ended_at IS NOT NULL
+                 AND s.started_at >= ($1::date::timestamp AT TIME ZONE $4)
+                 AND s.started_at < (($2::date + 1)::timestamp AT TIME ZONE $4)
+               GROUP BY 1, 2, 3, 4
+               ORDER BY 1 NULLS FIRST, 3 NULLS LAST"#,
+            query.from,
+            query.to,
+            period,
+            timezone,
+            query.by_tag,
+            workspace_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let totals = sqlx::query!(
+            r#"SELECT COALESCE(SUM(s.duration_seconds), 0)::BIGINT AS "total_seconds!",
+                      COALESCE(SUM(s.duration_seconds) FILTER (WHERE s.billable), 0)::BIGINT AS "billable_seconds!",
+                      COALESCE(SUM(b.amount), 0) AS "amount!"
+               FROM work_sessions s
+               JOIN session_billing b ON b.session_id = s.id
+               WHERE s.workspace_id = $4
+                 AND s.ended_at IS NOT NULL
+                 AND s.started_at >= ($1::date::timestamp AT TIME ZONE $3)
+                 AND s.started_at < (($2::date + 1)::timestamp AT TIME ZONE $3)"#,
+            query.from,
+            query.to,
+            timezone,
+            workspace_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(Report {
+            from: query.from,
+            to: query.to,
+            timezone,
+            period: query.period,
+            by_tag: query.by_tag,
+            total_seconds: totals.total_seconds,
+            billable_seconds: totals.billable_seconds,
+            amount: totals.amount,
+            currency,
+            rows,
+        })
+    }
+
+    // Invoice operations
+    /// Invoices the matching billable sessions and locks them. Fails if no
+    /// session matches.
+    pub async fn create_invoice(&self, workspace: &Workspace, user_id: Uuid, req: CreateInvoiceRequest) -> Result<Invoice> {
+        let timezone = req.timezone.unwrap_or_else(|| "UTC".to_string());
+        self.check_timezone(&timezone).await?;
+
+        let mut tx = self.pool.begin().await?;
+
+        let client_name = match req.client_id {
+            Some(client_id) => {
+                let name = sqlx::query_scalar!(
+                    "SELECT name FROM clients WHERE id = $1 AND workspace_id = $2",
+                    client_id,
+                    workspace.id
+                )
+                .fetch_optional(&mut *tx)
+                .await?;
+                match name {
+                    Some(name) => Some(name),
+                    None => {
+                        return Err(ValidationErrors::single("client_id", "is not a client in this workspace").into());
+                    }
+                }
+            }
+            None => None,
+        };
+        if let Some(tag_id) = req.tag_id {
+            let found = sqlx::query_scalar!(
+                "SELECT id FROM tags WHERE id = $1 AND workspace_id = $2",
+                tag_id,
+                workspace.id
+            )
+            .fetch_optional(&mut *tx)
+            .await?;
+            if found.is_none() {
+                return Err(ValidationErrors::single("tag_id", "is not a tag in this workspace").into());
+            }
+        }
+
+        // Numbers are handed out one at a time per workspace
+        sqlx::query!("SELECT id FROM workspaces WHERE id = $1 FOR UPDATE", workspace.id)
+            .fetch_one(&mut *tx)
+            .await?;
+        let number = sqlx::query_scalar!(
+            r#"SELECT COALESCE(MAX(number), 0) + 1 AS "number!" FROM invoices WHERE workspace_id = $1"#,
+            workspace.id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let invoice_id = Uuid::new_v4();
+        sqlx::query!(
+            "INSERT INTO invoices (id, workspace_id, number, client_id, client_name, tag_id, from_date, to_date, group_by, currency, total, created_by, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, 0, $11, $12)",
+            invoice_id,
+            workspace.id,
+            number,
+            req.client_id,
+            client_name,
+            req.tag_id,
+            req.from,
+            req.to,
+            req.group_by.as_str(),
+            workspace.currency,
+            user_id,
+            Utc::now()
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // Claiming the sessions locks them, so no session is invoiced twice
+        let claimed = sqlx::query!(
+            "UPDATE work_sessions s SET invoice_id = $1
+             FROM session_billing b
+             WHERE b.session_id = s.id
+               AND s.workspace_id = $2
+               AND s.billable AND s.ended_at IS NOT NULL AND s.invoice_id IS NULL AND b.amount IS NOT NULL
+               AND s.started_at >= ($3::date::timestamp AT TIME ZONE $5)
+               AND s.started_at < (($4::date + 1)::timestamp AT TIME ZONE $5)
+               AND ($6::uuid IS NULL OR s.project_id IN (SELECT id FROM projects WHERE client_id = $6))
+               AND ($7::uuid IS NULL OR EXISTS (
+                       SELECT 1 FROM session_tags st WHERE st.session_id = s.id AND st.tag_id = $7))",
+            invoice_id,
+            workspace.id,
+            req.from,
+            req.to,
+            timezone,
+            req.client_id,
+            req.tag_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        if claimed.rows_affected() == 0 {
+            return Err(InvalidInput("No uninvoiced billable sessions with a rate match".to_string()).into());
+        }
+
+        let lines = sqlx::query_as!(
+            InvoiceLine,
+            r#"SELECT
+                   CASE WHEN $2 = 'day' THEN to_char(s.started_at AT TIME ZONE $3, 'YYYY-MM-DD')
+                        ELSE COALESCE(
+                            (SELECT t.name FROM session_tags st
+                             JOIN tags t ON t.id = st.tag_id
+                             WHERE st.session_id = s.id AND ($4::uuid IS NULL OR t.id = $4)
+                             ORDER BY t.hourly_rate DESC NULLS LAST, t.name
+                             LIMIT 1),
+                            'Untagged')
+                   END AS "description!",
+                   SUM(s.duration_seconds)::BIGINT AS "seconds!",
+                   COUNT(*) AS "session_count!",
+                   SUM(b.amount) AS "amount!"
+               FROM work_sessions s
+               JOIN session_billing b ON b.session_id = s.id
+               WHERE s.invoice_id = $1
+               GROUP BY 1
+               ORDER BY 1"#,
+            invoice_id,
+            req.group_by.as_str(),
+            timezone,
+            req.tag_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for (position, line) in lines.iter().enumerate() {
+            sqlx::query!(
+                "INSERT INTO invoice_lines (invoice_id, position, description, seconds, session_count, amount)
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+                invoice_id,
+                position as i32,
+                line.description,
+                line.seconds,
+                line.session_count,
+                line.amount
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let total: Decimal = lines.iter().map(|l| l.amount).sum();
+        let row = sqlx::query_as!(
+            InvoiceRow,
+            "UPDATE invoices SET total = $2 WHERE id = $1
+             RETURNING id, number, client_id, client_name, tag_id, from_date, to_date, group_by, currency, total, created_at",
+            invoice_id,
+            total
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        row.into_invoice(lines)
+    }
+
+    pub async fn get_invoices(&self, workspace_id: Uuid) -> Result<Vec<Invoice>> {
+        let rows = sqlx::query_as!(
+            InvoiceRow,
+            "SELECT id, number, client_id, client_name, tag_id, from_date, to_date, group_by, currency, total, created_at
+             FROM invoices WHERE workspace_id = $1
+             ORDER BY number DESC",
+            workspace_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let ids: Vec<Uuid> = rows.iter().map(|r| r.id).collect();
+        let line_rows = sqlx::query!(
+            "SELECT invoice_id, description, seconds, session_count, amount
+             FROM invoice_lines WHERE invoice_id = ANY($1)
+             ORDER BY position",
+            &ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut lines: HashMap<Uuid, Vec<InvoiceLine>> = HashMap::new();
+        for line in line_rows {
+            lines.entry(line.invoice_id).or_default().push(InvoiceLine {
+                description: line.description,
+                seconds: line.seconds,
+                session_count: line.session_count,
+                amount: line.amount,
+            });
+        }
+
+        rows.into_iter()
+            .map(|row| {
+                let invoice_lines = lines.remove(&row.id).unwrap_or_default();
+                row.into_invoice(invoice_lines)
+            })
+            .collect()
+    }
+
+    pub async fn get_invoice(&self, workspace_id: Uuid, id: Uuid) -> Result<Option<Invoice>> {
+        let row = sqlx::query_as!(
+            InvoiceRow,
+            "SELECT id, number, client_id, client_name, tag_id, from_date, to_date, group_by, currency, total, created_at
+             FROM invoices WHERE id = $1 AND workspace_id = $2",
+            id,
+            workspace_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let lines = sqlx::query_as!(
+            InvoiceLine,
+            "SELECT description, seconds, session_count, amount
+             FROM invoice_lines WHERE invoice_id = $1
+             ORDER BY position",
+            id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        row.into_invoice(lines).map(Some)
+    }
+
+    /// Deletes an invoice, unlocking its sessions.
+    pub async fn delete_invoice(&self, workspace_id: Uuid, id: Uuid) -> Result<bool> {
+        let result = sqlx::query!("DELETE FROM invoices WHERE id = $1 AND workspace_id = $2", id, workspace_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // Webhook operations
+    pub async fn create_webhook(
+        &self,
+        workspace_id: Uuid,
+        user_id: Uuid,
+        req: &CreateWebhookRequest,
+        secret: &str,
+    ) -> Result<Webhook> {
+        let mut events: Vec<&str> = req.events.iter().map(WebhookEvent::as_str).collect();
+        events.sort();
+        events.dedup();
+
+        let webhook = sqlx::query_as!(
+            WebhookRow,
+            "INSERT INTO webhooks (id, workspace_id, url, secret, events, created_by, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING id, url, events, created_at",
+            Uuid::new_v4(),
+            workspace_id,
+            req.url.trim(),
+            secret,
+            &events as &[&str],
+            user_id,
+            Utc::now()
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        webhook.try_into()
+    }
+
+    pub async fn get_webhooks(&self, workspace_id: Uuid) -> Result<Vec<Webhook>> {
+        let webhooks = sqlx::query_as!(
+            WebhookRow,
+            "SELECT id, url, events, created_at FROM webhooks WHERE workspace_id = $1 ORDER BY created_at",
+            workspace_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        webhooks.into_iter().map(TryInto::try_into).collect()
+    }
+
+    /// Deletes a webhook along with its delivery log.
+    pub async fn delete_webhook(&self, workspace_id: Uuid, id: Uuid) -> Result<bool> {
+        let result = sqlx::query!("DELETE FROM webhooks WHERE id = $1 AND workspace_id = $2", id, workspace_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The webhook's most recent deliveries, newest first. Returns `None` if
+    /// the webhook does not exist.
+    pub async fn get_webhook_deliveries(&self, workspace_id: Uuid, id: Uuid) -> Result<Option<Vec<WebhookDelivery>>> {
+        if !self.webhook_exists(workspace_id, id).await? {
+            return Ok(None);
+        }
+
+        let deliveries = sqlx::query_as!(
+            DeliveryRow,
+            "SELECT id, event, status, attempts, next_attempt_at, response_status, last_error, created_at, delivered_at
+             FROM webhook_deliveries WHERE webhook_id = $1
+             ORDER BY created_at DESC LIMIT 100",
+            id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        deliveries.into_iter().map(TryInto::try_into).collect::<Result<_>>().map(Some)
+    }
+
+    /// Queues a `ping` to check a webhook. Returns `None` if it does not exist.
+    pub async fn queue_ping(&self, workspace_id: Uuid, id: Uuid) -> Result<Option<WebhookDelivery>> {
+        if !self.webhook_exists(workspace_id, id).await? {
+            return Ok(None);
+        }
+
+        let delivery_id = Uuid::new_v4();
+        let payload = webhook_payload(delivery_id, WebhookEvent::Ping, workspace_id, serde_json::json!({ "webhook_id": id }));
+        let delivery = sqlx::query_as!(
+            DeliveryRow,
+            "INSERT INTO webhook_deliveries (id, webhook_id, event, payload, next_attempt_at, created_at)
+             VALUES ($1, $2, $3, $4, $5, $5)
+             RETURNING id, event, status, attempts, next_attempt_at, response_status, last_error, created_at, delivered_at",
+            delivery_id,
+            id,
+            WebhookEvent::Ping.as_str(),
+            payload,
+            Utc::now()
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        delivery.try_into().map(Some)
+    }
+
+    /// Claims up to `limit` deliveries that are due, reserving them until
+    /// `lease_until` so no other worker sends them meanwhile.
+    pub async fn claim_due_deliveries(&self, limit: i64, lease_until: DateTime<Utc>) -> Result<Vec<DueDelivery>> {
+        let deliveries = sqlx::query_as!(
+            DueDelivery,
+            "UPDATE webhook_deliveries d SET next_attempt_at = $2
+             FROM webhooks w
+             WHERE w.id = d.webhook_id AND d.id IN (
+                 SELECT id FROM webhook_deliveries
+                 WHERE status = 'pending' AND next_attempt_at <= NOW()
+                 ORDER BY next_attempt_at
+                 LIMIT $1
+                 FOR UPDATE SKIP LOCKED
+             )
+             RETURNING d.id, w.url, w.secret, d.event, d.payload, d.attempts",
+            limit,
+            lease_until
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(deliveries)
+    }
+
+    pub async fn record_delivery_success(&self, id: Uuid, response_status: i32) -> Result<()> {
+        sqlx::query!(
+            "UPDATE webhook_deliveries
+             SET status = 'succeeded', attempts = attempts + 1, next_attempt_at = NULL,
+                 response_status = $2, last_error = NULL, delivered_at = NOW()
+             WHERE id = $1",
+            id,
+            response_status
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Records a failed attempt, to be retried at `retry_at`. Without one the
+    /// delivery is given up on.
+    pub async fn record_delivery_failure(
+        &self,
+        id: Uuid,
+        response_status: Option<i32>,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        sqlx::query!(
+            "UPDATE webhook_deliveries
+             SET status = CASE WHEN $4::timestamptz IS NULL THEN 'failed' ELSE 'pending' END,
+                 attempts = attempts + 1, next_attempt_at = $4, response_status = $2, last_error = $3
+             WHERE id = $1",
+            id,
+            response_status,
+            error,
+            retry_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Checks that the database answers.
+    pub async fn ping(&self) -> Result<()> {
+        sqlx::query!("SELECT 1 AS one").fetch_one(&self.pool).await?;
+        Ok(())
+    }
+
+    // Change notifications
+
+    /// Sends `payload` to every connection listening on `channel`.
+    pub async fn notify(&self, channel: &str, payload: &str) -> Result<()> {
+        sqlx::query!("SELECT pg_notify($1, $2)", channel, payload)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Opens a dedicated connection listening on `channel`.
+    pub async fn listen(&self, channel: &str) -> Result<PgListener> {
+        let mut listener = PgListener::connect_with(&self.pool).await?;
+        listener.listen(channel).await?;
+
+        Ok(listener)
+    }
+
+    // Helper methods
+    /// Inserts a finished session and its tags as part of `tx`.
+    async fn insert_session(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        workspace_id: Uuid,
+        user_id: Uuid,
+        req: CreateSessionRequest,
+    ) -> Result<WorkSession> {
+        let session_id = Uuid::new_v4();
+        let now = Utc::now();
+        let (started_at, ended_at, duration_seconds) =
+            resolve_bounds(req.started_at, req.ended_at, req.duration_seconds, now)?;
+        self.check_tags_exist(tx, workspace_id, &req.tag_ids).await?;
+        self.check_project_exists(tx, workspace_id, req.project_id).await?;
+
+        // Insert session
+        sqlx::query!(
+            "INSERT INTO work_sessions (id, workspace_id, user_id, duration_seconds, description, started_at, ended_at, created_at, updated_at, project_id, billable, hourly_rate) 
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+            session_id,
+            workspace_id,
+            user_id,
+            duration_seconds,
+            req.description,
+            started_at,
+            ended_at,
+            now,
+            now,
+            req.project_id,
+            req.billable,
+            req.hourly_rate
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        // Insert session tags
+        for tag_id in &req.tag_ids {
+            sqlx::query!(
+                "INSERT INTO session_tags (session_id, tag_id) VALUES ($1, $2)",
+                session_id,
+                tag_id
+            )
+            .execute(&mut **tx)
+            .await?;
+        }
+        self.queue_session_event(tx, workspace_id, WebhookEvent::SessionCreated, session_id).await?;
+
+        Ok(WorkSession {
+            id: session_id,
+            duration_seconds,
+            description: req.description,
+            started_at,
+            ended_at: Some(ended_at),
+            created_at: now,
+            updated_at: now,
+            project_id: req.project_id,
+            billable: req.billable,
+            hourly_rate: req.hourly_rate,
+        })
+    }
+
+    /// Creates a workspace with `user_id` as its owner as part of `tx`.
+    async fn insert_workspace(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        user_id: Uuid,
+        name: &str,
+        currency: &str,
+    ) -> Result<Workspace> {
+        let workspace_id = Uuid::new_v4();
+        let now = Utc::now();
+
+        sqlx::query!(
+            "INSERT INTO workspaces (id, name, currency, created_at) VALUES ($1, $2, $3, $4)",
+            workspace_id,
+            name,
+            currency,
+            now
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query!(
+            "INSERT INTO workspace_members (workspace_id, user_id, role, joined_at) VALUES ($1, $2, $3, $4)",
+            workspace_id,
+            user_id,
+            WorkspaceRole::Owner.as_str(),
+            now
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(Workspace {
+            id: workspace_id,
+            name: name.to_string(),
+            role: WorkspaceRole::Owner,
+            currency: currency.to_string(),
+            created_at: now,
+        })
+    }
+
+    /// Rejects demoting or removing `user_id` if they are the workspace's
+    /// only owner. Locks the workspace's owners for the rest of `tx`.
+    async fn check_not_last_owner(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        workspace_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<()> {
+        let owners = sqlx::query_scalar!(
+            "SELECT user_id FROM workspace_members
+             WHERE workspace_id = $1 AND role = 'owner'
+             FOR UPDATE",
+            workspace_id
+        )
+        .fetch_all(&mut **tx)
+        .await?;
+
+        if owners == [user_id] {
+            return Err(InvalidInput("A workspace needs at least one owner".to_string()).into());
+        }
+        Ok(())
+    }
+
+    /// Looks up the tag whose name clashed with `name`.
+    async fn tag_name_conflict(&self, workspace_id: Uuid, name: &str) -> Result<Conflict> {
+        let existing_id = sqlx::query_scalar!(
+            "SELECT id FROM tags WHERE workspace_id = $1 AND lower(btrim(name)) = lower($2)",
+            workspace_id,
+            name
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(Conflict {
+            message: format!("A tag named {name:?} already exists"),
+            existing_id: Some(existing_id),
+        })
+    }
+
+    /// Looks up the client whose name clashed with `name`.
+    async fn client_name_conflict(&self, workspace_id: Uuid, name: &str) -> Result<Conflict> {
+        let existing_id = sqlx::query_scalar!(
+            "SELECT id FROM clients WHERE workspace_id = $1 AND lower(btrim(name)) = lower($2)",
+            workspace_id,
+            name
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(Conflict {
+            message: format!("A client named {name:?} already exists"),
+            existing_id: Some(existing_id),
+        })
+    }
+
+    /// Looks up the project of `client_id` whose name clashed with `name`.
+    async fn project_name_conflict(&self, client_id: Uuid, name: &str) -> Result<Conflict> {
+        let existing_id = sqlx::query_scalar!(
+            "SELECT id FROM projects WHERE client_id = $1 AND lower(btrim(name)) = lower($2)",
+            client_id,
+            name
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(Conflict {
+            message: format!("This client already has a project named {name:?}"),
+            existing_id: Some(existing_id),
+        })
+    }
+
+    /// Rejects a client id that does not belong to one of the workspace's
+    /// clients.
+    async fn check_client_exists(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        workspace_id: Uuid,
+        client_id: Uuid,
+    ) -> Result<()> {
+        let found = sqlx::query_scalar!(
+            "SELECT id FROM clients WHERE id = $1 AND workspace_id = $2",
+            client_id,
+            workspace_id
+        )
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        if found.is_none() {
+            return Err(ValidationErrors::single("client_id", "is not a client in this workspace").into());
+        }
+        Ok(())
+    }
+
+    /// Rejects a project id that does not belong to one of the workspace's
+    /// projects.
+    async fn check_project_exists(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        workspace_id: Uuid,
+        project_id: Option<Uuid>,
+    ) -> Result<()> {
+        let Some(project_id) = project_id else {
+            return Ok(());
+        };
+
+        let found = sqlx::query_scalar!(
+            "SELECT p.id FROM projects p JOIN clients c ON c.id = p.client_id
+             WHERE p.id = $1 AND c.workspace_id = $2",
+            project_id,
+            workspace_id
+        )
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        if found.is_none() {
+            return Err(ValidationErrors::single("project_id", "is not a project in this workspace").into());
+        }
+        Ok(())
+    }
+
+    /// Rejects tag ids that do not belong to one of the workspace's tags.
+    async fn check_tags_exist(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        workspace_id: Uuid,
+        tag_ids: &[Uuid],
+    ) -> Result<()> {
+        if tag_ids.is_empty() {
+            return Ok(());
+        }
+
+        let found = sqlx::query_scalar!(
+            "SELECT id FROM tags WHERE id = ANY($1) AND workspace_id = $2",
+            tag_ids,
+            workspace_id
+        )
+        .fetch_all(&mut **tx)
+        .await?;
+        let missing: Vec<String> = tag_ids
+            .iter()
+            .filter(|id| !found.contains(id))
+            .map(Uuid::to_string)
+            .collect();
+
+        if !missing.is_empty() {
+            return Err(
+                ValidationErrors::single("tag_ids", format!("contains unknown tags: {}", missing.join(", "))).into(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Rejects changes to a session that has been invoiced.
+    async fn check_not_invoiced(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        workspace_id: Uuid,
+        id: Uuid,
+    ) -> Result<()> {
+        let invoice = sqlx::query!(
+            "SELECT i.id, i.number FROM work_sessions s
+             JOIN invoices i ON i.id = s.invoice_id
+             WHERE s.id = $1 AND s.workspace_id = $2",
+            id,
+            workspace_id
+        )
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        match invoice {
+            Some(invoice) => Err(Conflict {
+                message: format!("Session is on invoice #{} and cannot be changed", invoice.number),
+                existing_id: Some(invoice.id),
+            }
+            .into()),
+            None => Ok(()),
+        }
+    }
+
+    async fn check_timezone(&self, timezone: &str) -> Result<()> {
+        let known_timezone = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM pg_timezone_names WHERE name = $1) AS "exists!""#,
+            timezone
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        if !known_timezone {
+            return Err(InvalidInput(format!("Unknown timezone: {timezone}")).into());
+        }
+        Ok(())
+    }
+
+    async fn webhook_exists(&self, workspace_id: Uuid, id: Uuid) -> Result<bool> {
+        let webhook = sqlx::query_scalar!("SELECT id FROM webhooks WHERE id = $1 AND workspace_id = $2", id, workspace_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(webhook.is_some())
+    }
+
+    /// Queues `event` for the workspace's webhooks that subscribe to it, as
+    /// part of `tx` so it is only sent if the change is committed.
+    async fn queue_session_event(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        workspace_id: Uuid,
+        event: WebhookEvent,
+        session_id: Uuid,
+    ) -> Result<()> {
+        let webhook_ids = self.subscribed_webhooks(tx, workspace_id, event).await?;
+        if webhook_ids.is_empty() {
+            return Ok(());
+        }
+        match self.session_payload(tx, workspace_id, session_id).await? {
+            Some(data) => self.queue_deliveries(tx, workspace_id, event, &webhook_ids, data).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn subscribed_webhooks(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        workspace_id: Uuid,
+        event: WebhookEvent,
+    ) -> Result<Vec<Uuid>> {
+        let webhook_ids = sqlx::query_scalar!(
+            "SELECT id FROM webhooks WHERE workspace_id = $1 AND $2 = ANY(events)",
+            workspace_id,
+            event.as_str()
+        )
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Ok(webhook_ids)
+    }
+
+    /// The session as webhooks report it, or `None` if it does not exist.
+    async fn session_payload(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        workspace_id: Uuid,
+        session_id: Uuid,
+    ) -> Result<Option<serde_json::Value>> {
+        let session = sqlx::query_as!(
+            SessionDetailsRow,
+            r#"SELECT id AS "id!", duration_seconds AS "duration_seconds!", description, 
+                      started_at AS "started_at!", ended_at, created_at AS "created_at!", updated_at AS "updated_at!", user_id AS "user_id!", project_id,
+                      billable AS "billable!", hourly_rate, effective_rate, amount, currency AS "currency!",
+                      tags AS "tags!: Json<Vec<Tag>>", segments AS "segments!: Json<Vec<SessionSegment>>"
+               FROM session_details WHERE id = $1 AND workspace_id = $2"#,
+            session_id,
+            workspace_id
+        )
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        session
+            .map(|session| serde_json::to_value(WorkSessionWithTags::from(session)))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn queue_deliveries(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        workspace_id: Uuid,
+        event: WebhookEvent,
+        webhook_ids: &[Uuid],
+        data: serde_json::Value,
+    ) -> Result<()> {
+        let now = Utc::now();
+        for webhook_id in webhook_ids {
+            let delivery_id = Uuid::new_v4();
+            sqlx::query!(
+                "INSERT INTO webhook_deliveries (id, webhook_id, event, payload, next_attempt_at, created_at)
+                 VALUES ($1, $2, $3, $4, $5, $5)",
+                delivery_id,
+                webhook_id,
+                event.as_str(),
+                webhook_payload(delivery_id, event, workspace_id, data.clone()),
+                now
+            )
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Locks a session that has not been stopped yet. Returns `false` if no
+    /// such session exists.
+    async fn lock_unfinished_session(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        workspace_id: Uuid,
+        id: Uuid,
+    ) -> Result<bool> {
+        let session = sqlx::query!(
+            "SELECT id FROM work_sessions WHERE id = $1 AND workspace_id = $2 AND ended_at IS NULL FOR UPDATE",
+            id,
+            workspace_id
+        )
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        Ok(session.is_some())
+    }
+}
+
+/// Escapes LIKE wildcards so a search matches the text literally.
+fn escape_like(search: &str) -> String {
+    search
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Works out a finished session's start, end and duration from whichever of
+/// them the client supplied. The duration defaults to the full span and may
+/// be shorter than it, but never longer.
+fn resolve_bounds(
+    started_at: Option<DateTime<Utc>>,
+    ended_at: Option<DateTime<Utc>>,
+    duration_seconds: Option<i32>,
+    now: DateTime<Utc>,
+) -> Result<(DateTime<Utc>, DateTime<Utc>, i32)> {
+    if duration_seconds.is_some_and(|d| d < 0) {
+        return Err(InvalidInput("duration_seconds must not be negative".to_string()).into());
+    }
+    let duration = duration_seconds.map(|d| Duration::seconds(d.into()));
+
+    let (started_at, ended_at) = match (started_at, ended_at, duration) {
+        (Some(started_at), Some(ended_at), _) => (started_at, ended_at),
+        (Some(started_at), None, Some(duration)) => (started_at, started_at + duration),
+        (Some(started_at), None, None) => (started_at, now),
+        (None, Some(ended_at), Some(duration)) => (ended_at - duration, ended_at),
+        (None, None, Some(duration)) => (now - duration, now),
+        (None, _, None) => {
+            return Err(InvalidInput(
+                "started_at or duration_seconds is required".to_string(),
+            )
+            .into());
+        }
+    };
+
+    if ended_at < started_at {
+        return Err(InvalidInput("ended_at must not be before started_at".to_string()).into());
+    }
+
+    let span = i32::try_from((ended_at - started_at).num_seconds())
+        .map_err(|_| InvalidInput("Session is too long".to_string()))?;
+    let duration_seconds = duration_seconds.unwrap_or(span);
+    if duration_seconds > span {
+        return Err(InvalidInput(
+            "duration_seconds must not exceed the time between started_at and ended_at".to_string(),
+        )
+        .into());
+    }
+
+    Ok((started_at, ended_at, duration_seconds))
+}
+
+/// The body posted for a webhook delivery.
+fn webhook_payload(id: Uuid, event: WebhookEvent, workspace_id: Uuid, data: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "event": event,
+        "workspace_id": workspace_id,
+        "created_at": Utc::now(),
+        "data": data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::Future;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+    /// Counts the statements sqlx runs, which it reports as `sqlx::query` events.
+    struct QueryCounter(Arc<AtomicUsize>);
+
+    impl<S: tracing::Subscriber> Layer<S> for QueryCounter {
+        fn on_event(&self, event: &tracing::Event<'_>, _: Context<'_, S>) {
+            if event.metadata().target() == "sqlx::query" {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Runs `future`, returning its output with the number of statements it ran.
+    async fn count_queries<T>(future: impl Future<Output = T>) -> (T, usize) {
+        let count = Arc::new(AtomicUsize::new(0));
+        let subscriber = tracing_subscriber::registry().with(QueryCounter(count.clone()));
+        let output = {
+            let _guard = tracing::subscriber::set_default(subscriber);
+            future.await
+        };
+        (output, count.load(Ordering::Relaxed))
+    }
+
+    /// A new user and their personal workspace.
+    async fn personal_workspace(db: &Database, email: &str) -> Result<(Uuid, Uuid)> {
+        let user = db.create_user(email, "not a real hash").await?;
+        let workspace = db.get_workspaces(user.id).await?.remove(0);
+        Ok((workspace.id, user.id))
+    }
+
+    async fn create_tag(db: &Database, workspace_id: Uuid, user_id: Uuid, name: &str) -> Result<Tag> {
+        let req = CreateTagRequest {
+            name: name.to_string(),
+            color: Some("#3B82F6".to_string()),
+            hourly_rate: Some(Decimal::new(5000, 2)),
+        };
+        db.create_tag(workspace_id, user_id, req).await
+    }
+
+    /// Tracks a session with two segments by starting, pausing, resuming and stopping it.
+    async fn track_session(db: &Database, workspace_id: Uuid, user_id: Uuid, tag_ids: &[Uuid]) -> Result<Uuid> {
+        let req = StartSessionRequest {
+            description: Some("Tracked".to_string()),
+            tag_ids: tag_ids.to_vec(),
+            project_id: None,
+            billable: false,
+            hourly_rate: None,
+        };
+        let session = db.start_session(workspace_id, user_id, req).await?.expect("no session is running");
+        db.pause_session(workspace_id, session.id).await?;
+        db.resume_session(workspace_id, session.id).await?;
+        db.stop_session(workspace_id, session.id).await?;
+        Ok(session.id)
+    }
+
+    /// A finished session of `minutes` starting at the RFC 3339 time `started_at`.
+    fn logged(started_at: &str, minutes: i64) -> CreateSessionRequest {
+        let started_at = DateTime::parse_from_rfc3339(started_at).unwrap().with_timezone(&Utc);
+        CreateSessionRequest {
+            duration_seconds: None,
+            description: Some("Logged".to_string()),
+            started_at: Some(started_at),
+            ended_at: Some(started_at + Duration::minutes(minutes)),
+            tag_ids: Vec::new(),
+            project_id: None,
+            billable: false,
+            hourly_rate: None,
+        }
+    }
+
+    async fn log_session(db: &Database, workspace_id: Uuid, user_id: Uuid, started_at: &str, minutes: i64) -> Result<Uuid> {
+        Ok(db.create_session(workspace_id, user_id, logged(started_at, minutes)).await?.id)
+    }
+
+    /// Logs a billable session, at `hourly_rate` if given.
+    async fn bill_session(
+        db: &Database,
+        workspace_id: Uuid,
+        user_id: Uuid,
+        req: CreateSessionRequest,
+        hourly_rate: Option<i64>,
+    ) -> Result<Uuid> {
+        let req = CreateSessionRequest {
+            billable: true,
+            hourly_rate: hourly_rate.map(Decimal::from),
+            ..req
+        };
+        Ok(db.create_session(workspace_id, user_id, req).await?.id)
+    }
+
+    fn invoice_request(from: &str, to: &str, timezone: &str) -> CreateInvoiceRequest {
+        CreateInvoiceRequest {
+            client_id: None,
+            tag_id: None,
+            from: from.parse().unwrap(),
+            to: to.parse().unwrap(),
+            timezone: Some(timezone.to_string()),
+            group_by: InvoiceGrouping::Tag,
+        }
+    }
+
+    async fn invoiced_sessions(db: &Database, invoice_id: Uuid) -> Result<Vec<Uuid>> {
+        let mut ids = sqlx::query_scalar::<_, Uuid>("SELECT id FROM work_sessions WHERE invoice_id = $1")
+            .bind(invoice_id)
+            .fetch_all(&db.pool)
+            .await?;
+        ids.sort();
+        Ok(ids)
+    }
+
+    fn report_query(from: &str, to: &str, timezone: &str, period: Option<ReportPeriod>) -> ReportQuery {
+        ReportQuery {
+            from: from.parse().unwrap(),
+            to: to.parse().unwrap(),
+            timezone: Some(timezone.to_string()),
+            period,
+            by_tag: false,
+        }
+    }
+
+    #[sqlx::test]
+    async fn listing_sessions_takes_one_query_however_many_there_are(pool: PgPool) -> Result<()> {
+        let db = Database::new(pool);
+        let (workspace_id, user_id) = personal_workspace(&db, "test@example.com").await?;
+        let tag_ids = [
+            create_tag(&db, workspace_id, user_id, "Backend").await?.id,
+            create_tag(&db, workspace_id, user_id, "Frontend").await?.id,
+        ];
+        let query = SessionQuery {
+            limit: Some(SessionQuery::MAX_LIMIT),
+            ..Default::default()
+        };
+
+        let mut tracked = 0;
+        for total in [1, 5, 25] {
+            while tracked < total {
+                track_session(&db, workspace_id, user_id, &tag_ids).await?;
+                tracked += 1;
+            }
+
+            let (page, queries) = count_queries(db.get_sessions(workspace_id, &query)).await;
+            let page = page?;
+            assert_eq!(page.items.len(), total);
+            assert!(page.items.iter().all(|s| s.tags.len() == 2 && s.segments.len() == 2));
+            assert_eq!(queries, 1, "listing {total} sessions");
+
+            let id = page.items[0].id;
+            let (session, queries) = count_queries(db.get_session(workspace_id, id)).await;
+            assert_eq!(session?.map(|s| (s.tags.len(), s.segments.len())), Some((2, 2)));
+            assert_eq!(queries, 1, "fetching a session among {total}");
+        }
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn session_updates_keep_absent_fields_and_clear_null_ones(pool: PgPool) -> Result<()> {
+        let db = Database::new(pool);
+        let (workspace_id, user_id) = personal_workspace(&db, "test@example.com").await?;
+        let rate = Decimal::new(8550, 2);
+        let req = CreateSessionRequest {
+            duration_seconds: Some(3600),
+            description: Some("Review".to_string()),
+            started_at: None,
+            ended_at: None,
+            tag_ids: Vec::new(),
+            project_id: None,
+            billable: true,
+            hourly_rate: Some(rate),
+        };
+        let id = db.create_session(workspace_id, user_id, req).await?.id;
+
+        let absent = UpdateSessionRequest {
+            billable: FieldUpdate::Set(false),
+            ..Default::default()
+        };
+        let session = db.update_session(workspace_id, id, absent).await?.expect("session exists");
+        assert_eq!(session.description.as_deref(), Some("Review"));
+        assert_eq!(session.hourly_rate, Some(rate));
+        assert!(!session.billable);
+
+        let null = UpdateSessionRequest {
+            description: FieldUpdate::Clear,
+            hourly_rate: FieldUpdate::Clear,
+            ..Default::default()
+        };
+        db.update_session(workspace_id, id, null).await?;
+        let session = db.get_session(workspace_id, id).await?.expect("session exists");
+        assert_eq!(session.description, None);
+        assert_eq!(session.hourly_rate, None);
+        assert_eq!(session.duration_seconds, 3600);
+        assert!(!session.billable);
+
+        let set = UpdateSessionRequest {
+            description: FieldUpdate::Set("Pairing".to_string()),
+            hourly_rate: FieldUpdate::Set(rate),
+            ..Default::default()
+        };
+        let session = db.update_session(workspace_id, id, set).await?.expect("session exists");
+        assert_eq!(session.description.as_deref(), Some("Pairing"));
+        assert_eq!(session.hourly_rate, Some(rate));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn tag_updates_keep_absent_fields_and_clear_null_ones(pool: PgPool) -> Result<()> {
+        let db = Database::new(pool);
+        let (workspace_id, user_id) = personal_workspace(&db, "test@example.com").await?;
+        let tag = create_tag(&db, workspace_id, user_id, "Backend").await?;
+
+        let absent = UpdateTagRequest {
+            name: FieldUpdate::Set("Server".to_string()),
+            ..Default::default()
+        };
+        let updated = db.update_tag(workspace_id, tag.id, absent).await?.expect("tag exists");
+        assert_eq!(updated.name, "Server");
+        assert_eq!(updated.color, tag.color);
+        assert_eq!(updated.hourly_rate, tag.hourly_rate);
+
+        let null = UpdateTagRequest {
+            color: FieldUpdate::Clear,
+            hourly_rate: FieldUpdate::Clear,
+            ..Default::default()
+        };
+        db.update_tag(workspace_id, tag.id, null).await?;
+        let updated = db.get_tag(workspace_id, tag.id).await?.expect("tag exists");
+        assert_eq!(updated.name, "Server");
+        assert_eq!(updated.color, None);
+        assert_eq!(updated.hourly_rate, None);
+
+        let set = UpdateTagRequest {
+            color: FieldUpdate::Set("#10B981".to_string()),
+            hourly_rate: FieldUpdate::Set(Decimal::new(60, 0)),
+            ..Default::default()
+        };
+        let updated = db.update_tag(workspace_id, tag.id, set).await?.expect("tag exists");
+        assert_eq!(updated.color.as_deref(), Some("#10B981"));
+        assert_eq!(updated.hourly_rate, Some(Decimal::new(60, 0)));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn deleting_a_session_reports_it_only_to_its_own_workspace(pool: PgPool) -> Result<()> {
+        let db = Database::new(pool);
+        let (workspace_id, user_id) = personal_workspace(&db, "test@example.com").await?;
+        let (other_workspace_id, other_user_id) = personal_workspace(&db, "other@example.com").await?;
+        let req = CreateWebhookRequest {
+            url: "https://example.com/hook".to_string(),
+            events: vec![WebhookEvent::SessionDeleted],
+            secret: None,
+        };
+        let webhook = db.create_webhook(workspace_id, user_id, &req, "whsec_test").await?;
+        let deliveries = || db.get_webhook_deliveries(workspace_id, webhook.id);
+
+        let other_id = track_session(&db, other_workspace_id, other_user_id, &[]).await?;
+        assert!(!db.delete_session(workspace_id, other_id).await?);
+        assert!(db.get_session(other_workspace_id, other_id).await?.is_some());
+        assert_eq!(deliveries().await?.map(|d| d.len()), Some(0));
+
+        let id = track_session(&db, workspace_id, user_id, &[]).await?;
+        assert!(db.delete_session(workspace_id, id).await?);
+        assert!(!db.delete_session(workspace_id, id).await?);
+        let deliveries = deliveries().await?.expect("webhook exists");
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].event, WebhookEvent::SessionDeleted);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn client_and_project_names_are_trimmed_before_checking_clashes(pool: PgPool) -> Result<()> {
+        let db = Database::new(pool);
+        let (workspace_id, _) = personal_workspace(&db, "test@example.com").await?;
+        let client = db.create_client(workspace_id, CreateClientRequest { name: " Acme ".to_string() }).await?;
+        assert_eq!(client.name, "Acme");
+
+        let clash = db.create_client(workspace_id, CreateClientRequest { name: "acme  ".to_string() }).await;
+        let conflict = clash.expect_err("names clash").downcast::<Conflict>()?;
+        assert_eq!(conflict.existing_id, Some(client.id));
+
+        let req = |name: &str| CreateProjectRequest {
+            client_id: client.id,
+            name: name.to_string(),
+            hourly_rate: None,
+            budget_seconds: None,
+        };
+        let project = db.create_project(workspace_id, req("Website\t")).await?;
+        assert_eq!(project.name, "Website");
+        let conflict = db.create_project(workspace_id, req(" website")).await.expect_err("names clash").downcast::<Conflict>()?;
+        assert_eq!(conflict.existing_id, Some(project.id));
+
+        let other = db.create_project(workspace_id, req("App")).await?;
+        let rename = UpdateProjectRequest {
+            name: FieldUpdate::Set("WEBSITE ".to_string()),
+            ..Default::default()
+        };
+        let conflict = db.update_project(workspace_id, other.id, rename).await.expect_err("names clash").downcast::<Conflict>()?;
+        assert_eq!(conflict.existing_id, Some(project.id));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn running_sessions_are_left_out_of_reports(pool: PgPool) -> Result<()> {
+        let db = Database::new(pool);
+        let (workspace_id, user_id) = personal_workspace(&db, "test@example.com").await?;
+        let workspace = db.get_workspaces(user_id).await?.remove(0);
+        track_session(&db, workspace_id, user_id, &[]).await?;
+        let req = StartSessionRequest {
+            description: None,
+            tag_ids: Vec::new(),
+            project_id: None,
+            billable: false,
+            hourly_rate: None,
+        };
+        db.start_session(workspace_id, user_id, req).await?.expect("no session is running");
+
+        let today = Utc::now().date_naive().to_string();
+        let report = db.get_report(&workspace, report_query(&today, &today, "UTC", None)).await?;
+
+        assert_eq!(report.rows.len(), 1);
+        assert_eq!(report.rows[0].session_count, 1);
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn report_days_follow_the_timezone(pool: PgPool) -> Result<()> {
+        let db = Database::new(pool);
+        let (workspace_id, user_id) = personal_workspace(&db, "test@example.com").await?;
+        let workspace = db.get_workspaces(user_id).await?.remove(0);
+        // 00:30 on 11 March in Berlin, which is an hour ahead of UTC
+        log_session(&db, workspace_id, user_id, "2024-03-10T23:30:00Z", 60).await?;
+
+        let day = Some(ReportPeriod::Day);
+        let berlin = db.get_report(&workspace, report_query("2024-03-11", "2024-03-11", "Europe/Berlin", day)).await?;
+        assert_eq!(berlin.total_seconds, 3600);
+        assert_eq!(berlin.rows[0].period_start, Some("2024-03-11".parse()?));
+
+        let utc = db.get_report(&workspace, report_query("2024-03-11", "2024-03-11", "UTC", day)).await?;
+        assert_eq!(utc.total_seconds, 0);
+        assert!(utc.rows.is_empty());
+
+        let utc = db.get_report(&workspace, report_query("2024-03-10", "2024-03-10", "UTC", day)).await?;
+        assert_eq!(utc.total_seconds, 3600);
+        assert_eq!(utc.rows[0].period_start, Some("2024-03-10".parse()?));
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn reports_group_by_iso_week_and_calendar_month(pool: PgPool) -> Result<()> {
+        let db = Database::new(pool);
+        let (workspace_id, user_id) = personal_workspace(&db, "test@example.com").await?;
+        let workspace = db.get_workspaces(user_id).await?.remove(0);
+        // A Wednesday, the Sunday closing that week, and the Monday after
+        log_session(&db, workspace_id, user_id, "2024-01-31T12:00:00Z", 10).await?;
+        log_session(&db, workspace_id, user_id, "2024-02-04T12:00:00Z", 20).await?;
+        log_session(&db, workspace_id, user_id, "2024-02-05T12:00:00Z", 40).await?;
+
+        let buckets = |report: Report| {
+            report
+                .rows
+                .into_iter()
+                .map(|row| (row.period_start.unwrap().to_string(), row.session_count, row.total_seconds))
+                .collect::<Vec<_>>()
+        };
+
+        let weeks = db.get_report(&workspace, report_query("2024-01-01", "2024-02-29", "UTC", Some(ReportPeriod::Week))).await?;
+        assert_eq!(
+            buckets(weeks),
+            [("2024-01-29".to_string(), 2, 1800), ("2024-02-05".to_string(), 1, 2400)]
+        );
+
+        let months = db.get_report(&workspace, report_query("2024-01-01", "2024-02-29", "UTC", Some(ReportPeriod::Month))).await?;
+        assert_eq!(
+            buckets(months),
+            [("2024-01-01".to_string(), 1, 600), ("2024-02-01".to_string(), 2, 3600)]
+        );
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn invoices_claim_finished_billable_sessions_with_an_amount_in_the_local_range(pool: PgPool) -> Result<()> {
+        let db = Database::new(pool);
+        let (workspace_id, user_id) = personal_workspace(&db, "test@example.com").await?;
+        let workspace = db.get_workspaces(user_id).await?.remove(0);
+
+        let in_may = bill_session(&db, workspace_id, user_id, logged("2024-05-10T10:00:00Z", 60), Some(100)).await?;
+        // 00:30 on 1 May in Berlin
+        let berlin_may = bill_session(&db, workspace_id, user_id, logged("2024-04-30T22:30:00Z", 60), Some(100)).await?;
+        // 00:30 on 1 June in Berlin
+        bill_session(&db, workspace_id, user_id, logged("2024-05-31T22:30:00Z", 60), Some(100)).await?;
+        // Not billable, and billable without a rate
+        let unbillable = CreateSessionRequest { hourly_rate: Some(Decimal::from(100)), ..logged("2024-05-11T10:00:00Z", 60) };
+        db.create_session(workspace_id, user_id, unbillable).await?;
+        bill_session(&db, workspace_id, user_id, logged("2024-05-12T10:00:00Z", 60), None).await?;
+
+        let invoice = db.create_invoice(&workspace, user_id, invoice_request("2024-05-01", "2024-05-31", "Europe/Berlin")).await?;
+        let mut expected = vec![in_may, berlin_may];
+        expected.sort();
+        assert_eq!(invoiced_sessions(&db, invoice.id).await?, expected);
+        assert_eq!(invoice.total, Decimal::from(200));
+
+        // Invoiced sessions are not claimed again
+        let again = db.create_invoice(&workspace, user_id, invoice_request("2024-05-01", "2024-05-31", "Europe/Berlin")).await;
+        assert!(again.unwrap_err().is::<InvalidInput>());
+
+        // Nor are running ones
+        let req = StartSessionRequest {
+            description: None,
+            tag_ids: Vec::new(),
+            project_id: None,
+            billable: true,
+            hourly_rate: Some(Decimal::from(100)),
+        };
+        db.start_session(workspace_id, user_id, req).await?.expect("no session is running");
+        let today = Utc::now().date_naive().to_string();
+        let running = db.create_invoice(&workspace, user_id, invoice_request(&today, &today, "UTC")).await;
+        assert!(running.unwrap_err().is::<InvalidInput>());
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn invoices_only_claim_the_chosen_client_or_tag(pool: PgPool) -> Result<()> {
+        let db = Database::new(pool);
+        let (workspace_id, user_id) = personal_workspace(&db, "test@example.com").await?;
+        let workspace = db.get_workspaces(user_id).await?.remove(0);
+        let mut projects = Vec::new();
+        for name in ["Acme", "Globex"] {
+            let client = db.create_client(workspace_id, CreateClientRequest { name: name.to_string() }).await?;
+            let req = CreateProjectRequest {
+                client_id: client.id,
+                name: "Website".to_string(),
+                hourly_rate: Some(Decimal::from(100)),
+                budget_seconds: None,
+            };
+            projects.push((client.id, db.create_project(workspace_id, req).await?.id));
+        }
+        let tag = create_tag(&db, workspace_id, user_id, "Design").await?;
+
+        let mut sessions = Vec::new();
+        for (_, project_id) in &projects {
+            let req = CreateSessionRequest { project_id: Some(*project_id), ..logged("2024-05-10T10:00:00Z", 60) };
+            sessions.push(bill_session(&db, workspace_id, user_id, req, None).await?);
+        }
+        let req = CreateSessionRequest { tag_ids: vec![tag.id], ..logged("2024-05-10T12:00:00Z", 60) };
+        let tagged = bill_session(&db, workspace_id, user_id, req, None).await?;
+
+        let req = CreateInvoiceRequest { client_id: Some(projects[0].0), ..invoice_request("2024-05-01", "2024-05-31", "UTC") };
+        let invoice = db.create_invoice(&workspace, user_id, req).await?;
+        assert_eq!(invoice.client_name.as_deref(), Some("Acme"));
+        assert_eq!(invoiced_sessions(&db, invoice.id).await?, [sessions[0]]);
+
+        let req = CreateInvoiceRequest { tag_id: Some(tag.id), ..invoice_request("2024-05-01", "2024-05-31", "UTC") };
+        let invoice = db.create_invoice(&workspace, user_id, req).await?;
+        assert_eq!(invoiced_sessions(&db, invoice.id).await?, [tagged]);
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn invoice_numbers_count_up_in_each_workspace(pool: PgPool) -> Result<()> {
+        let db = Database::new(pool);
+        let mut numbers = Vec::new();
+        for email in ["first@example.com", "second@example.com"] {
+            let (workspace_id, user_id) = personal_workspace(&db, email).await?;
+            let workspace = db.get_workspaces(user_id).await?.remove(0);
+            for day in ["2024-05-10", "2024-05-11"] {
+                bill_session(&db, workspace_id, user_id, logged(&format!("{day}T10:00:00Z"), 60), Some(100)).await?;
+                let invoice = db.create_invoice(&workspace, user_id, invoice_request(day, day, "UTC")).await?;
+                numbers.push(invoice.number);
+            }
+        }
+
+        assert_eq!(numbers, [1, 2, 1, 2]);
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn tag_lines_count_a_session_under_its_highest_rate_tag(pool: PgPool) -> Result<()> {
+        let db = Database::new(pool);
+        let (workspace_id, user_id) = personal_workspace(&db, "test@example.com").await?;
+        let workspace = db.get_workspaces(user_id).await?.remove(0);
+        let low = create_tag(&db, workspace_id, user_id, "Admin").await?;
+        let req = CreateTagRequest {
+            name: "Consulting".to_string(),
+            color: None,
+            hourly_rate: Some(Decimal::from(80)),
+        };
+        let high = db.create_tag(workspace_id, user_id, req).await?;
+
+        let req = CreateSessionRequest { tag_ids: vec![low.id, high.id], ..logged("2024-05-10T10:00:00Z", 60) };
+        bill_session(&db, workspace_id, user_id, req, None).await?;
+        let req = CreateSessionRequest { tag_ids: vec![low.id], ..logged("2024-05-11T10:00:00Z", 30) };
+        bill_session(&db, workspace_id, user_id, req, None).await?;
+        bill_session(&db, workspace_id, user_id, logged("2024-05-12T10:00:00Z", 60), Some(10)).await?;
+
+        let invoice = db.create_invoice(&workspace, user_id, invoice_request("2024-05-01", "2024-05-31", "UTC")).await?;
+        let lines: Vec<_> = invoice
+            .lines
+            .iter()
+            .map(|line| (line.description.as_str(), line.session_count, line.amount))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                ("Admin", 1, Decimal::new(2500, 2)),
+                ("Consulting", 1, Decimal::new(8000, 2)),
+                ("Untagged", 1, Decimal::new(1000, 2)),
+            ]
+        );
+        assert_eq!(invoice.total, Decimal::new(11500, 2));
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn invoiced_sessions_are_locked_until_the_invoice_is_deleted(pool: PgPool) -> Result<()> {
+        use axum::{http::StatusCode, response::IntoResponse};
+        use crate::error::ApiError;
+
+        let db = Database::new(pool);
+        let (workspace_id, user_id) = personal_workspace(&db, "test@example.com").await?;
+        let workspace = db.get_workspaces(user_id).await?.remove(0);
+        let id = bill_session(&db, workspace_id, user_id, logged("2024-05-10T10:00:00Z", 60), Some(100)).await?;
+        let invoice = db.create_invoice(&workspace, user_id, invoice_request("2024-05-10", "2024-05-10", "UTC")).await?;
+
+        let status = |e: anyhow::Error| ApiError::from(e).into_response().status();
+        let patch = || UpdateSessionRequest {
+            description: FieldUpdate::Set("Changed".to_string()),
+            ..Default::default()
+        };
+        let err = db.update_session(workspace_id, id, patch()).await.unwrap_err();
+        assert_eq!(status(err), StatusCode::CONFLICT);
+        let err = db.replace_session(workspace_id, id, logged("2024-05-10T10:00:00Z", 30)).await.unwrap_err();
+        assert_eq!(status(err), StatusCode::CONFLICT);
+        let err = db.delete_session(workspace_id, id).await.unwrap_err();
+        assert_eq!(status(err), StatusCode::CONFLICT);
+
+        assert!(db.delete_invoice(workspace_id, invoice.id).await?);
+        assert!(db.update_session(workspace_id, id, patch()).await?.is_some());
+        let invoice = db.create_invoice(&workspace, user_id, invoice_request("2024-05-10", "2024-05-10", "UTC")).await?;
+        assert_eq!(invoiced_sessions(&db, invoice.id).await?, [id]);
+        Ok(())
+    }
+
+    fn import_record(started_at: &str, minutes: i64, tags: &[&str]) -> ImportSessionRecord {
+        let req = logged(started_at, minutes);
+        ImportSessionRecord {
+            started_at: req.started_at,
+            ended_at: req.ended_at,
+            duration_seconds: None,
+            description: req.description,
+            tags: tags.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    async fn tag_names(db: &Database, workspace_id: Uuid) -> Result<Vec<String>> {
+        let mut names: Vec<_> = db.get_tags(workspace_id).await?.into_iter().map(|t| t.name).collect();
+        names.sort();
+        Ok(names)
+    }
+
+    #[sqlx::test]
+    async fn dry_run_imports_roll_back_sessions_and_new_tags(pool: PgPool) -> Result<()> {
+        let db = Database::new(pool);
+        let (workspace_id, user_id) = personal_workspace(&db, "test@example.com").await?;
+        create_tag(&db, workspace_id, user_id, "Backend").await?;
+        let records = || {
+            vec![
+                Ok(import_record("2024-05-10T09:00:00Z", 60, &["backend", "Research"])),
+                Ok(import_record("2024-05-11T09:00:00Z", 30, &["Research"])),
+            ]
+        };
+
+        let report = db.import_sessions(workspace_id, user_id, records(), true).await?;
+        assert!(report.dry_run && !report.committed);
+        assert_eq!(report.imported, 2);
+        assert_eq!(report.created_tags, ["Research"]);
+        assert!(report.errors.is_empty());
+        assert!(db.get_sessions(workspace_id, &SessionQuery::default()).await?.items.is_empty());
+        assert_eq!(tag_names(&db, workspace_id).await?, ["Backend"]);
+
+        let report = db.import_sessions(workspace_id, user_id, records(), false).await?;
+        assert!(report.committed);
+        assert_eq!(db.get_sessions(workspace_id, &SessionQuery::default()).await?.items.len(), 2);
+        assert_eq!(tag_names(&db, workspace_id).await?, ["Backend", "Research"]);
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn one_bad_row_rejects_the_whole_import(pool: PgPool) -> Result<()> {
+        let db = Database::new(pool);
+        let (workspace_id, user_id) = personal_workspace(&db, "test@example.com").await?;
+        let backwards = ImportSessionRecord {
+            ended_at: Some(DateTime::parse_from_rfc3339("2024-05-12T08:00:00Z")?.with_timezone(&Utc)),
+            ..import_record("2024-05-12T09:00:00Z", 60, &[])
+        };
+        let records = vec![
+            Ok(import_record("2024-05-10T09:00:00Z", 60, &["Research"])),
+            Err("CSV deserialize error: record 2".to_string()),
+            Ok(backwards),
+            Ok(import_record("2024-05-13T09:00:00Z", 60, &["  "])),
+            Ok(import_record("2024-05-14T09:00:00Z", 60, &[])),
+        ];
+
+        let report = db.import_sessions(workspace_id, user_id, records, false).await?;
+        assert!(!report.committed);
+        assert_eq!(report.imported, 0);
+        let errors: Vec<_> = report.errors.iter().map(|e| (e.row, e.message.as_str())).collect();
+        assert_eq!(
+            errors,
+            [
+                (2, "CSV deserialize error: record 2"),
+                (3, "ended_at: must not be before started_at"),
+                (4, "Invalid tag name: \"\""),
+            ]
+        );
+        assert!(db.get_sessions(workspace_id, &SessionQuery::default()).await?.items.is_empty());
+        assert!(tag_names(&db, workspace_id).await?.is_empty());
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn exported_csv_imports_with_its_tags(pool: PgPool) -> Result<()> {
+        use crate::session_csv::{read_sessions, write_sessions};
+
+        let db = Database::new(pool);
+        let (workspace_id, user_id) = personal_workspace(&db, "first@example.com").await?;
+        let backend = create_tag(&db, workspace_id, user_id, "Backend").await?.id;
+        let review = create_tag(&db, workspace_id, user_id, "Code review").await?.id;
+        for (started_at, tag_ids) in [
+            ("2024-05-10T09:00:00Z", vec![backend, review]),
+            ("2024-05-11T09:00:00Z", vec![review]),
+            ("2024-05-12T09:00:00Z", vec![]),
+        ] {
+            let req = CreateSessionRequest { tag_ids, ..logged(started_at, 45) };
+            db.create_session(workspace_id, user_id, req).await?;
+        }
+        let exported = db.get_sessions(workspace_id, &SessionQuery::default()).await?.items;
+
+        let (other_workspace_id, other_user_id) = personal_workspace(&db, "second@example.com").await?;
+        let csv = write_sessions(&exported, true)?;
+        let report = db.import_sessions(other_workspace_id, other_user_id, read_sessions(&csv), false).await?;
+        assert!(report.committed, "{:?}", report.errors);
+        assert_eq!(report.imported, 3);
+
+        let summary = |sessions: Vec<WorkSessionWithTags>| {
+            sessions
+                .into_iter()
+                .map(|s| {
+                    let mut tags: Vec<_> = s.tags.into_iter().map(|t| t.name).collect();
+                    tags.sort();
+                    (s.started_at, s.ended_at, s.duration_seconds, s.description, tags)
+                })
+                .collect::<Vec<_>>()
+        };
+        let imported = db.get_sessions(other_workspace_id, &SessionQuery::default()).await?.items;
+        assert_eq!(summary(imported), summary(exported));
+        assert_eq!(tag_names(&db, other_workspace_id).await?, ["Backend", "Code review"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use axum::{
+    extract::rejection::{JsonRejection, PathRejection, QueryRejection},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use shared::{ApiResponse, ErrorCode, ValidationErrors};
+
+use uuid::Uuid;
+
+use crate::db::{Conflict, InvalidInput};
+use crate::extract::Json;
+
+/// Error returned by handlers. Every variant is sent as an `ApiResponse`
+/// body carrying a machine-readable `ErrorCode`.
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("{0}")]
+    Unauthorized(&'static str),
+    #[error("{0}")]
+    Forbidden(&'static str),
+    #[error("{0} not found")]
+    NotFound(&'static str),
+    #[error("{0}")]
+    Validation(String),
+    #[error("{0}")]
+    InvalidFields(#[from] ValidationErrors),
+    /// `existing_id` names the resource the request clashed with, if any
+    #[error("{message}")]
+    Conflict {
+        message: String,
+        existing_id: Option<Uuid>,
+    },
+    #[error(transparent)]
+    Internal(anyhow::Error),
+}
+
+impl ApiError {
+    fn status(&self) -> StatusCode {
+        match self {
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Validation(_) | ApiError::InvalidFields(_) => StatusCode::BAD_REQUEST,
+            ApiError::Conflict { .. } => StatusCode::CONFLICT,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn code(&self) -> ErrorCode {
+        match self {
+            ApiError::Unauthorized(_) => ErrorCode::Unauthorized,
+            ApiError::Forbidden(_) => ErrorCode::Forbidden,
+            ApiError::NotFound(_) => ErrorCode::NotFound,
+            ApiError::Validation(_) | ApiError::InvalidFields(_) => ErrorCode::Validation,
+            ApiError::Conflict { .. } => ErrorCode::Conflict,
+            ApiError::Internal(_) => ErrorCode::Internal,
+        }
+    }
+}
+
+/// Database errors are internal unless the input was rejected.
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        let e = match e.downcast::<ValidationErrors>() {
+            Ok(errors) => return ApiError::InvalidFields(errors),
+            Err(e) => e,
+        };
+        let e = match e.downcast::<Conflict>() {
+            Ok(Conflict { message, existing_id }) => {
+                return ApiError::Conflict { message, existing_id }
+            }
+            Err(e) => e,
+        };
+        match e.downcast::<InvalidInput>() {
+            Ok(InvalidInput(message)) => ApiError::Validation(message),
+            Err(e) => ApiError::Internal(e),
+        }
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        ApiError::Validation(rejection.body_text())
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        ApiError::Validation(rejection.body_text())
+    }
+}
+
+impl From<PathRejection> for ApiError {
+    fn from(rejection: PathRejection) -> Self {
+        ApiError::Validation(rejection.body_text())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        if let ApiError::InvalidFields(errors) = self {
+            return (status, Json(ApiResponse::<()>::invalid(errors))).into_response();
+        }
+
+        let message = match &self {
+            ApiError::Internal(e) => {
+                tracing::error!("Internal error: {:#}", e);
+                "Internal server error".to_string()
+            }
+            other => other.to_string(),
+        };
+
+        let mut body = ApiResponse::<()>::error(self.code(), message);
+        if let ApiError::Conflict { existing_id, .. } = self {
+            body.existing_id = existing_id;
+        }
+        (status, Json(body)).into_response()
+    }
+}
+
+pub type ApiResult<T> = Result<Json<ApiResponse<T>>, ApiError>;
@@ -0,0 +1,113 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use shared::LiveEvent;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::db::Database;
+
+/// Postgres channel that changes travel on between instances
+const CHANNEL: &str = "wst_events";
+/// `NOTIFY` payloads must stay below 8000 bytes
+const MAX_PAYLOAD: usize = 7900;
+/// Changes kept for subscribers that are slow to read them
+const CAPACITY: usize = 256;
+
+/// A change in one workspace, or in all of them if `workspace_id` is unset.
+#[derive(Debug, Serialize, Deserialize)]
+struct Change {
+    workspace_id: Option<Uuid>,
+    event: LiveEvent,
+}
+
+/// Fans session and tag changes out to the SSE subscribers of their
+/// workspace. When shared through Postgres, changes are sent with `NOTIFY`
+/// so subscribers on every instance using the database receive them.
+#[derive(Clone)]
+pub struct Events {
+    sender: broadcast::Sender<Arc<Change>>,
+    db: Option<Arc<Database>>,
+}
+
+impl Events {
+    /// Events seen only by subscribers of this instance.
+    pub fn local() -> Self {
+        let (sender, _) = broadcast::channel(CAPACITY);
+        Self { sender, db: None }
+    }
+
+    /// Events shared with every instance listening on the database.
+    pub async fn shared(db: Arc<Database>) -> anyhow::Result<Self> {
+        let mut listener = db.listen(CHANNEL).await?;
+        let (sender, _) = broadcast::channel(CAPACITY);
+
+        let forward = sender.clone();
+        tokio::spawn(async move {
+            loop {
+                match listener.try_recv().await {
+                    Ok(Some(notification)) => match serde_json::from_str::<Change>(notification.payload()) {
+                        Ok(change) => {
+                            let _ = forward.send(Arc::new(change));
+                        }
+                        Err(e) => warn!("Ignoring malformed change notification: {e}"),
+                    },
+                    // Notifications sent while reconnecting are lost
+                    Ok(None) => {
+                        let _ = forward.send(Arc::new(Change { workspace_id: None, event: LiveEvent::Resync }));
+                    }
+                    Err(e) => {
+                        error!("Listening for changes failed: {e}");
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                    }
+                }
+            }
+        });
+
+        Ok(Self { sender, db: Some(db) })
+    }
+
+    /// Sends `event` to the subscribers of `workspace_id`. Failures are
+    /// logged, as the change itself has already been made.
+    pub async fn publish(&self, workspace_id: Uuid, event: LiveEvent) {
+        let mut change = Change { workspace_id: Some(workspace_id), event };
+        let Some(db) = &self.db else {
+            // Nobody listening is not an error
+            let _ = self.sender.send(Arc::new(change));
+            return;
+        };
+
+        let mut payload = serde_json::to_string(&change);
+        if payload.as_ref().is_ok_and(|payload| payload.len() > MAX_PAYLOAD) {
+            change.event = LiveEvent::Resync;
+            payload = serde_json::to_string(&change);
+        }
+        let result = match payload {
+            Ok(payload) => db.notify(CHANNEL, &payload).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = result {
+            error!("Failed to publish change: {e:#}");
+        }
+    }
+
+    /// Changes to `workspace_id` from now on. Subscribers that fall too far
+    /// behind are sent `Resync` in place of the changes they missed.
+    pub fn subscribe(&self, workspace_id: Uuid) -> impl Stream<Item = LiveEvent> {
+        futures::stream::unfold(self.sender.subscribe(), move |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(change) if change.workspace_id.is_none_or(|id| id == workspace_id) => {
+                        return Some((change.event.clone(), receiver));
+                    }
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(_)) => return Some((LiveEvent::Resync, receiver)),
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+}
@@ -1,147 +1,1321 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::Json,
+    body::{Body, Bytes},
+    extract::State,
+    http::{header, HeaderMap},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
 };
+use axum_extra::extract::cookie::{Cookie, CookieJar};
+use futures::stream::{self, StreamExt, TryStreamExt};
 use shared::*;
 use uuid::Uuid;
 
-use crate::AppState;
+use crate::auth::{self, CurrentUser, LoggedInUser, Membership};
+use crate::error::{ApiError, ApiResult};
+use crate::extract::{Json, Path, Query};
+use crate::openapi::NoData;
+use crate::{invoice_render, session_csv, AppState};
 
 // Session handlers
+#[utoipa::path(
+    get,
+    path = "/api/sessions",
+    tag = "sessions",
+    params(WorkspaceParam, SessionQuery),
+    responses((status = 200, description = "Success", body = ApiResponse<Page<WorkSessionWithTags>>)),
+)]
 pub async fn get_sessions(
     State(state): State<AppState>,
-) -> Result<Json<ApiResponse<Vec<WorkSessionWithTags>>>, StatusCode> {
-    match state.db.get_sessions().await {
-        Ok(sessions) => Ok(Json(ApiResponse::success(sessions))),
-        Err(e) => {
-            tracing::error!("Failed to get sessions: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+    member: Membership,
+    Query(query): Query<SessionQuery>,
+) -> ApiResult<Page<WorkSessionWithTags>> {
+    member.require(WorkspaceRole::Viewer)?;
+    let sessions = state.db.get_sessions(member.workspace.id, &query).await?;
+    Ok(Json(ApiResponse::success(sessions)))
+}
+
+/// Streams every session matching the listing filters as CSV. Sessions are
+/// read a page at a time, so the export never holds the full history.
+#[utoipa::path(
+    get,
+    path = "/api/sessions/export.csv",
+    tag = "sessions",
+    params(WorkspaceParam, SessionQuery),
+    responses((status = 200, description = "Matching sessions as CSV", content_type = "text/csv", body = String)),
+)]
+pub async fn export_sessions(
+    State(state): State<AppState>,
+    member: Membership,
+    Query(query): Query<SessionQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    member.require(WorkspaceRole::Viewer)?;
+    let workspace_id = member.workspace.id;
+    let first_page = SessionQuery {
+        limit: Some(SessionQuery::MAX_LIMIT),
+        cursor: None,
+        ..query
+    };
+
+    let pages = stream::try_unfold(Some(first_page), move |next| {
+        let db = state.db.clone();
+        async move {
+            let Some(query) = next else {
+                return Ok(None);
+            };
+            let page = db.get_sessions(workspace_id, &query).await?;
+            let csv = session_csv::write_sessions(&page.items, query.cursor.is_none())?;
+            let next = page.next_cursor.map(|cursor| SessionQuery {
+                cursor: Some(cursor),
+                ..query
+            });
+            Ok::<_, anyhow::Error>(Some((csv, next)))
+        }
+    })
+    .inspect_err(|e| tracing::error!("Failed to export sessions: {}", e));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"sessions.csv\""),
+        ],
+        Body::from_stream(pages),
+    ))
+}
+
+/// Imports sessions from a CSV file (`Content-Type: text/csv`) or a JSON
+/// array of records.
+#[utoipa::path(
+    post,
+    path = "/api/sessions/import",
+    tag = "sessions",
+    params(WorkspaceParam, ImportOptions),
+    request_body(content((Vec<ImportSessionRecord> = "application/json"), (String = "text/csv"))),
+    responses((status = 200, description = "Success", body = ApiResponse<ImportReport>)),
+)]
+pub async fn import_sessions(
+    State(state): State<AppState>,
+    member: Membership,
+    Query(options): Query<ImportOptions>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> ApiResult<ImportReport> {
+    member.require(WorkspaceRole::Member)?;
+    let is_csv = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/csv"));
+
+    let records = if is_csv {
+        session_csv::read_sessions(&body)
+    } else {
+        match serde_json::from_slice::<Vec<serde_json::Value>>(&body) {
+            Ok(values) => values
+                .into_iter()
+                .map(|value| serde_json::from_value(value).map_err(|e| e.to_string()))
+                .collect(),
+            Err(e) => return Err(ApiError::Validation(e.to_string())),
         }
+    };
+
+    let report = state.db.import_sessions(member.workspace.id, member.user.id, records, options.dry_run).await?;
+    if report.committed {
+        state.events.publish(member.workspace.id, LiveEvent::Resync).await;
     }
+    Ok(Json(ApiResponse::success(report)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/sessions/{id}",
+    tag = "sessions",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Session id")),
+    responses((status = 200, description = "Success", body = ApiResponse<WorkSessionWithTags>)),
+)]
 pub async fn get_session(
     State(state): State<AppState>,
+    member: Membership,
     Path(id): Path<Uuid>,
-) -> Result<Json<ApiResponse<WorkSessionWithTags>>, StatusCode> {
-    match state.db.get_session(id).await {
-        Ok(Some(session)) => Ok(Json(ApiResponse::success(session))),
-        Ok(None) => Err(StatusCode::NOT_FOUND),
-        Err(e) => {
-            tracing::error!("Failed to get session {}: {}", id, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
-    }
+) -> ApiResult<WorkSessionWithTags> {
+    member.require(WorkspaceRole::Viewer)?;
+    let session = state.db.get_session(member.workspace.id, id)
+        .await?
+        .ok_or(ApiError::NotFound("Session"))?;
+    Ok(Json(ApiResponse::success(session)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/sessions",
+    tag = "sessions",
+    params(WorkspaceParam),
+    request_body = CreateSessionRequest,
+    responses((status = 200, description = "Success", body = ApiResponse<WorkSession>)),
+)]
 pub async fn create_session(
     State(state): State<AppState>,
+    member: Membership,
     Json(req): Json<CreateSessionRequest>,
-) -> Result<Json<ApiResponse<WorkSession>>, StatusCode> {
-    match state.db.create_session(req).await {
-        Ok(session) => Ok(Json(ApiResponse::success(session))),
-        Err(e) => {
-            tracing::error!("Failed to create session: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
-    }
+) -> ApiResult<WorkSession> {
+    member.require(WorkspaceRole::Member)?;
+    req.validate()?;
+    let session = state.db.create_session(member.workspace.id, member.user.id, req).await?;
+    publish_session(&state, member.workspace.id, session.id, true).await;
+    Ok(Json(ApiResponse::success(session)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/sessions/start",
+    tag = "sessions",
+    params(WorkspaceParam),
+    request_body = StartSessionRequest,
+    responses((status = 200, description = "Success", body = ApiResponse<WorkSession>)),
+)]
+pub async fn start_session(
+    State(state): State<AppState>,
+    member: Membership,
+    Json(req): Json<StartSessionRequest>,
+) -> ApiResult<WorkSession> {
+    member.require(WorkspaceRole::Member)?;
+    req.validate()?;
+    let session = state.db.start_session(member.workspace.id, member.user.id, req)
+        .await?
+        .ok_or_else(|| ApiError::Conflict {
+            message: "A session is already running".to_string(),
+            existing_id: None,
+        })?;
+    publish_session(&state, member.workspace.id, session.id, true).await;
+    Ok(Json(ApiResponse::success(session)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/sessions/{id}/stop",
+    tag = "sessions",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Session id")),
+    responses((status = 200, description = "Success", body = ApiResponse<WorkSession>)),
+)]
+pub async fn stop_session(
+    State(state): State<AppState>,
+    member: Membership,
+    Path(id): Path<Uuid>,
+) -> ApiResult<WorkSession> {
+    check_session_access(&state, &member, id).await?;
+    let session = state.db.stop_session(member.workspace.id, id)
+        .await?
+        .ok_or(ApiError::NotFound("Session"))?;
+    publish_session(&state, member.workspace.id, session.id, false).await;
+    Ok(Json(ApiResponse::success(session)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/sessions/{id}/pause",
+    tag = "sessions",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Session id")),
+    responses((status = 200, description = "Success", body = ApiResponse<WorkSession>)),
+)]
+pub async fn pause_session(
+    State(state): State<AppState>,
+    member: Membership,
+    Path(id): Path<Uuid>,
+) -> ApiResult<WorkSession> {
+    check_session_access(&state, &member, id).await?;
+    let session = state.db.pause_session(member.workspace.id, id)
+        .await?
+        .ok_or(ApiError::NotFound("Session"))?;
+    publish_session(&state, member.workspace.id, session.id, false).await;
+    Ok(Json(ApiResponse::success(session)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/sessions/{id}/resume",
+    tag = "sessions",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Session id")),
+    responses((status = 200, description = "Success", body = ApiResponse<WorkSession>)),
+)]
+pub async fn resume_session(
+    State(state): State<AppState>,
+    member: Membership,
+    Path(id): Path<Uuid>,
+) -> ApiResult<WorkSession> {
+    check_session_access(&state, &member, id).await?;
+    let session = state.db.resume_session(member.workspace.id, id)
+        .await?
+        .ok_or(ApiError::NotFound("Session"))?;
+    publish_session(&state, member.workspace.id, session.id, false).await;
+    Ok(Json(ApiResponse::success(session)))
+}
+
+/// Replaces a session with a full representation.
+#[utoipa::path(
+    put,
+    path = "/api/sessions/{id}",
+    tag = "sessions",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Session id")),
+    request_body = CreateSessionRequest,
+    responses((status = 200, description = "Success", body = ApiResponse<WorkSession>)),
+)]
+pub async fn replace_session(
+    State(state): State<AppState>,
+    member: Membership,
+    Path(id): Path<Uuid>,
+    Json(req): Json<CreateSessionRequest>,
+) -> ApiResult<WorkSession> {
+    req.validate()?;
+    check_session_access(&state, &member, id).await?;
+    let session = state.db.replace_session(member.workspace.id, id, req)
+        .await?
+        .ok_or(ApiError::NotFound("Session"))?;
+    publish_session(&state, member.workspace.id, session.id, false).await;
+    Ok(Json(ApiResponse::success(session)))
+}
+
+/// Applies a JSON Merge Patch to a session.
+#[utoipa::path(
+    patch,
+    path = "/api/sessions/{id}",
+    tag = "sessions",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Session id")),
+    request_body(content = UpdateSessionRequest, content_type = "application/merge-patch+json"),
+    responses((status = 200, description = "Success", body = ApiResponse<WorkSession>)),
+)]
 pub async fn update_session(
     State(state): State<AppState>,
+    member: Membership,
     Path(id): Path<Uuid>,
     Json(req): Json<UpdateSessionRequest>,
-) -> Result<Json<ApiResponse<WorkSession>>, StatusCode> {
-    match state.db.update_session(id, req).await {
-        Ok(Some(session)) => Ok(Json(ApiResponse::success(session))),
-        Ok(None) => Err(StatusCode::NOT_FOUND),
-        Err(e) => {
-            tracing::error!("Failed to update session {}: {}", id, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
-    }
+) -> ApiResult<WorkSession> {
+    req.validate()?;
+    check_session_access(&state, &member, id).await?;
+    let session = state.db.update_session(member.workspace.id, id, req)
+        .await?
+        .ok_or(ApiError::NotFound("Session"))?;
+    publish_session(&state, member.workspace.id, session.id, false).await;
+    Ok(Json(ApiResponse::success(session)))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/sessions/{id}",
+    tag = "sessions",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Session id")),
+    responses((status = 200, description = "Success", body = ApiResponse<NoData>)),
+)]
 pub async fn delete_session(
     State(state): State<AppState>,
+    member: Membership,
     Path(id): Path<Uuid>,
-) -> Result<Json<ApiResponse<()>>, StatusCode> {
-    match state.db.delete_session(id).await {
-        Ok(true) => Ok(Json(ApiResponse::success(()))),
-        Ok(false) => Err(StatusCode::NOT_FOUND),
-        Err(e) => {
-            tracing::error!("Failed to delete session {}: {}", id, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
+) -> ApiResult<()> {
+    check_session_access(&state, &member, id).await?;
+    if state.db.delete_session(member.workspace.id, id).await? {
+        state.events.publish(member.workspace.id, LiveEvent::SessionDeleted { id }).await;
+        Ok(Json(ApiResponse::success(())))
+    } else {
+        Err(ApiError::NotFound("Session"))
     }
 }
 
 // Tag handlers
+#[utoipa::path(
+    get,
+    path = "/api/tags",
+    tag = "tags",
+    params(WorkspaceParam),
+    responses((status = 200, description = "Success", body = ApiResponse<Vec<Tag>>)),
+)]
 pub async fn get_tags(
     State(state): State<AppState>,
-) -> Result<Json<ApiResponse<Vec<Tag>>>, StatusCode> {
-    match state.db.get_tags().await {
-        Ok(tags) => Ok(Json(ApiResponse::success(tags))),
-        Err(e) => {
-            tracing::error!("Failed to get tags: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
-    }
+    member: Membership,
+) -> ApiResult<Vec<Tag>> {
+    member.require(WorkspaceRole::Viewer)?;
+    let tags = state.db.get_tags(member.workspace.id).await?;
+    Ok(Json(ApiResponse::success(tags)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/tags/{id}",
+    tag = "tags",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Tag id")),
+    responses((status = 200, description = "Success", body = ApiResponse<Tag>)),
+)]
 pub async fn get_tag(
     State(state): State<AppState>,
+    member: Membership,
     Path(id): Path<Uuid>,
-) -> Result<Json<ApiResponse<Tag>>, StatusCode> {
-    match state.db.get_tag(id).await {
-        Ok(Some(tag)) => Ok(Json(ApiResponse::success(tag))),
-        Ok(None) => Err(StatusCode::NOT_FOUND),
-        Err(e) => {
-            tracing::error!("Failed to get tag {}: {}", id, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
-    }
+) -> ApiResult<Tag> {
+    member.require(WorkspaceRole::Viewer)?;
+    let tag = state.db.get_tag(member.workspace.id, id)
+        .await?
+        .ok_or(ApiError::NotFound("Tag"))?;
+    Ok(Json(ApiResponse::success(tag)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/tags",
+    tag = "tags",
+    params(WorkspaceParam),
+    request_body = CreateTagRequest,
+    responses((status = 200, description = "Success", body = ApiResponse<Tag>)),
+)]
 pub async fn create_tag(
     State(state): State<AppState>,
+    member: Membership,
     Json(req): Json<CreateTagRequest>,
-) -> Result<Json<ApiResponse<Tag>>, StatusCode> {
-    match state.db.create_tag(req).await {
-        Ok(tag) => Ok(Json(ApiResponse::success(tag))),
-        Err(e) => {
-            tracing::error!("Failed to create tag: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
-    }
+) -> ApiResult<Tag> {
+    member.require(WorkspaceRole::Member)?;
+    req.validate()?;
+    let tag = state.db.create_tag(member.workspace.id, member.user.id, req).await?;
+    state.events.publish(member.workspace.id, LiveEvent::TagCreated(tag.clone())).await;
+    Ok(Json(ApiResponse::success(tag)))
+}
+
+/// Replaces a tag with a full representation.
+#[utoipa::path(
+    put,
+    path = "/api/tags/{id}",
+    tag = "tags",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Tag id")),
+    request_body = CreateTagRequest,
+    responses((status = 200, description = "Success", body = ApiResponse<Tag>)),
+)]
+pub async fn replace_tag(
+    State(state): State<AppState>,
+    member: Membership,
+    Path(id): Path<Uuid>,
+    Json(req): Json<CreateTagRequest>,
+) -> ApiResult<Tag> {
+    member.require(WorkspaceRole::Admin)?;
+    req.validate()?;
+    let tag = state.db.replace_tag(member.workspace.id, id, req)
+        .await?
+        .ok_or(ApiError::NotFound("Tag"))?;
+    state.events.publish(member.workspace.id, LiveEvent::TagUpdated(tag.clone())).await;
+    Ok(Json(ApiResponse::success(tag)))
 }
 
+/// Applies a JSON Merge Patch to a tag.
+#[utoipa::path(
+    patch,
+    path = "/api/tags/{id}",
+    tag = "tags",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Tag id")),
+    request_body(content = UpdateTagRequest, content_type = "application/merge-patch+json"),
+    responses((status = 200, description = "Success", body = ApiResponse<Tag>)),
+)]
 pub async fn update_tag(
     State(state): State<AppState>,
+    member: Membership,
     Path(id): Path<Uuid>,
     Json(req): Json<UpdateTagRequest>,
-) -> Result<Json<ApiResponse<Tag>>, StatusCode> {
-    match state.db.update_tag(id, req).await {
-        Ok(Some(tag)) => Ok(Json(ApiResponse::success(tag))),
-        Ok(None) => Err(StatusCode::NOT_FOUND),
-        Err(e) => {
-            tracing::error!("Failed to update tag {}: {}", id, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
-    }
+) -> ApiResult<Tag> {
+    member.require(WorkspaceRole::Admin)?;
+    req.validate()?;
+    let tag = state.db.update_tag(member.workspace.id, id, req)
+        .await?
+        .ok_or(ApiError::NotFound("Tag"))?;
+    state.events.publish(member.workspace.id, LiveEvent::TagUpdated(tag.clone())).await;
+    Ok(Json(ApiResponse::success(tag)))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/tags/{id}",
+    tag = "tags",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Tag id")),
+    responses((status = 200, description = "Success", body = ApiResponse<NoData>)),
+)]
 pub async fn delete_tag(
     State(state): State<AppState>,
+    member: Membership,
     Path(id): Path<Uuid>,
-) -> Result<Json<ApiResponse<()>>, StatusCode> {
-    match state.db.delete_tag(id).await {
-        Ok(true) => Ok(Json(ApiResponse::success(()))),
-        Ok(false) => Err(StatusCode::NOT_FOUND),
-        Err(e) => {
-            tracing::error!("Failed to delete tag {}: {}", id, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
+) -> ApiResult<()> {
+    member.require(WorkspaceRole::Admin)?;
+    if state.db.delete_tag(member.workspace.id, id).await? {
+        state.events.publish(member.workspace.id, LiveEvent::TagDeleted { id }).await;
+        Ok(Json(ApiResponse::success(())))
+    } else {
+        Err(ApiError::NotFound("Tag"))
+    }
+}
+
+// Client handlers
+#[utoipa::path(
+    get,
+    path = "/api/clients",
+    tag = "clients",
+    params(WorkspaceParam),
+    responses((status = 200, description = "Success", body = ApiResponse<Vec<Client>>)),
+)]
+pub async fn get_clients(
+    State(state): State<AppState>,
+    member: Membership,
+) -> ApiResult<Vec<Client>> {
+    member.require(WorkspaceRole::Viewer)?;
+    let clients = state.db.get_clients(member.workspace.id).await?;
+    Ok(Json(ApiResponse::success(clients)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/clients/{id}",
+    tag = "clients",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Client id")),
+    responses((status = 200, description = "Success", body = ApiResponse<Client>)),
+)]
+pub async fn get_client(
+    State(state): State<AppState>,
+    member: Membership,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Client> {
+    member.require(WorkspaceRole::Viewer)?;
+    let client = state.db.get_client(member.workspace.id, id)
+        .await?
+        .ok_or(ApiError::NotFound("Client"))?;
+    Ok(Json(ApiResponse::success(client)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/clients",
+    tag = "clients",
+    params(WorkspaceParam),
+    request_body = CreateClientRequest,
+    responses((status = 200, description = "Success", body = ApiResponse<Client>)),
+)]
+pub async fn create_client(
+    State(state): State<AppState>,
+    member: Membership,
+    Json(req): Json<CreateClientRequest>,
+) -> ApiResult<Client> {
+    member.require(WorkspaceRole::Member)?;
+    req.validate()?;
+    let client = state.db.create_client(member.workspace.id, req).await?;
+    Ok(Json(ApiResponse::success(client)))
+}
+
+/// Replaces a client with a full representation.
+#[utoipa::path(
+    put,
+    path = "/api/clients/{id}",
+    tag = "clients",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Client id")),
+    request_body = CreateClientRequest,
+    responses((status = 200, description = "Success", body = ApiResponse<Client>)),
+)]
+pub async fn replace_client(
+    State(state): State<AppState>,
+    member: Membership,
+    Path(id): Path<Uuid>,
+    Json(req): Json<CreateClientRequest>,
+) -> ApiResult<Client> {
+    member.require(WorkspaceRole::Admin)?;
+    req.validate()?;
+    let client = state.db.replace_client(member.workspace.id, id, req)
+        .await?
+        .ok_or(ApiError::NotFound("Client"))?;
+    Ok(Json(ApiResponse::success(client)))
+}
+
+/// Applies a JSON Merge Patch to a client.
+#[utoipa::path(
+    patch,
+    path = "/api/clients/{id}",
+    tag = "clients",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Client id")),
+    request_body(content = UpdateClientRequest, content_type = "application/merge-patch+json"),
+    responses((status = 200, description = "Success", body = ApiResponse<Client>)),
+)]
+pub async fn update_client(
+    State(state): State<AppState>,
+    member: Membership,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateClientRequest>,
+) -> ApiResult<Client> {
+    member.require(WorkspaceRole::Admin)?;
+    req.validate()?;
+    let client = state.db.update_client(member.workspace.id, id, req)
+        .await?
+        .ok_or(ApiError::NotFound("Client"))?;
+    Ok(Json(ApiResponse::success(client)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/clients/{id}",
+    tag = "clients",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Client id")),
+    responses((status = 200, description = "Success", body = ApiResponse<NoData>)),
+)]
+pub async fn delete_client(
+    State(state): State<AppState>,
+    member: Membership,
+    Path(id): Path<Uuid>,
+) -> ApiResult<()> {
+    member.require(WorkspaceRole::Admin)?;
+    if state.db.delete_client(member.workspace.id, id).await? {
+        Ok(Json(ApiResponse::success(())))
+    } else {
+        Err(ApiError::NotFound("Client"))
+    }
+}
+
+// Project handlers
+#[utoipa::path(
+    get,
+    path = "/api/projects",
+    tag = "projects",
+    params(WorkspaceParam),
+    responses((status = 200, description = "Success", body = ApiResponse<Vec<Project>>)),
+)]
+pub async fn get_projects(
+    State(state): State<AppState>,
+    member: Membership,
+) -> ApiResult<Vec<Project>> {
+    member.require(WorkspaceRole::Viewer)?;
+    let projects = state.db.get_projects(member.workspace.id).await?;
+    Ok(Json(ApiResponse::success(projects)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/projects/{id}",
+    tag = "projects",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Project id")),
+    responses((status = 200, description = "Success", body = ApiResponse<Project>)),
+)]
+pub async fn get_project(
+    State(state): State<AppState>,
+    member: Membership,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Project> {
+    member.require(WorkspaceRole::Viewer)?;
+    let project = state.db.get_project(member.workspace.id, id)
+        .await?
+        .ok_or(ApiError::NotFound("Project"))?;
+    Ok(Json(ApiResponse::success(project)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/projects",
+    tag = "projects",
+    params(WorkspaceParam),
+    request_body = CreateProjectRequest,
+    responses((status = 200, description = "Success", body = ApiResponse<Project>)),
+)]
+pub async fn create_project(
+    State(state): State<AppState>,
+    member: Membership,
+    Json(req): Json<CreateProjectRequest>,
+) -> ApiResult<Project> {
+    member.require(WorkspaceRole::Member)?;
+    req.validate()?;
+    let project = state.db.create_project(member.workspace.id, req).await?;
+    Ok(Json(ApiResponse::success(project)))
+}
+
+/// Replaces a project with a full representation.
+#[utoipa::path(
+    put,
+    path = "/api/projects/{id}",
+    tag = "projects",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Project id")),
+    request_body = CreateProjectRequest,
+    responses((status = 200, description = "Success", body = ApiResponse<Project>)),
+)]
+pub async fn replace_project(
+    State(state): State<AppState>,
+    member: Membership,
+    Path(id): Path<Uuid>,
+    Json(req): Json<CreateProjectRequest>,
+) -> ApiResult<Project> {
+    member.require(WorkspaceRole::Admin)?;
+    req.validate()?;
+    let project = state.db.replace_project(member.workspace.id, id, req)
+        .await?
+        .ok_or(ApiError::NotFound("Project"))?;
+    Ok(Json(ApiResponse::success(project)))
+}
+
+/// Applies a JSON Merge Patch to a project.
+#[utoipa::path(
+    patch,
+    path = "/api/projects/{id}",
+    tag = "projects",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Project id")),
+    request_body(content = UpdateProjectRequest, content_type = "application/merge-patch+json"),
+    responses((status = 200, description = "Success", body = ApiResponse<Project>)),
+)]
+pub async fn update_project(
+    State(state): State<AppState>,
+    member: Membership,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateProjectRequest>,
+) -> ApiResult<Project> {
+    member.require(WorkspaceRole::Admin)?;
+    req.validate()?;
+    let project = state.db.update_project(member.workspace.id, id, req)
+        .await?
+        .ok_or(ApiError::NotFound("Project"))?;
+    Ok(Json(ApiResponse::success(project)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/projects/{id}",
+    tag = "projects",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Project id")),
+    responses((status = 200, description = "Success", body = ApiResponse<NoData>)),
+)]
+pub async fn delete_project(
+    State(state): State<AppState>,
+    member: Membership,
+    Path(id): Path<Uuid>,
+) -> ApiResult<()> {
+    member.require(WorkspaceRole::Admin)?;
+    if state.db.delete_project(member.workspace.id, id).await? {
+        Ok(Json(ApiResponse::success(())))
+    } else {
+        Err(ApiError::NotFound("Project"))
+    }
+}
+
+// Report handlers
+#[utoipa::path(
+    get,
+    path = "/api/reports",
+    tag = "reports",
+    params(WorkspaceParam, ReportQuery),
+    responses((status = 200, description = "Success", body = ApiResponse<Report>)),
+)]
+pub async fn get_report(
+    State(state): State<AppState>,
+    member: Membership,
+    Query(query): Query<ReportQuery>,
+) -> ApiResult<Report> {
+    member.require(WorkspaceRole::Viewer)?;
+    let report = state.db.get_report(&member.workspace, query).await?;
+    Ok(Json(ApiResponse::success(report)))
+}
+
+// Invoice handlers
+#[utoipa::path(
+    get,
+    path = "/api/invoices",
+    tag = "invoices",
+    params(WorkspaceParam),
+    responses((status = 200, description = "Success", body = ApiResponse<Vec<Invoice>>)),
+)]
+pub async fn get_invoices(
+    State(state): State<AppState>,
+    member: Membership,
+) -> ApiResult<Vec<Invoice>> {
+    member.require(WorkspaceRole::Viewer)?;
+    let invoices = state.db.get_invoices(member.workspace.id).await?;
+    Ok(Json(ApiResponse::success(invoices)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/invoices/{id}",
+    tag = "invoices",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Invoice id")),
+    responses((status = 200, description = "Success", body = ApiResponse<Invoice>)),
+)]
+pub async fn get_invoice(
+    State(state): State<AppState>,
+    member: Membership,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Invoice> {
+    member.require(WorkspaceRole::Viewer)?;
+    let invoice = state.db.get_invoice(member.workspace.id, id)
+        .await?
+        .ok_or(ApiError::NotFound("Invoice"))?;
+    Ok(Json(ApiResponse::success(invoice)))
+}
+
+/// Invoices the matching billable sessions, locking them from changes.
+#[utoipa::path(
+    post,
+    path = "/api/invoices",
+    tag = "invoices",
+    params(WorkspaceParam),
+    request_body = CreateInvoiceRequest,
+    responses((status = 200, description = "Success", body = ApiResponse<Invoice>)),
+)]
+pub async fn create_invoice(
+    State(state): State<AppState>,
+    member: Membership,
+    Json(req): Json<CreateInvoiceRequest>,
+) -> ApiResult<Invoice> {
+    member.require(WorkspaceRole::Admin)?;
+    req.validate()?;
+    let invoice = state.db.create_invoice(&member.workspace, member.user.id, req).await?;
+    Ok(Json(ApiResponse::success(invoice)))
+}
+
+/// Deletes an invoice and unlocks its sessions.
+#[utoipa::path(
+    delete,
+    path = "/api/invoices/{id}",
+    tag = "invoices",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Invoice id")),
+    responses((status = 200, description = "Success", body = ApiResponse<NoData>)),
+)]
+pub async fn delete_invoice(
+    State(state): State<AppState>,
+    member: Membership,
+    Path(id): Path<Uuid>,
+) -> ApiResult<()> {
+    member.require(WorkspaceRole::Admin)?;
+    if state.db.delete_invoice(member.workspace.id, id).await? {
+        Ok(Json(ApiResponse::success(())))
+    } else {
+        Err(ApiError::NotFound("Invoice"))
     }
-}
\ No newline at end of file
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/invoices/{id}/html",
+    tag = "invoices",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Invoice id")),
+    responses((status = 200, description = "The invoice as an HTML page", content_type = "text/html", body = String)),
+)]
+pub async fn get_invoice_html(
+    State(state): State<AppState>,
+    member: Membership,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    member.require(WorkspaceRole::Viewer)?;
+    let invoice = state.db.get_invoice(member.workspace.id, id)
+        .await?
+        .ok_or(ApiError::NotFound("Invoice"))?;
+    let html = invoice_render::to_html(&invoice, &member.workspace.name);
+    Ok(([(header::CONTENT_TYPE, "text/html; charset=utf-8")], html))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/invoices/{id}/pdf",
+    tag = "invoices",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Invoice id")),
+    responses((status = 200, description = "The invoice as a PDF document", content_type = "application/pdf", body = Vec<u8>)),
+)]
+pub async fn get_invoice_pdf(
+    State(state): State<AppState>,
+    member: Membership,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    member.require(WorkspaceRole::Viewer)?;
+    let invoice = state.db.get_invoice(member.workspace.id, id)
+        .await?
+        .ok_or(ApiError::NotFound("Invoice"))?;
+    let pdf = invoice_render::to_pdf(&invoice, &member.workspace.name)?;
+    let disposition = format!("inline; filename=\"invoice-{}.pdf\"", invoice.number);
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        pdf,
+    ))
+}
+
+// Live updates
+/// Streams the workspace's session and tag changes as Server-Sent Events.
+#[utoipa::path(
+    get,
+    path = "/api/events",
+    tag = "events",
+    params(WorkspaceParam),
+    responses((status = 200, description = "A `change` event for every session or tag change in the workspace", content_type = "text/event-stream", body = LiveEvent)),
+)]
+pub async fn get_events(
+    State(state): State<AppState>,
+    member: Membership,
+) -> Result<impl IntoResponse, ApiError> {
+    member.require(WorkspaceRole::Viewer)?;
+    let changes = state
+        .events
+        .subscribe(member.workspace.id)
+        .map(|event| Event::default().event("change").json_data(event));
+
+    // Keeps nginx from buffering the stream
+    Ok(([("x-accel-buffering", "no")], Sse::new(changes).keep_alive(KeepAlive::default())))
+}
+
+// Webhook handlers
+#[utoipa::path(
+    get,
+    path = "/api/webhooks",
+    tag = "webhooks",
+    params(WorkspaceParam),
+    responses((status = 200, description = "Success", body = ApiResponse<Vec<Webhook>>)),
+)]
+pub async fn get_webhooks(
+    State(state): State<AppState>,
+    member: Membership,
+) -> ApiResult<Vec<Webhook>> {
+    member.require(WorkspaceRole::Admin)?;
+    let webhooks = state.db.get_webhooks(member.workspace.id).await?;
+    Ok(Json(ApiResponse::success(webhooks)))
+}
+
+/// Creates a webhook. The response is the only time its secret is shown.
+#[utoipa::path(
+    post,
+    path = "/api/webhooks",
+    tag = "webhooks",
+    params(WorkspaceParam),
+    request_body = CreateWebhookRequest,
+    responses((status = 200, description = "Success", body = ApiResponse<CreatedWebhook>)),
+)]
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    member: Membership,
+    Json(req): Json<CreateWebhookRequest>,
+) -> ApiResult<CreatedWebhook> {
+    member.require(WorkspaceRole::Admin)?;
+    req.validate()?;
+    let secret = req
+        .secret
+        .clone()
+        .unwrap_or_else(|| format!("whsec_{}", auth::generate_token()));
+    let webhook = state
+        .db
+        .create_webhook(member.workspace.id, member.user.id, &req, &secret)
+        .await?;
+    Ok(Json(ApiResponse::success(CreatedWebhook { webhook, secret })))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{id}",
+    tag = "webhooks",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Webhook id")),
+    responses((status = 200, description = "Success", body = ApiResponse<NoData>)),
+)]
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    member: Membership,
+    Path(id): Path<Uuid>,
+) -> ApiResult<()> {
+    member.require(WorkspaceRole::Admin)?;
+    if state.db.delete_webhook(member.workspace.id, id).await? {
+        Ok(Json(ApiResponse::success(())))
+    } else {
+        Err(ApiError::NotFound("Webhook"))
+    }
+}
+
+/// The webhook's delivery log, newest first.
+#[utoipa::path(
+    get,
+    path = "/api/webhooks/{id}/deliveries",
+    tag = "webhooks",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Webhook id")),
+    responses((status = 200, description = "Success", body = ApiResponse<Vec<WebhookDelivery>>)),
+)]
+pub async fn get_webhook_deliveries(
+    State(state): State<AppState>,
+    member: Membership,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Vec<WebhookDelivery>> {
+    member.require(WorkspaceRole::Admin)?;
+    let deliveries = state.db.get_webhook_deliveries(member.workspace.id, id)
+        .await?
+        .ok_or(ApiError::NotFound("Webhook"))?;
+    Ok(Json(ApiResponse::success(deliveries)))
+}
+
+/// Queues a `ping` delivery to check that the endpoint is reachable.
+#[utoipa::path(
+    post,
+    path = "/api/webhooks/{id}/ping",
+    tag = "webhooks",
+    params(WorkspaceParam, ("id" = Uuid, Path, description = "Webhook id")),
+    responses((status = 200, description = "Success", body = ApiResponse<WebhookDelivery>)),
+)]
+pub async fn ping_webhook(
+    State(state): State<AppState>,
+    member: Membership,
+    Path(id): Path<Uuid>,
+) -> ApiResult<WebhookDelivery> {
+    member.require(WorkspaceRole::Admin)?;
+    let delivery = state.db.queue_ping(member.workspace.id, id)
+        .await?
+        .ok_or(ApiError::NotFound("Webhook"))?;
+    Ok(Json(ApiResponse::success(delivery)))
+}
+
+// Workspace handlers
+#[utoipa::path(
+    get,
+    path = "/api/workspaces",
+    tag = "workspaces",
+    responses((status = 200, description = "Success", body = ApiResponse<Vec<Workspace>>)),
+)]
+pub async fn get_workspaces(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+) -> ApiResult<Vec<Workspace>> {
+    let workspaces = state.db.get_workspaces(user.id).await?;
+    Ok(Json(ApiResponse::success(workspaces)))
+}
+
+/// Creates a workspace with the caller as its owner.
+#[utoipa::path(
+    post,
+    path = "/api/workspaces",
+    tag = "workspaces",
+    request_body = WorkspaceRequest,
+    responses((status = 200, description = "Success", body = ApiResponse<Workspace>)),
+)]
+pub async fn create_workspace(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Json(req): Json<WorkspaceRequest>,
+) -> ApiResult<Workspace> {
+    req.validate()?;
+    let workspace = state.db.create_workspace(user.id, req.name.trim(), &req.currency).await?;
+    Ok(Json(ApiResponse::success(workspace)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{id}",
+    tag = "workspaces",
+    params(("id" = Uuid, Path, description = "Workspace id")),
+    request_body = WorkspaceRequest,
+    responses((status = 200, description = "Success", body = ApiResponse<Workspace>)),
+)]
+pub async fn replace_workspace(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Uuid>,
+    Json(req): Json<WorkspaceRequest>,
+) -> ApiResult<Workspace> {
+    let member = Membership::load(&state, user, Some(id)).await?;
+    member.require(WorkspaceRole::Admin)?;
+    req.validate()?;
+    let workspace = state.db.update_workspace(&member.workspace, req.name.trim(), &req.currency).await?;
+    Ok(Json(ApiResponse::success(workspace)))
+}
+
+/// Deletes a workspace along with its sessions and tags.
+#[utoipa::path(
+    delete,
+    path = "/api/workspaces/{id}",
+    tag = "workspaces",
+    params(("id" = Uuid, Path, description = "Workspace id")),
+    responses((status = 200, description = "Success", body = ApiResponse<NoData>)),
+)]
+pub async fn delete_workspace(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<()> {
+    let member = Membership::load(&state, user, Some(id)).await?;
+    member.require(WorkspaceRole::Owner)?;
+    state.db.delete_workspace(id).await?;
+    Ok(Json(ApiResponse::success(())))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{id}/members",
+    tag = "workspaces",
+    params(("id" = Uuid, Path, description = "Workspace id")),
+    responses((status = 200, description = "Success", body = ApiResponse<Vec<WorkspaceMember>>)),
+)]
+pub async fn get_members(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Vec<WorkspaceMember>> {
+    let member = Membership::load(&state, user, Some(id)).await?;
+    member.require(WorkspaceRole::Viewer)?;
+    let members = state.db.get_members(id).await?;
+    Ok(Json(ApiResponse::success(members)))
+}
+
+/// Adds an existing account to a workspace. Admins cannot grant a role
+/// above their own.
+#[utoipa::path(
+    post,
+    path = "/api/workspaces/{id}/members",
+    tag = "workspaces",
+    params(("id" = Uuid, Path, description = "Workspace id")),
+    request_body = AddMemberRequest,
+    responses((status = 200, description = "Success", body = ApiResponse<WorkspaceMember>)),
+)]
+pub async fn add_member(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Uuid>,
+    Json(req): Json<AddMemberRequest>,
+) -> ApiResult<WorkspaceMember> {
+    let member = Membership::load(&state, user, Some(id)).await?;
+    member.require(WorkspaceRole::Admin)?;
+    member.require(req.role)?;
+    let added = state.db.add_member(id, req.email.trim(), req.role).await?;
+    Ok(Json(ApiResponse::success(added)))
+}
+
+/// Changes a member's role. Admins can only manage members whose role, old
+/// and new, is not above their own.
+#[utoipa::path(
+    patch,
+    path = "/api/workspaces/{id}/members/{user_id}",
+    tag = "workspaces",
+    params(("id" = Uuid, Path, description = "Workspace id"), ("user_id" = Uuid, Path, description = "Member user id")),
+    request_body(content = UpdateMemberRequest, content_type = "application/merge-patch+json"),
+    responses((status = 200, description = "Success", body = ApiResponse<WorkspaceMember>)),
+)]
+pub async fn update_member(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path((id, user_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpdateMemberRequest>,
+) -> ApiResult<WorkspaceMember> {
+    let member = Membership::load(&state, user, Some(id)).await?;
+    member.require(WorkspaceRole::Admin)?;
+    let target = state.db.get_member(id, user_id)
+        .await?
+        .ok_or(ApiError::NotFound("Member"))?;
+    member.require(target.role)?;
+    member.require(req.role)?;
+
+    let updated = state.db.update_member_role(id, user_id, req.role)
+        .await?
+        .ok_or(ApiError::NotFound("Member"))?;
+    Ok(Json(ApiResponse::success(updated)))
+}
+
+/// Removes a member. Anyone may leave a workspace; removing others takes an
+/// admin whose role is not below theirs.
+#[utoipa::path(
+    delete,
+    path = "/api/workspaces/{id}/members/{user_id}",
+    tag = "workspaces",
+    params(("id" = Uuid, Path, description = "Workspace id"), ("user_id" = Uuid, Path, description = "Member user id")),
+    responses((status = 200, description = "Success", body = ApiResponse<NoData>)),
+)]
+pub async fn remove_member(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+    Path((id, user_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<()> {
+    let member = Membership::load(&state, user, Some(id)).await?;
+    if user_id != member.user.id {
+        member.require(WorkspaceRole::Admin)?;
+        let target = state.db.get_member(id, user_id)
+            .await?
+            .ok_or(ApiError::NotFound("Member"))?;
+        member.require(target.role)?;
+    }
+
+    if state.db.remove_member(id, user_id).await? {
+        Ok(Json(ApiResponse::success(())))
+    } else {
+        Err(ApiError::NotFound("Member"))
+    }
+}
+
+// API token handlers
+#[utoipa::path(
+    get,
+    path = "/api/tokens",
+    tag = "tokens",
+    responses((status = 200, description = "Success", body = ApiResponse<Vec<ApiToken>>)),
+)]
+pub async fn get_api_tokens(
+    State(state): State<AppState>,
+    CurrentUser(user): CurrentUser,
+) -> ApiResult<Vec<ApiToken>> {
+    let tokens = state.db.get_api_tokens(user.id).await?;
+    Ok(Json(ApiResponse::success(tokens)))
+}
+
+/// Creates a token. The response is the only time the token is shown.
+/// Requires the login cookie; API tokens cannot create tokens.
+#[utoipa::path(
+    post,
+    path = "/api/tokens",
+    tag = "tokens",
+    request_body = CreateApiTokenRequest,
+    responses((status = 200, description = "Success", body = ApiResponse<CreatedApiToken>)),
+)]
+pub async fn create_api_token(
+    State(state): State<AppState>,
+    LoggedInUser(user): LoggedInUser,
+    Json(req): Json<CreateApiTokenRequest>,
+) -> ApiResult<CreatedApiToken> {
+    req.validate()?;
+    let token = auth::generate_api_token();
+    let api_token = state
+        .db
+        .create_api_token(user.id, &req, &auth::hash_token(&token))
+        .await?;
+    Ok(Json(ApiResponse::success(CreatedApiToken { api_token, token })))
+}
+
+/// Revokes a token. Requires the login cookie, like creating one.
+#[utoipa::path(
+    delete,
+    path = "/api/tokens/{id}",
+    tag = "tokens",
+    params(("id" = Uuid, Path, description = "Token id")),
+    responses((status = 200, description = "Success", body = ApiResponse<NoData>)),
+)]
+pub async fn delete_api_token(
+    State(state): State<AppState>,
+    LoggedInUser(user): LoggedInUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<()> {
+    if state.db.delete_api_token(user.id, id).await? {
+        Ok(Json(ApiResponse::success(())))
+    } else {
+        Err(ApiError::NotFound("API token"))
+    }
+}
+
+// Auth handlers
+/// Creates an account and logs it in.
+#[utoipa::path(
+    post,
+    path = "/api/auth/register",
+    tag = "auth",
+    request_body = RegisterRequest,
+    responses((status = 200, description = "Sets the login cookie", body = ApiResponse<User>)),
+    security(()),
+)]
+pub async fn register(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(req): Json<RegisterRequest>,
+) -> Result<(CookieJar, Json<ApiResponse<User>>), ApiError> {
+    req.validate()?;
+    let password = req.password;
+    let password_hash = tokio::task::spawn_blocking(move || auth::hash_password(&password))
+        .await
+        .map_err(|e| ApiError::Internal(e.into()))??;
+
+    let user = state.db.create_user(req.email.trim(), &password_hash).await?;
+    let jar = log_in(&state, jar, &user).await?;
+    Ok((jar, Json(ApiResponse::success(user))))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses((status = 200, description = "Sets the login cookie", body = ApiResponse<User>)),
+    security(()),
+)]
+pub async fn login(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(req): Json<LoginRequest>,
+) -> Result<(CookieJar, Json<ApiResponse<User>>), ApiError> {
+    let (user, password_hash) = state
+        .db
+        .get_user_credentials(req.email.trim())
+        .await?
+        .ok_or(ApiError::Unauthorized("Invalid email or password"))?;
+
+    let password = req.password;
+    let valid = tokio::task::spawn_blocking(move || auth::verify_password(&password, &password_hash))
+        .await
+        .map_err(|e| ApiError::Internal(e.into()))?;
+    if !valid {
+        return Err(ApiError::Unauthorized("Invalid email or password"));
+    }
+
+    let jar = log_in(&state, jar, &user).await?;
+    Ok((jar, Json(ApiResponse::success(user))))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/logout",
+    tag = "auth",
+    responses((status = 200, description = "Clears the login cookie", body = ApiResponse<NoData>)),
+)]
+pub async fn logout(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<(CookieJar, Json<ApiResponse<()>>), ApiError> {
+    if let Some(cookie) = jar.get(auth::LOGIN_COOKIE) {
+        state.db.delete_login_session(&auth::hash_token(cookie.value())).await?;
+    }
+    let jar = jar.remove(Cookie::build(auth::LOGIN_COOKIE).path("/"));
+    Ok((jar, Json(ApiResponse::success(()))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/auth/me",
+    tag = "auth",
+    responses((status = 200, description = "Success", body = ApiResponse<User>)),
+)]
+pub async fn current_user(CurrentUser(user): CurrentUser) -> ApiResult<User> {
+    Ok(Json(ApiResponse::success(user)))
+}
+
+/// Starts a login for `user` and sets its cookie.
+async fn log_in(state: &AppState, jar: CookieJar, user: &User) -> Result<CookieJar, ApiError> {
+    let token = auth::generate_token();
+    let expires_at = chrono::Utc::now() + auth::login_lifetime();
+    state
+        .db
+        .create_login_session(user.id, &auth::hash_token(&token), expires_at)
+        .await?;
+    Ok(jar.add(auth::login_cookie(token, state.secure_cookies)))
+}
+
+/// Rejects changes to a session the caller may not edit.
+async fn check_session_access(state: &AppState, member: &Membership, id: Uuid) -> Result<(), ApiError> {
+    let owner = state
+        .db
+        .get_session_owner(member.workspace.id, id)
+        .await?
+        .ok_or(ApiError::NotFound("Session"))?;
+    member.require_session_access(owner)
+}
+
+/// Broadcasts a session as it now reads, tags and all. Failing to load it
+/// is only logged, as the change itself has already been made.
+async fn publish_session(state: &AppState, workspace_id: Uuid, id: Uuid, created: bool) {
+    match state.db.get_session(workspace_id, id).await {
+        Ok(Some(session)) if created => state.events.publish(workspace_id, LiveEvent::SessionCreated(session)).await,
+        Ok(Some(session)) => state.events.publish(workspace_id, LiveEvent::SessionUpdated(session)).await,
+        Ok(None) => {}
+        Err(e) => tracing::error!("Failed to load session {id} to publish: {e:#}"),
+    }
+}
+
+/// Whether the backend and its database are up, for container healthchecks.
+#[utoipa::path(
+    get,
+    path = "/api/health",
+    tag = "health",
+    security(()),
+    responses((status = 200, description = "The backend and its database are up", body = ApiResponse<NoData>)),
+)]
+pub async fn health(State(state): State<AppState>) -> ApiResult<()> {
+    state.db.ping().await?;
+    Ok(Json(ApiResponse::success(())))
+}
+
+pub async fn not_found() -> ApiError {
+    ApiError::NotFound("Route")
+}
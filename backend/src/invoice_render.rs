@@ -0,0 +1,207 @@
+use anyhow::Result;
+use printpdf::{BuiltinFont, IndirectFontRef, Line, Mm, PdfDocument, PdfLayerReference, Point};
+use rust_decimal::Decimal;
+use shared::Invoice;
+
+/// Line items that fit on one PDF page below the header.
+const LINES_PER_PAGE: usize = 30;
+
+/// Hours worked, rounded to two decimal places.
+fn hours(seconds: i64) -> Decimal {
+    (Decimal::from(seconds) / Decimal::from(3600)).round_dp(2)
+}
+
+fn title(invoice: &Invoice) -> String {
+    format!("Invoice #{}", invoice.number)
+}
+
+fn period(invoice: &Invoice) -> String {
+    format!("{} to {}", invoice.from, invoice.to)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Renders an invoice as a standalone HTML page.
+pub fn to_html(invoice: &Invoice, workspace_name: &str) -> String {
+    let mut rows = String::new();
+    for line in &invoice.lines {
+        rows.push_str(&format!(
+            "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+            escape_html(&line.description),
+            line.session_count,
+            hours(line.seconds),
+            line.amount,
+        ));
+    }
+
+    let client = invoice
+        .client_name
+        .as_deref()
+        .map(|name| format!("<p>Bill to: {}</p>\n", escape_html(name)))
+        .unwrap_or_default();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ border-bottom: 1px solid #ddd; padding: 0.4em; text-align: left; }}
+.num {{ text-align: right; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p>{workspace}</p>
+{client}<p>Period: {period}</p>
+<table>
+<tr><th>Item</th><th class="num">Sessions</th><th class="num">Hours</th><th class="num">Amount ({currency})</th></tr>
+{rows}<tr><th colspan="3">Total</th><th class="num">{total} {currency}</th></tr>
+</table>
+</body>
+</html>
+"#,
+        title = title(invoice),
+        workspace = escape_html(workspace_name),
+        period = period(invoice),
+        currency = invoice.currency,
+        total = invoice.total,
+    )
+}
+
+/// Builtin PDF fonts only cover Windows-1252, so other characters are
+/// replaced.
+fn pdf_text(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_ascii() || ('\u{a0}'..='\u{ff}').contains(&c) { c } else { '?' })
+        .collect()
+}
+
+fn rule(layer: &PdfLayerReference, y: f32) {
+    layer.add_line(Line {
+        points: vec![(Point::new(Mm(20.0), Mm(y)), false), (Point::new(Mm(190.0), Mm(y)), false)],
+        is_closed: false,
+    });
+}
+
+fn table_row(layer: &PdfLayerReference, font: &IndirectFontRef, y: f32, cells: [&str; 4]) {
+    layer.use_text(pdf_text(cells[0]), 10.0, Mm(20.0), Mm(y), font);
+    layer.use_text(cells[1], 10.0, Mm(120.0), Mm(y), font);
+    layer.use_text(cells[2], 10.0, Mm(145.0), Mm(y), font);
+    layer.use_text(cells[3], 10.0, Mm(165.0), Mm(y), font);
+}
+
+/// Renders an invoice as an A4 PDF document.
+pub fn to_pdf(invoice: &Invoice, workspace_name: &str) -> Result<Vec<u8>> {
+    let (doc, page, layer) = PdfDocument::new(title(invoice), Mm(210.0), Mm(297.0), "Invoice");
+    let font = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+    let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+    let mut layer = doc.get_page(page).get_layer(layer);
+
+    layer.use_text(title(invoice), 20.0, Mm(20.0), Mm(270.0), &bold);
+    layer.use_text(pdf_text(workspace_name), 12.0, Mm(20.0), Mm(260.0), &font);
+    let mut y = 252.0;
+    if let Some(client) = &invoice.client_name {
+        layer.use_text(pdf_text(&format!("Bill to: {client}")), 12.0, Mm(20.0), Mm(y), &font);
+        y -= 8.0;
+    }
+    layer.use_text(format!("Period: {}", period(invoice)), 12.0, Mm(20.0), Mm(y), &font);
+    y -= 16.0;
+
+    let amount_header = format!("Amount ({})", invoice.currency);
+    table_row(&layer, &bold, y, ["Item", "Sessions", "Hours", &amount_header]);
+    rule(&layer, y - 2.0);
+    y -= 8.0;
+
+    for (i, line) in invoice.lines.iter().enumerate() {
+        if i > 0 && i % LINES_PER_PAGE == 0 {
+            let (page, next) = doc.add_page(Mm(210.0), Mm(297.0), "Invoice");
+            layer = doc.get_page(page).get_layer(next);
+            y = 270.0;
+        }
+        table_row(
+            &layer,
+            &font,
+            y,
+            [
+                &line.description,
+                &line.session_count.to_string(),
+                &hours(line.seconds).to_string(),
+                &line.amount.to_string(),
+            ],
+        );
+        y -= 7.0;
+    }
+
+    rule(&layer, y + 4.0);
+    let total = format!("{} {}", invoice.total, invoice.currency);
+    table_row(&layer, &bold, y - 2.0, ["Total", "", "", &total]);
+
+    Ok(doc.save_to_bytes()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, Utc};
+    use printpdf::lopdf;
+    use shared::{InvoiceGrouping, InvoiceLine};
+    use uuid::Uuid;
+
+    fn invoice(line_count: usize) -> Invoice {
+        let lines: Vec<_> = (1..=line_count)
+            .map(|day| InvoiceLine {
+                description: format!("Day {day}"),
+                seconds: 3600,
+                session_count: 1,
+                amount: Decimal::from(100),
+            })
+            .collect();
+        Invoice {
+            id: Uuid::new_v4(),
+            number: 1,
+            client_id: None,
+            client_name: Some("Acme & Sons".to_string()),
+            tag_id: None,
+            from: NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(),
+            to: NaiveDate::from_ymd_opt(2024, 5, 31).unwrap(),
+            group_by: InvoiceGrouping::Day,
+            currency: "EUR".to_string(),
+            total: Decimal::from(100 * line_count as i64),
+            created_at: Utc::now(),
+            lines,
+        }
+    }
+
+    #[test]
+    fn html_special_characters_are_escaped() {
+        assert_eq!(escape_html(r#"<a href="x">Tom & Jerry's</a>"#), "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;");
+        let html = to_html(&invoice(1), "<Studio>");
+        assert!(html.contains("<p>&lt;Studio&gt;</p>"));
+        assert!(html.contains("<p>Bill to: Acme &amp; Sons</p>"));
+    }
+
+    #[test]
+    fn pdf_text_replaces_characters_outside_latin_1() {
+        assert_eq!(pdf_text("Café crème, 5 €"), "Café crème, 5 ?");
+        assert_eq!(pdf_text("日本 – review"), "?? ? review");
+    }
+
+    #[test]
+    fn pdf_starts_a_new_page_every_30_lines() -> Result<()> {
+        for (line_count, pages) in [(1, 1), (30, 1), (31, 2), (61, 3)] {
+            let pdf = to_pdf(&invoice(line_count), "Studio")?;
+            assert_eq!(lopdf::Document::load_mem(&pdf)?.get_pages().len(), pages, "{line_count} lines");
+        }
+        Ok(())
+    }
+}
@@ -1,23 +1,39 @@
 use axum::{
-    http::Method,
-    routing::{delete, get, post, put},
-    Router,
+    extract::DefaultBodyLimit,
+    http::{header, HeaderValue, Method},
+    middleware,
+    routing::get,
 };
 use sqlx::PgPool;
 use std::sync::Arc;
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::cors::CorsLayer;
 use tracing::info;
 use tracing_subscriber::fmt::init;
+use utoipa::OpenApi;
+use utoipa_axum::{router::OpenApiRouter, routes};
 
+mod auth;
 mod db;
+mod error;
+mod events;
+mod extract;
 mod handlers;
+mod invoice_render;
+mod openapi;
+mod session_csv;
+mod webhooks;
 
 use db::Database;
+use events::Events;
 use handlers::*;
+use openapi::ApiDoc;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: Arc<Database>,
+    /// Mark login cookies `Secure`, for deployments served over HTTPS
+    pub secure_cookies: bool,
+    pub events: Events,
 }
 
 #[tokio::main]
@@ -36,28 +52,33 @@ async fn main() -> anyhow::Result<()> {
     sqlx::migrate!("./migrations").run(&pool).await?;
 
     let db = Arc::new(Database::new(pool));
-    let app_state = AppState { db };
+    // Internal hosts webhooks may reach, e.g. a receiver next to the backend
+    let webhook_hosts = webhooks::AllowedHosts::parse(&std::env::var("WEBHOOK_ALLOWED_HOSTS").unwrap_or_default());
+    webhooks::spawn_worker(db.clone(), webhook_hosts)?;
 
-    // Configure CORS for Railway deployment
+    let secure_cookies = std::env::var("COOKIE_SECURE").is_ok_and(|v| v == "true");
+    // Multi-instance deployments share changes through Postgres
+    let events = if std::env::var("EVENTS_NOTIFY").is_ok_and(|v| v == "true") {
+        Events::shared(db.clone()).await?
+    } else {
+        Events::local()
+    };
+    let app_state = AppState { db, secure_cookies, events };
+
+    // Login cookies are only sent cross-origin to the listed frontends. In
+    // production the frontend is served from the same origin via nginx.
+    let allowed_origins = std::env::var("ALLOWED_ORIGINS")
+        .unwrap_or_else(|_| "http://localhost:8000,http://127.0.0.1:8000".to_string())
+        .split(',')
+        .filter_map(|origin| HeaderValue::from_str(origin.trim()).ok())
+        .collect::<Vec<_>>();
     let cors = CorsLayer::new()
-        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
-        .allow_headers(Any)
-        .allow_origin(Any) // In production, consider restricting to specific domains
-        .allow_credentials(false);
-
-    let app = Router::new()
-        .route("/api/sessions", get(get_sessions))
-        .route("/api/sessions", post(create_session))
-        .route("/api/sessions/:id", get(get_session))
-        .route("/api/sessions/:id", put(update_session))
-        .route("/api/sessions/:id", delete(delete_session))
-        .route("/api/tags", get(get_tags))
-        .route("/api/tags", post(create_tag))
-        .route("/api/tags/:id", get(get_tag))
-        .route("/api/tags/:id", put(update_tag))
-        .route("/api/tags/:id", delete(delete_tag))
-        .layer(cors)
-        .with_state(app_state);
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::PATCH, Method::DELETE])
+        .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE])
+        .allow_origin(allowed_origins)
+        .allow_credentials(true);
+
+    let app = app(app_state).layer(cors);
 
     let port = std::env::var("PORT")
         .unwrap_or_else(|_| "8080".to_string());
@@ -69,4 +90,428 @@ async fn main() -> anyhow::Result<()> {
     axum::serve(listener, app).await?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// The whole backend apart from CORS, which depends on the deployment.
+fn app(app_state: AppState) -> axum::Router {
+    let (router, api) = api_routes();
+
+    router
+        .merge(openapi::routes(api))
+        .fallback(not_found)
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth::authenticate))
+        // Outside `authenticate`, so a stale cookie or token cannot fail
+        // healthchecks. Documented in `ApiDoc`.
+        .route("/api/health", get(health))
+        .with_state(app_state)
+}
+
+/// The API's routes, with the OpenAPI document describing them.
+fn api_routes() -> (axum::Router<AppState>, utoipa::openapi::OpenApi) {
+    let import = OpenApiRouter::new()
+        .routes(routes!(import_sessions))
+        .layer(DefaultBodyLimit::max(32 * 1024 * 1024));
+
+    let (router, mut api) = OpenApiRouter::with_openapi(ApiDoc::openapi())
+        .routes(routes!(register))
+        .routes(routes!(login))
+        .routes(routes!(logout))
+        .routes(routes!(current_user))
+        .routes(routes!(get_workspaces, create_workspace))
+        .routes(routes!(replace_workspace, delete_workspace))
+        .routes(routes!(get_members, add_member))
+        .routes(routes!(update_member, remove_member))
+        .routes(routes!(get_api_tokens, create_api_token))
+        .routes(routes!(delete_api_token))
+        .routes(routes!(get_sessions, create_session))
+        .routes(routes!(start_session))
+        .routes(routes!(export_sessions))
+        .merge(import)
+        .routes(routes!(get_session, replace_session, update_session, delete_session))
+        .routes(routes!(stop_session))
+        .routes(routes!(pause_session))
+        .routes(routes!(resume_session))
+        .routes(routes!(get_tags, create_tag))
+        .routes(routes!(get_tag, replace_tag, update_tag, delete_tag))
+        .routes(routes!(get_clients, create_client))
+        .routes(routes!(get_client, replace_client, update_client, delete_client))
+        .routes(routes!(get_projects, create_project))
+        .routes(routes!(get_project, replace_project, update_project, delete_project))
+        .routes(routes!(get_report))
+        .routes(routes!(get_invoices, create_invoice))
+        .routes(routes!(get_invoice, delete_invoice))
+        .routes(routes!(get_invoice_html))
+        .routes(routes!(get_invoice_pdf))
+        .routes(routes!(get_events))
+        .routes(routes!(get_webhooks, create_webhook))
+        .routes(routes!(delete_webhook))
+        .routes(routes!(get_webhook_deliveries))
+        .routes(routes!(ping_webhook))
+        .split_for_parts();
+    openapi::add_error_responses(&mut api);
+    (router, api)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use serde_json::{json, Value};
+    use sha2::Digest;
+    use shared::{CreateApiTokenRequest, CreateSessionRequest, CreateTagRequest, WorkspaceRole};
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    /// The backend on a test database, called the way clients call it.
+    struct TestApp {
+        app: axum::Router,
+        db: Arc<Database>,
+    }
+
+    /// A user with their personal workspace and a read-write API token.
+    struct TestUser {
+        id: Uuid,
+        email: String,
+        workspace_id: Uuid,
+        token: String,
+    }
+
+    impl TestApp {
+        fn new(pool: PgPool) -> Self {
+            let db = Arc::new(Database::new(pool));
+            let app = app(AppState {
+                db: db.clone(),
+                secure_cookies: false,
+                events: Events::local(),
+            });
+            TestApp { app, db }
+        }
+
+        async fn user(&self, email: &str) -> anyhow::Result<TestUser> {
+            let user = self.db.create_user(email, "not a real hash").await?;
+            let workspace_id = self.db.get_workspaces(user.id).await?.remove(0).id;
+            let token = self.token(user.id, false).await?;
+            Ok(TestUser {
+                id: user.id,
+                email: email.to_string(),
+                workspace_id,
+                token,
+            })
+        }
+
+        async fn token(&self, user_id: Uuid, read_only: bool) -> anyhow::Result<String> {
+            let token = auth::generate_api_token();
+            let req = CreateApiTokenRequest {
+                name: "Tests".to_string(),
+                read_only,
+            };
+            self.db.create_api_token(user_id, &req, &auth::hash_token(&token)).await?;
+            Ok(token)
+        }
+
+        /// Logs `user` in, returning the value of their login cookie.
+        async fn login(&self, user: &TestUser) -> anyhow::Result<String> {
+            let cookie = auth::generate_token();
+            let expires_at = chrono::Utc::now() + auth::login_lifetime();
+            self.db.create_login_session(user.id, &auth::hash_token(&cookie), expires_at).await?;
+            Ok(cookie)
+        }
+
+        /// Adds `user` to `owner`'s personal workspace.
+        async fn join(&self, owner: &TestUser, user: &TestUser, role: WorkspaceRole) -> anyhow::Result<()> {
+            self.db.add_member(owner.workspace_id, &user.email, role).await?;
+            Ok(())
+        }
+
+        async fn session(&self, workspace_id: Uuid, user: &TestUser) -> anyhow::Result<Uuid> {
+            let req = CreateSessionRequest {
+                duration_seconds: Some(3600),
+                description: None,
+                started_at: None,
+                ended_at: None,
+                tag_ids: Vec::new(),
+                project_id: None,
+                billable: false,
+                hourly_rate: None,
+            };
+            Ok(self.db.create_session(workspace_id, user.id, req).await?.id)
+        }
+
+        async fn tag(&self, workspace_id: Uuid, user: &TestUser, name: &str) -> anyhow::Result<Uuid> {
+            let req = CreateTagRequest {
+                name: name.to_string(),
+                color: None,
+                hourly_rate: None,
+            };
+            Ok(self.db.create_tag(workspace_id, user.id, req).await?.id)
+        }
+
+        /// Sends a request with `token` as its bearer token and returns the
+        /// status with the JSON body, or `null` if there is none.
+        async fn call(&self, method: &str, uri: &str, token: &str, body: Option<Value>) -> (StatusCode, Value) {
+            let request = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header::AUTHORIZATION, format!("Bearer {token}"));
+            self.send(request, body).await
+        }
+
+        /// Sends a request with `cookie` as the login cookie.
+        async fn call_logged_in(&self, method: &str, uri: &str, cookie: &str, body: Option<Value>) -> (StatusCode, Value) {
+            let request = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header::COOKIE, format!("{}={cookie}", auth::LOGIN_COOKIE));
+            self.send(request, body).await
+        }
+
+        async fn send(&self, request: axum::http::request::Builder, body: Option<Value>) -> (StatusCode, Value) {
+            let request = match body {
+                Some(body) => request
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(body.to_string())),
+                None => request.body(Body::empty()),
+            }
+            .unwrap();
+            let response = self.app.clone().oneshot(request).await.unwrap();
+            let status = response.status();
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+        }
+    }
+
+    #[sqlx::test]
+    async fn viewers_can_read_but_not_write(pool: PgPool) -> anyhow::Result<()> {
+        let app = TestApp::new(pool);
+        let owner = app.user("owner@example.com").await?;
+        let viewer = app.user("viewer@example.com").await?;
+        app.join(&owner, &viewer, WorkspaceRole::Viewer).await?;
+        let session_id = app.session(owner.workspace_id, &owner).await?;
+        let ws = owner.workspace_id;
+
+        let (status, _) = app.call("GET", &format!("/api/sessions?workspace_id={ws}"), &viewer.token, None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let writes = [
+            ("POST", format!("/api/sessions?workspace_id={ws}"), Some(json!({ "duration_seconds": 60, "tag_ids": [] }))),
+            ("POST", format!("/api/sessions/start?workspace_id={ws}"), Some(json!({ "tag_ids": [] }))),
+            ("PATCH", format!("/api/sessions/{session_id}?workspace_id={ws}"), Some(json!({ "description": "Mine now" }))),
+            ("DELETE", format!("/api/sessions/{session_id}?workspace_id={ws}"), None),
+            ("POST", format!("/api/tags?workspace_id={ws}"), Some(json!({ "name": "Design" }))),
+        ];
+        for (method, uri, body) in writes {
+            let (status, body) = app.call(method, &uri, &viewer.token, body).await;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{method} {uri}");
+            assert_eq!(body["error_code"], "forbidden");
+        }
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn members_change_only_their_own_sessions(pool: PgPool) -> anyhow::Result<()> {
+        let app = TestApp::new(pool);
+        let owner = app.user("owner@example.com").await?;
+        let member = app.user("member@example.com").await?;
+        let admin = app.user("admin@example.com").await?;
+        app.join(&owner, &member, WorkspaceRole::Member).await?;
+        app.join(&owner, &admin, WorkspaceRole::Admin).await?;
+        let ws = owner.workspace_id;
+        let theirs = app.session(ws, &owner).await?;
+        let own = app.session(ws, &member).await?;
+        let patch = || Some(json!({ "description": "Edited" }));
+
+        let (status, _) = app.call("PATCH", &format!("/api/sessions/{theirs}?workspace_id={ws}"), &member.token, patch()).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = app.call("DELETE", &format!("/api/sessions/{theirs}?workspace_id={ws}"), &member.token, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, _) = app.call("PATCH", &format!("/api/sessions/{own}?workspace_id={ws}"), &member.token, patch()).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = app.call("PATCH", &format!("/api/sessions/{theirs}?workspace_id={ws}"), &admin.token, patch()).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = app.call("DELETE", &format!("/api/sessions/{own}?workspace_id={ws}"), &admin.token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn only_admins_rename_or_delete_tags(pool: PgPool) -> anyhow::Result<()> {
+        let app = TestApp::new(pool);
+        let owner = app.user("owner@example.com").await?;
+        let member = app.user("member@example.com").await?;
+        let admin = app.user("admin@example.com").await?;
+        app.join(&owner, &member, WorkspaceRole::Member).await?;
+        app.join(&owner, &admin, WorkspaceRole::Admin).await?;
+        let ws = owner.workspace_id;
+        // Members may still create tags
+        let (status, body) = app.call("POST", &format!("/api/tags?workspace_id={ws}"), &member.token, Some(json!({ "name": "Design" }))).await;
+        assert_eq!(status, StatusCode::OK);
+        let tag = body["data"]["id"].as_str().unwrap().to_string();
+        let tag_uri = format!("/api/tags/{tag}?workspace_id={ws}");
+
+        let (status, _) = app.call("PATCH", &tag_uri, &member.token, Some(json!({ "name": "Drawing" }))).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = app.call("PUT", &tag_uri, &member.token, Some(json!({ "name": "Drawing" }))).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = app.call("DELETE", &tag_uri, &member.token, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, body) = app.call("PATCH", &tag_uri, &admin.token, Some(json!({ "name": "Drawing" }))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["name"], "Drawing");
+        let (status, _) = app.call("DELETE", &tag_uri, &admin.token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn admins_cannot_grant_or_manage_owners(pool: PgPool) -> anyhow::Result<()> {
+        let app = TestApp::new(pool);
+        let owner = app.user("owner@example.com").await?;
+        let admin = app.user("admin@example.com").await?;
+        let member = app.user("member@example.com").await?;
+        let newcomer = app.user("newcomer@example.com").await?;
+        app.join(&owner, &admin, WorkspaceRole::Admin).await?;
+        app.join(&owner, &member, WorkspaceRole::Member).await?;
+        let members = format!("/api/workspaces/{}/members", owner.workspace_id);
+
+        let (status, _) = app.call("PATCH", &format!("{members}/{}", member.id), &admin.token, Some(json!({ "role": "owner" }))).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let add = |role: &str| Some(json!({ "email": newcomer.email, "role": role }));
+        let (status, _) = app.call("POST", &members, &admin.token, add("owner")).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = app.call("PATCH", &format!("{members}/{}", owner.id), &admin.token, Some(json!({ "role": "viewer" }))).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = app.call("DELETE", &format!("{members}/{}", owner.id), &admin.token, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, _) = app.call("PATCH", &format!("{members}/{}", member.id), &admin.token, Some(json!({ "role": "admin" }))).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = app.call("POST", &members, &admin.token, add("member")).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = app.call("PATCH", &format!("{members}/{}", member.id), &owner.token, Some(json!({ "role": "owner" }))).await;
+        assert_eq!(status, StatusCode::OK);
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn other_workspaces_ids_are_not_found(pool: PgPool) -> anyhow::Result<()> {
+        let app = TestApp::new(pool);
+        let owner = app.user("owner@example.com").await?;
+        let outsider = app.user("outsider@example.com").await?;
+        let session_id = app.session(owner.workspace_id, &owner).await?;
+        let tag_id = app.tag(owner.workspace_id, &owner, "Design").await?;
+
+        // Ids from another workspace are looked up in the caller's own
+        let requests = [
+            ("GET", format!("/api/sessions/{session_id}"), None),
+            ("PATCH", format!("/api/sessions/{session_id}"), Some(json!({ "description": "Mine now" }))),
+            ("DELETE", format!("/api/sessions/{session_id}"), None),
+            ("PATCH", format!("/api/tags/{tag_id}"), Some(json!({ "name": "Mine now" }))),
+            ("DELETE", format!("/api/tags/{tag_id}"), None),
+            // Naming the workspace does not help either
+            ("GET", format!("/api/sessions/{session_id}?workspace_id={}", owner.workspace_id), None),
+            ("GET", format!("/api/workspaces/{}/members", owner.workspace_id), None),
+        ];
+        for (method, uri, body) in requests {
+            let (status, body) = app.call(method, &uri, &outsider.token, body).await;
+            assert_eq!(status, StatusCode::NOT_FOUND, "{method} {uri}");
+            assert_eq!(body["error_code"], "not_found");
+        }
+
+        let (status, _) = app.call("GET", &format!("/api/sessions/{session_id}"), &owner.token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn read_only_tokens_can_only_read(pool: PgPool) -> anyhow::Result<()> {
+        let app = TestApp::new(pool);
+        let user = app.user("test@example.com").await?;
+        let token = app.token(user.id, true).await?;
+        let session_id = app.session(user.workspace_id, &user).await?;
+
+        let (status, _) = app.call("GET", "/api/sessions", &token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = app.call("HEAD", "/api/sessions", &token, None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let writes = [
+            ("POST", "/api/tags".to_string(), Some(json!({ "name": "Design" }))),
+            ("PATCH", format!("/api/sessions/{session_id}"), Some(json!({ "description": "Edited" }))),
+            ("DELETE", format!("/api/sessions/{session_id}"), None),
+        ];
+        for (method, uri, body) in writes {
+            let (status, body) = app.call(method, &uri, &token, body).await;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{method} {uri}");
+            assert_eq!(body["message"], "This API token is read-only");
+        }
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn revoked_tokens_stop_working(pool: PgPool) -> anyhow::Result<()> {
+        let app = TestApp::new(pool);
+        let user = app.user("test@example.com").await?;
+        let cookie = app.login(&user).await?;
+
+        let (status, _) = app.call("GET", "/api/auth/me", &user.token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, tokens) = app.call_logged_in("GET", "/api/tokens", &cookie, None).await;
+        let id = tokens["data"][0]["id"].as_str().unwrap().to_string();
+        let (status, _) = app.call_logged_in("DELETE", &format!("/api/tokens/{id}"), &cookie, None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, body) = app.call("GET", "/api/auth/me", &user.token, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["message"], "Invalid API token");
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn tokens_are_stored_only_as_their_sha256(pool: PgPool) -> anyhow::Result<()> {
+        let app = TestApp::new(pool.clone());
+        let user = app.user("test@example.com").await?;
+        let cookie = app.login(&user).await?;
+
+        let (status, body) = app.call_logged_in("POST", "/api/tokens", &cookie, Some(json!({ "name": "CI" }))).await;
+        assert_eq!(status, StatusCode::OK);
+        let token = body["data"]["token"].as_str().unwrap();
+        let id: Uuid = body["data"]["id"].as_str().unwrap().parse()?;
+
+        let stored: Vec<u8> = sqlx::query_scalar("SELECT token_hash FROM api_tokens WHERE id = $1")
+            .bind(id)
+            .fetch_one(&pool)
+            .await?;
+        assert_eq!(stored, sha2::Sha256::digest(token.as_bytes()).to_vec());
+        let mentions: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM api_tokens t WHERE strpos(t::text, $1) > 0")
+            .bind(token)
+            .fetch_one(&pool)
+            .await?;
+        assert_eq!(mentions, 0);
+
+        let (status, _) = app.call("GET", "/api/auth/me", token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn api_tokens_cannot_create_or_revoke_tokens(pool: PgPool) -> anyhow::Result<()> {
+        let app = TestApp::new(pool);
+        let user = app.user("test@example.com").await?;
+
+        let (status, tokens) = app.call("GET", "/api/tokens", &user.token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let id = tokens["data"][0]["id"].as_str().unwrap().to_string();
+
+        let (status, _) = app.call("POST", "/api/tokens", &user.token, Some(json!({ "name": "Another" }))).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = app.call("DELETE", &format!("/api/tokens/{id}"), &user.token, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (_, tokens) = app.call("GET", "/api/tokens", &user.token, None).await;
+        assert_eq!(tokens["data"].as_array().unwrap().len(), 1);
+        Ok(())
+    }
+}
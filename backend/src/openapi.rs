@@ -0,0 +1,110 @@
+use axum::Router;
+use shared::ApiResponse;
+use utoipa::{
+    openapi::{
+        path::Operation,
+        security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+        ContentBuilder, OpenApi as OpenApiDoc, ResponseBuilder,
+    },
+    Modify, OpenApi, PartialSchema, ToSchema,
+};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::auth::LOGIN_COOKIE;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(crate::handlers::health),
+    info(
+        title = "Work Session Tracker API",
+        description = "Every JSON response is wrapped in an `ApiResponse` envelope. \
+                       Workspace-scoped routes take an optional `workspace_id`.",
+    ),
+    tags(
+        (name = "auth", description = "Accounts and the login cookie"),
+        (name = "workspaces", description = "Workspaces and their members"),
+        (name = "tokens", description = "Personal API tokens"),
+        (name = "sessions", description = "Work sessions"),
+        (name = "tags", description = "Session tags"),
+        (name = "clients", description = "Clients that projects belong to"),
+        (name = "projects", description = "Projects that sessions are booked against"),
+        (name = "reports", description = "Time reports"),
+        (name = "invoices", description = "Invoices for billable sessions"),
+        (name = "events", description = "Live session and tag changes"),
+        (name = "webhooks", description = "Session events posted to your own endpoints"),
+        (name = "health", description = "Service status"),
+    ),
+    modifiers(&SecuritySchemes),
+    security(("login_cookie" = []), ("api_token" = [])),
+)]
+pub struct ApiDoc;
+
+/// `data` of responses that carry none.
+#[derive(ToSchema)]
+pub struct NoData;
+
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut OpenApiDoc) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "login_cookie",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new(LOGIN_COOKIE))),
+        );
+        components.add_security_scheme(
+            "api_token",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
+/// Documents the error envelope every operation can answer with.
+pub fn add_error_responses(openapi: &mut OpenApiDoc) {
+    let error = ResponseBuilder::new()
+        .description("Error, with `error_code` and `message` set")
+        .content(
+            "application/json",
+            ContentBuilder::new().schema(Some(<ApiResponse<NoData> as PartialSchema>::schema())).build(),
+        )
+        .build();
+
+    for item in openapi.paths.paths.values_mut() {
+        let operations: [&mut Option<Operation>; 5] =
+            [&mut item.get, &mut item.put, &mut item.post, &mut item.patch, &mut item.delete];
+        for operation in operations.into_iter().flatten() {
+            operation.responses.responses.insert("default".to_string(), error.clone().into());
+        }
+    }
+}
+
+/// Serves the document at `/api/openapi.json` and Swagger UI, which is
+/// embedded in the binary, at `/api/docs`.
+pub fn routes<S: Clone + Send + Sync + 'static>(openapi: OpenApiDoc) -> Router<S> {
+    SwaggerUi::new("/api/docs").url("/api/openapi.json", openapi).into()
+}
+
+#[cfg(test)]
+mod tests {
+    /// The document as handed to API consumers, kept next to the code so
+    /// changes to it show up in review.
+    const CHECKED_IN: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/openapi.json");
+
+    /// Fails when a route or one of its types changes without `openapi.json`
+    /// being regenerated, with `UPDATE_OPENAPI=1 cargo test`.
+    #[test]
+    fn checked_in_document_matches_the_routes() {
+        let (_, api) = crate::api_routes();
+        let generated = api.to_pretty_json().expect("OpenAPI document serializes") + "\n";
+
+        if std::env::var_os("UPDATE_OPENAPI").is_some() {
+            std::fs::write(CHECKED_IN, generated).expect("openapi.json is writable");
+            return;
+        }
+        let checked_in = std::fs::read_to_string(CHECKED_IN).unwrap_or_default();
+        assert!(
+            checked_in == generated,
+            "backend/openapi.json is out of date; regenerate it with UPDATE_OPENAPI=1 cargo test -p work-session-tracker-backend"
+        );
+    }
+}
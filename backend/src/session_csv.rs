@@ -0,0 +1,225 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use shared::{ImportSessionRecord, WorkSessionWithTags};
+
+/// Column layout of exported sessions.
+pub const HEADER: [&str; 6] = ["id", "started_at", "ended_at", "duration_seconds", "description", "tags"];
+
+/// Separates tag names within the `tags` column.
+pub const TAG_DELIMITER: &str = ";";
+
+/// Characters that make spreadsheets read a cell as a formula.
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// Whether a cell would be read as a formula, or is one already quoted, so
+/// that quoting stays reversible.
+fn looks_like_formula(cell: &str) -> bool {
+    cell.trim_start_matches('\'').starts_with(FORMULA_PREFIXES)
+}
+
+/// Prefixes free-text cells that spreadsheets would evaluate with `'`.
+fn escape_formula(cell: String) -> String {
+    if looks_like_formula(&cell) {
+        format!("'{cell}")
+    } else {
+        cell
+    }
+}
+
+fn unescape_formula(cell: String) -> String {
+    match cell.strip_prefix('\'') {
+        Some(rest) if looks_like_formula(rest) => rest.to_string(),
+        _ => cell,
+    }
+}
+
+/// Renders sessions as CSV rows, preceded by the header row if requested.
+/// Descriptions and tags that look like formulas are quoted.
+pub fn write_sessions(sessions: &[WorkSessionWithTags], with_header: bool) -> Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    if with_header {
+        writer.write_record(HEADER)?;
+    }
+
+    for session in sessions {
+        let tags = session
+            .tags
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect::<Vec<_>>()
+            .join(TAG_DELIMITER);
+
+        writer.write_record([
+            session.id.to_string(),
+            session.started_at.to_rfc3339(),
+            session.ended_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            session.duration_seconds.to_string(),
+            escape_formula(session.description.clone().unwrap_or_default()),
+            escape_formula(tags),
+        ])?;
+    }
+
+    Ok(writer.into_inner()?)
+}
+
+/// A row of an imported CSV file. Other columns, such as `id`, are ignored.
+#[derive(Deserialize)]
+struct CsvRecord {
+    started_at: Option<DateTime<Utc>>,
+    ended_at: Option<DateTime<Utc>>,
+    duration_seconds: Option<i32>,
+    description: Option<String>,
+    tags: Option<String>,
+}
+
+/// Reads sessions from CSV with a header row, keeping unparsable rows as
+/// errors so they can be reported alongside validation failures. Undoes the
+/// formula quoting of `write_sessions`.
+pub fn read_sessions(data: &[u8]) -> Vec<std::result::Result<ImportSessionRecord, String>> {
+    csv::Reader::from_reader(data)
+        .deserialize::<CsvRecord>()
+        .map(|record| {
+            let record = record.map_err(|e| e.to_string())?;
+            Ok(ImportSessionRecord {
+                started_at: record.started_at,
+                ended_at: record.ended_at,
+                duration_seconds: record.duration_seconds,
+                description: record.description.map(unescape_formula),
+                tags: unescape_formula(record.tags.unwrap_or_default())
+                    .split(TAG_DELIMITER)
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use shared::Tag;
+    use uuid::Uuid;
+
+    fn session(description: &str, tags: &[&str]) -> WorkSessionWithTags {
+        let started_at = Utc.with_ymd_and_hms(2024, 5, 10, 9, 0, 0).unwrap();
+        WorkSessionWithTags {
+            id: Uuid::new_v4(),
+            duration_seconds: 3600,
+            description: Some(description.to_string()),
+            started_at,
+            ended_at: Some(started_at + chrono::Duration::hours(1)),
+            created_at: started_at,
+            updated_at: started_at,
+            user_id: Uuid::new_v4(),
+            project_id: None,
+            billable: false,
+            hourly_rate: None,
+            effective_rate: None,
+            amount: None,
+            currency: "EUR".to_string(),
+            tags: tags
+                .iter()
+                .map(|name| Tag {
+                    id: Uuid::new_v4(),
+                    name: name.to_string(),
+                    color: None,
+                    hourly_rate: None,
+                    created_at: started_at,
+                })
+                .collect(),
+            segments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn formulas_are_quoted_on_export() -> Result<()> {
+        let sessions = [
+            session("=HYPERLINK(\"http://evil.example\")", &["@SUM(A1)", "Backend"]),
+            session("+1", &[]),
+            session("-1", &[]),
+            session("\tindented", &[]),
+            session("'=already quoted", &[]),
+            session("Plain 'quoted' text", &["Backend"]),
+        ];
+        let csv = String::from_utf8(write_sessions(&sessions, false)?)?;
+        let cells: Vec<_> = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(csv.as_bytes())
+            .records()
+            .map(|record| {
+                let record = record.unwrap();
+                (record[4].to_string(), record[5].to_string())
+            })
+            .collect();
+
+        assert_eq!(
+            cells,
+            [
+                ("'=HYPERLINK(\"http://evil.example\")".to_string(), "'@SUM(A1);Backend".to_string()),
+                ("'+1".to_string(), String::new()),
+                ("'-1".to_string(), String::new()),
+                ("'\tindented".to_string(), String::new()),
+                ("''=already quoted".to_string(), String::new()),
+                ("Plain 'quoted' text".to_string(), "Backend".to_string()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn quoted_formulas_round_trip() -> Result<()> {
+        let sessions = [
+            session("=1+1", &["-internal", "Backend"]),
+            session("'=already quoted", &[]),
+            session("'just an apostrophe", &[]),
+            session("@home", &[]),
+        ];
+        let csv = write_sessions(&sessions, true)?;
+        let records: Vec<_> = read_sessions(&csv).into_iter().map(|r| r.unwrap()).collect();
+
+        for (session, record) in sessions.iter().zip(&records) {
+            assert_eq!(record.description, session.description);
+            let tags: Vec<_> = session.tags.iter().map(|t| t.name.clone()).collect();
+            assert_eq!(record.tags, tags);
+            assert_eq!(record.started_at, Some(session.started_at));
+            assert_eq!(record.ended_at, session.ended_at);
+        }
+        assert_eq!(records.len(), sessions.len());
+        Ok(())
+    }
+
+    #[test]
+    fn unquoted_formulas_are_imported_as_written() {
+        let csv = "started_at,duration_seconds,description,tags\n2024-05-10T09:00:00Z,60,=1+1,@home\n";
+        let record = read_sessions(csv.as_bytes()).remove(0).unwrap();
+        assert_eq!(record.description.as_deref(), Some("=1+1"));
+        assert_eq!(record.tags, ["@home"]);
+    }
+
+    #[test]
+    fn rows_are_read_in_order_with_unparsable_ones_as_errors() {
+        let csv = "id,started_at,ended_at,duration_seconds,description,tags\n\
+                   ignored,2024-05-10T09:00:00Z,2024-05-10T10:00:00Z,,Planning, Backend ;;Code review \n\
+                   ignored,yesterday,,,Broken,\n\
+                   ignored,,,600,,\n";
+        let records = read_sessions(csv.as_bytes());
+        assert_eq!(records.len(), 3);
+
+        let first = records[0].as_ref().unwrap();
+        assert_eq!(first.started_at, Some(Utc.with_ymd_and_hms(2024, 5, 10, 9, 0, 0).unwrap()));
+        assert_eq!(first.ended_at, Some(Utc.with_ymd_and_hms(2024, 5, 10, 10, 0, 0).unwrap()));
+        assert_eq!(first.description.as_deref(), Some("Planning"));
+        assert_eq!(first.tags, ["Backend", "Code review"]);
+
+        assert!(records[1].as_ref().unwrap_err().starts_with("CSV deserialize error: record 2"));
+
+        let third = records[2].as_ref().unwrap();
+        assert_eq!(third.duration_seconds, Some(600));
+        assert_eq!(third.description, None);
+        assert!(third.tags.is_empty());
+    }
+}
@@ -0,0 +1,281 @@
+use std::collections::HashSet;
+use std::error::Error as _;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use hyper::client::connect::dns::Name;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use reqwest::{header, Url};
+use sha2::Sha256;
+use tracing::error;
+
+use crate::db::{Database, DueDelivery};
+
+/// How often the worker looks for due deliveries when idle
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const BATCH_SIZE: i64 = 20;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long a claimed delivery is reserved for its worker, after which it is
+/// sent again in case that worker died
+const LEASE: chrono::Duration = chrono::Duration::seconds(60);
+/// Attempts before a delivery is marked failed. Retries wait 30 seconds,
+/// doubling each time, so the last is about an hour after the first.
+const MAX_ATTEMPTS: i32 = 8;
+const FIRST_RETRY_SECONDS: i64 = 30;
+
+/// Hosts that webhooks may reach although they are internal, such as a
+/// receiver on the same network. Any other host must resolve to public
+/// addresses only, so workspace admins cannot use webhooks to probe the
+/// server's network.
+#[derive(Clone, Default)]
+pub struct AllowedHosts(Arc<HashSet<String>>);
+
+impl AllowedHosts {
+    /// Reads a comma-separated list of host names and IP addresses.
+    pub fn parse(list: &str) -> Self {
+        let hosts = list
+            .split(',')
+            .map(|host| host.trim().trim_start_matches('[').trim_end_matches(']').to_lowercase())
+            .filter(|host| !host.is_empty())
+            .collect();
+        Self(Arc::new(hosts))
+    }
+
+    fn contains(&self, host: &str) -> bool {
+        self.0.contains(&host.to_lowercase())
+    }
+}
+
+/// Resolves webhook hosts, refusing those with internal addresses unless
+/// they are allowed. Connections only go to the addresses checked here, so
+/// a host cannot pass the check and then resolve elsewhere.
+struct PublicResolver(AllowedHosts);
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let allowed = self.0.contains(name.as_str());
+        Box::pin(async move {
+            let addrs = tokio::net::lookup_host((name.as_str(), 0)).await?.collect::<Vec<_>>();
+            if !allowed && addrs.iter().any(|addr| !is_public(addr.ip())) {
+                return Err(format!("{} resolves to an internal address", name.as_str()).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Starts the background worker that posts queued deliveries to their
+/// webhooks. Several instances may run against one database.
+pub fn spawn_worker(db: Arc<Database>, allowed_hosts: AllowedHosts) -> anyhow::Result<()> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        // A proxy would resolve hosts itself, bypassing the resolver
+        .no_proxy()
+        .dns_resolver(Arc::new(PublicResolver(allowed_hosts.clone())))
+        .user_agent("work-session-tracker-webhooks")
+        .build()?;
+
+    tokio::spawn(async move {
+        loop {
+            match deliver_due(&db, &client, &allowed_hosts).await {
+                Ok(0) => tokio::time::sleep(POLL_INTERVAL).await,
+                Ok(_) => {}
+                Err(e) => {
+                    error!("Failed to claim webhook deliveries: {e:#}");
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Sends a batch of due deliveries, returning how many there were.
+async fn deliver_due(db: &Database, client: &reqwest::Client, allowed_hosts: &AllowedHosts) -> anyhow::Result<usize> {
+    let due = db.claim_due_deliveries(BATCH_SIZE, Utc::now() + LEASE).await?;
+    let count = due.len();
+    futures::future::join_all(due.into_iter().map(|delivery| deliver(db, client, allowed_hosts, delivery))).await;
+    Ok(count)
+}
+
+async fn deliver(db: &Database, client: &reqwest::Client, allowed_hosts: &AllowedHosts, delivery: DueDelivery) {
+    // Addresses given as IP literals are never resolved, so are checked here.
+    // Retrying cannot help.
+    if let Some(ip) = internal_ip(&delivery.url, allowed_hosts) {
+        let error = format!("Refusing to deliver to internal address {ip}");
+        if let Err(e) = db.record_delivery_failure(delivery.id, None, &error, None).await {
+            error!("Failed to record webhook delivery {}: {e:#}", delivery.id);
+        }
+        return;
+    }
+
+    let body = delivery.payload.to_string();
+    let signature = signature_header(&delivery.secret, Utc::now().timestamp(), &body);
+
+    let response = client
+        .post(&delivery.url)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("X-Wst-Event", &delivery.event)
+        .header("X-Wst-Delivery", delivery.id.to_string())
+        .header("X-Wst-Signature", signature)
+        .body(body)
+        .send()
+        .await;
+
+    let result = match response {
+        Ok(response) if response.status().is_success() => {
+            db.record_delivery_success(delivery.id, response.status().as_u16().into()).await
+        }
+        Ok(response) => {
+            let status = response.status();
+            fail(db, &delivery, Some(status.as_u16().into()), &format!("Endpoint answered {status}")).await
+        }
+        Err(e) => fail(db, &delivery, None, &describe(&e)).await,
+    };
+    if let Err(e) = result {
+        error!("Failed to record webhook delivery {}: {e:#}", delivery.id);
+    }
+}
+
+/// Records a failed attempt, scheduling a retry unless it was the last.
+async fn fail(db: &Database, delivery: &DueDelivery, status: Option<i32>, error: &str) -> anyhow::Result<()> {
+    let retry_at = retry_delay(delivery.attempts + 1).map(|delay| Utc::now() + delay);
+    db.record_delivery_failure(delivery.id, status, error, retry_at).await
+}
+
+/// How long to wait after `attempts` failed attempts, doubling each time, or
+/// `None` once the delivery is given up on.
+fn retry_delay(attempts: i32) -> Option<chrono::Duration> {
+    (attempts < MAX_ATTEMPTS).then(|| chrono::Duration::seconds(FIRST_RETRY_SECONDS << (attempts - 1)))
+}
+
+/// The error with its causes, as reqwest's own message rarely says what went wrong.
+fn describe(e: &reqwest::Error) -> String {
+    let mut message = e.to_string();
+    let mut source = e.source();
+    while let Some(cause) = source {
+        // Causes often repeat the error they wrap
+        let cause_message = cause.to_string();
+        if !message.contains(&cause_message) {
+            message.push_str(": ");
+            message.push_str(&cause_message);
+        }
+        source = cause.source();
+    }
+    message
+}
+
+/// The URL's host, if it is an IP address that webhooks may not reach.
+fn internal_ip(url: &str, allowed_hosts: &AllowedHosts) -> Option<IpAddr> {
+    // The URL parser normalizes IPv4 written in other forms, like `0x7f.1`
+    let url = Url::parse(url).ok()?;
+    let ip: IpAddr = url.host_str()?.trim_start_matches('[').trim_end_matches(']').parse().ok()?;
+    (!is_public(ip) && !allowed_hosts.contains(&ip.to_string())).then_some(ip)
+}
+
+/// Whether an address is on the public internet, rather than loopback,
+/// private, link-local, unspecified or otherwise reserved.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_v4(ip),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, _, _] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        // "This network", carrier-grade NAT, benchmarking and reserved
+        || a == 0
+        || (a == 100 && (64..128).contains(&b))
+        || (a == 198 && (18..20).contains(&b))
+        || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Unique local, link-local and documentation
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+/// The `X-Wst-Signature` value: the timestamp and, as `v1`, the hex
+/// HMAC-SHA256 of `{timestamp}.{body}`.
+fn signature_header(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{timestamp}.{body}").as_bytes());
+    format!("t={timestamp},v1={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn internal_addresses_are_refused_unless_allowed() {
+        let none = AllowedHosts::default();
+        for url in [
+            "http://127.0.0.1:9000/",
+            "http://0x7f.1/",
+            "http://10.1.2.3/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://100.64.0.1/",
+            "http://0.0.0.0/",
+            "http://[::1]/",
+            "http://[fd00::1]/",
+            "http://[fe80::1]/",
+            "http://[::ffff:192.168.0.1]/",
+        ] {
+            assert!(internal_ip(url, &none).is_some(), "{url} should be refused");
+        }
+
+        for url in ["https://93.184.216.34/hook", "https://[2606:4700::1111]/", "https://example.com/hook"] {
+            assert_eq!(internal_ip(url, &none), None, "{url} should be allowed");
+        }
+
+        let allowed = AllowedHosts::parse(" 10.1.2.3, [::1] ,Localhost");
+        assert_eq!(internal_ip("http://10.1.2.3/", &allowed), None);
+        assert_eq!(internal_ip("http://[::1]:9000/", &allowed), None);
+        assert!(allowed.contains("localhost"));
+        assert!(internal_ip("http://10.1.2.4/", &allowed).is_some());
+    }
+
+    #[test]
+    fn signatures_match_a_known_hmac() {
+        // From Python: hmac.new(secret, b'1700000000.{"event":"ping"}', hashlib.sha256).hexdigest()
+        assert_eq!(
+            signature_header("whsec_test_secret_0123", 1_700_000_000, r#"{"event":"ping"}"#),
+            "t=1700000000,v1=65632b22e18673734d3a36ddb17032528c5fe89804295d167b3e26ccb6090efb"
+        );
+    }
+
+    #[test]
+    fn retries_back_off_and_stop_after_the_last_attempt() {
+        let delays: Vec<_> = (1..=MAX_ATTEMPTS).map(|attempts| retry_delay(attempts).map(|d| d.num_seconds())).collect();
+        assert_eq!(
+            delays,
+            [Some(30), Some(60), Some(120), Some(240), Some(480), Some(960), Some(1920), None]
+        );
+        // Just over an hour of retries in all
+        let total: i64 = delays.iter().flatten().sum();
+        assert_eq!(total, 3810);
+    }
+}
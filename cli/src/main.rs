@@ -0,0 +1,381 @@
+use anyhow::{bail, Result};
+use api_client::{ApiClient, ReqwestTransport};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone, Utc, Weekday};
+use clap::{Args, Parser, Subcommand};
+use serde::Serialize;
+use shared::{ReportQuery, SessionQuery, SortOrder, StartSessionRequest, Validate, WorkSessionWithTags};
+use uuid::Uuid;
+
+mod table;
+
+type Api = ApiClient<ReqwestTransport>;
+
+/// Command-line client for the Work Session Tracker.
+#[derive(Parser)]
+#[command(name = "wst", version)]
+struct Cli {
+    /// Base URL of the tracker
+    #[arg(long, env = "WST_URL", default_value = "http://localhost:8080", global = true)]
+    url: String,
+
+    /// Personal API token, created on the API Tokens page
+    #[arg(long, env = "WST_TOKEN", hide_env_values = true, global = true)]
+    token: Option<String>,
+
+    /// Workspace to use instead of your first one
+    #[arg(long, env = "WST_WORKSPACE", global = true)]
+    workspace: Option<Uuid>,
+
+    /// Print the API's JSON instead of tables
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Start a session
+    Start {
+        /// What you are working on
+        description: Option<String>,
+        /// Tag the session, by name; may be repeated
+        #[arg(short, long = "tag")]
+        tags: Vec<String>,
+        /// Mark the session billable
+        #[arg(long)]
+        billable: bool,
+    },
+    /// Stop your running session
+    Stop,
+    /// Show your running session
+    Status,
+    /// List sessions
+    Log {
+        /// Start of the listing: today, yesterday, a weekday or YYYY-MM-DD
+        #[arg(long, default_value = "today")]
+        since: String,
+    },
+    /// List tags
+    Tags,
+    /// Show time per tag
+    Report(ReportRange),
+}
+
+#[derive(Args)]
+#[group(multiple = false)]
+struct ReportRange {
+    /// This week, from Monday (the default)
+    #[arg(long)]
+    week: bool,
+    /// This month
+    #[arg(long)]
+    month: bool,
+    /// Start of a custom range: today, yesterday, a weekday or YYYY-MM-DD
+    #[arg(long)]
+    since: Option<String>,
+}
+
+impl ReportRange {
+    /// First day of the range, which ends `today`.
+    fn start(&self, today: NaiveDate) -> Result<NaiveDate> {
+        Ok(match &self.since {
+            Some(since) => parse_day(since, today)?,
+            None if self.month => today.with_day(1).unwrap_or(today),
+            None => today - Duration::days(today.weekday().num_days_from_monday().into()),
+        })
+    }
+}
+
+fn format_duration(seconds: i64) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    format!("{hours}h {minutes:02}m")
+}
+
+fn tag_names(session: &WorkSessionWithTags) -> String {
+    session.tags.iter().map(|t| t.name.as_str()).collect::<Vec<_>>().join(", ")
+}
+
+/// Parses a day relative to `today`; weekdays mean the latest such day, which
+/// may be today.
+fn parse_day(input: &str, today: NaiveDate) -> Result<NaiveDate> {
+    let input = input.trim().to_lowercase();
+    match input.as_str() {
+        "today" => return Ok(today),
+        "yesterday" => return Ok(today - Duration::days(1)),
+        _ => {}
+    }
+    if let Ok(weekday) = input.parse::<Weekday>() {
+        let days_back = (today.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+        return Ok(today - Duration::days(days_back.into()));
+    }
+    NaiveDate::parse_from_str(&input, "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("Expected today, yesterday, a weekday or YYYY-MM-DD, got {input:?}"))
+}
+
+/// Local midnight at the start of `day`.
+fn start_of_day(day: NaiveDate) -> DateTime<Utc> {
+    let midnight = day.and_hms_opt(0, 0, 0).unwrap_or_default();
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Every session matching `query`, following the pages to the end.
+async fn all_sessions(api: &Api, query: SessionQuery) -> Result<Vec<WorkSessionWithTags>> {
+    let mut query = SessionQuery {
+        limit: Some(SessionQuery::MAX_LIMIT),
+        ..query
+    };
+    let mut sessions = Vec::new();
+    loop {
+        let page = api.get_sessions(&query).await?;
+        sessions.extend(page.items);
+        match page.next_cursor {
+            Some(cursor) => query.cursor = Some(cursor),
+            None => return Ok(sessions),
+        }
+    }
+}
+
+/// The caller's running session in the workspace. There is at most one.
+async fn running_session(api: &Api) -> Result<Option<WorkSessionWithTags>> {
+    let user = api.current_user().await?;
+    let sessions = all_sessions(api, SessionQuery {
+        running: Some(true),
+        ..Default::default()
+    })
+    .await?;
+    Ok(sessions.into_iter().find(|s| s.user_id == user.id))
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    let Some(token) = cli.token.filter(|t| !t.is_empty()) else {
+        bail!("No API token; pass --token or set WST_TOKEN");
+    };
+    let api = ApiClient::new(&cli.url, ReqwestTransport::new(token)).with_workspace(cli.workspace);
+    let today = Local::now().date_naive();
+
+    match cli.command {
+        Command::Start { description, tags, billable } => {
+            let known = api.get_tags().await?;
+            let tag_ids = tags
+                .iter()
+                .map(|name| {
+                    known
+                        .iter()
+                        .find(|t| t.name.eq_ignore_ascii_case(name.trim()))
+                        .map(|t| t.id)
+                        .ok_or_else(|| anyhow::anyhow!("Unknown tag: {name}"))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let req = StartSessionRequest {
+                description,
+                tag_ids,
+                project_id: None,
+                billable,
+                hourly_rate: None,
+            };
+            req.validate()?;
+            let session = api.start_session(&req).await?;
+            if cli.json {
+                return print_json(&session);
+            }
+            println!("Started session {}", session.id);
+        }
+        Command::Stop => {
+            let Some(running) = running_session(&api).await? else {
+                bail!("No session is running");
+            };
+            let session = api.stop_session(running.id).await?;
+            if cli.json {
+                return print_json(&session);
+            }
+            println!("Stopped after {}", format_duration(session.duration_seconds.into()));
+        }
+        Command::Status => {
+            let running = running_session(&api).await?;
+            if cli.json {
+                return print_json(&running);
+            }
+            match running {
+                Some(session) => {
+                    let now = Utc::now();
+                    let seconds: i64 = session.segments.iter().map(|s| i64::from(s.duration_seconds(now))).sum();
+                    let state = if session.is_paused() { "Paused" } else { "Running" };
+                    println!("{state}: {}", session.description.as_deref().unwrap_or("(no description)"));
+                    println!("Since:   {}", session.started_at.with_timezone(&Local).format("%Y-%m-%d %H:%M"));
+                    println!("Tracked: {}", format_duration(seconds));
+                    if !session.tags.is_empty() {
+                        println!("Tags:    {}", tag_names(&session));
+                    }
+                }
+                None => println!("No session is running"),
+            }
+        }
+        Command::Log { since } => {
+            let from = start_of_day(parse_day(&since, today)?);
+            let sessions = all_sessions(&api, SessionQuery {
+                from: Some(from),
+                order: SortOrder::Asc,
+                ..Default::default()
+            })
+            .await?;
+            if cli.json {
+                return print_json(&sessions);
+            }
+
+            let rows: Vec<Vec<String>> = sessions
+                .iter()
+                .map(|s| {
+                    let started = s.started_at.with_timezone(&Local);
+                    vec![
+                        started.format("%Y-%m-%d").to_string(),
+                        started.format("%H:%M").to_string(),
+                        s.ended_at
+                            .map(|t| t.with_timezone(&Local).format("%H:%M").to_string())
+                            .unwrap_or_else(|| "running".to_string()),
+                        format_duration(s.duration_seconds.into()),
+                        tag_names(s),
+                        s.description.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            table::print(&["Date", "Start", "End", "Duration", "Tags", "Description"], &rows);
+
+            let total: i64 = sessions.iter().map(|s| i64::from(s.duration_seconds)).sum();
+            let noun = if sessions.len() == 1 { "session" } else { "sessions" };
+            println!("\n{} {noun}, {}", sessions.len(), format_duration(total));
+        }
+        Command::Tags => {
+            let tags = api.get_tags().await?;
+            if cli.json {
+                return print_json(&tags);
+            }
+            let rows: Vec<Vec<String>> = tags
+                .iter()
+                .map(|t| {
+                    vec![
+                        t.name.clone(),
+                        t.color.clone().unwrap_or_default(),
+                        t.hourly_rate.map(|r| r.to_string()).unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            table::print(&["Name", "Color", "Rate"], &rows);
+        }
+        Command::Report(range) => {
+            let query = ReportQuery {
+                from: range.start(today)?,
+                to: today,
+                timezone: iana_time_zone::get_timezone().ok(),
+                period: None,
+                by_tag: true,
+            };
+            let report = api.get_report(&query).await?;
+            if cli.json {
+                return print_json(&report);
+            }
+
+            let rows: Vec<Vec<String>> = report
+                .rows
+                .iter()
+                .map(|r| {
+                    vec![
+                        r.tag_name.clone().unwrap_or_else(|| "Untagged".to_string()),
+                        r.session_count.to_string(),
+                        format_duration(r.total_seconds),
+                        format_duration(r.billable_seconds),
+                        r.amount.to_string(),
+                    ]
+                })
+                .collect();
+            println!("{} to {}\n", report.from, report.to);
+            table::print(&["Tag", "Sessions", "Time", "Billable", "Amount"], &rows);
+            println!(
+                "\nTotal {}, {} billable, earning {} {}",
+                format_duration(report.total_seconds),
+                format_duration(report.billable_seconds),
+                report.amount,
+                report.currency
+            );
+        }
+    }
+    Ok(())
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    if let Err(e) = run(Cli::parse()).await {
+        eprintln!("wst: {e}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn range(week: bool, month: bool, since: Option<&str>) -> ReportRange {
+        ReportRange {
+            week,
+            month,
+            since: since.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn days_are_parsed_relative_to_today() {
+        // A Monday
+        let today = day("2024-05-13");
+        assert_eq!(parse_day("today", today).unwrap(), today);
+        assert_eq!(parse_day(" Yesterday ", today).unwrap(), day("2024-05-12"));
+        assert_eq!(parse_day("2024-01-31", today).unwrap(), day("2024-01-31"));
+        assert!(parse_day("last week", today).is_err());
+
+        // Yesterday across a month boundary in a leap year
+        assert_eq!(parse_day("yesterday", day("2024-03-01")).unwrap(), day("2024-02-29"));
+    }
+
+    #[test]
+    fn weekdays_mean_the_latest_such_day() {
+        let monday = day("2024-05-13");
+        assert_eq!(parse_day("monday", monday).unwrap(), monday);
+        assert_eq!(parse_day("Sunday", monday).unwrap(), day("2024-05-12"));
+        assert_eq!(parse_day("tue", monday).unwrap(), day("2024-05-07"));
+
+        let sunday = day("2024-05-19");
+        assert_eq!(parse_day("monday", sunday).unwrap(), monday);
+        assert_eq!(parse_day("sunday", sunday).unwrap(), sunday);
+    }
+
+    #[test]
+    fn report_ranges_start_on_monday_the_first_or_the_given_day() {
+        let monday = day("2024-05-13");
+        let sunday = day("2024-05-19");
+        // The week is the default
+        assert_eq!(range(false, false, None).start(monday).unwrap(), monday);
+        assert_eq!(range(true, false, None).start(monday).unwrap(), monday);
+        assert_eq!(range(true, false, None).start(sunday).unwrap(), monday);
+
+        assert_eq!(range(false, true, None).start(sunday).unwrap(), day("2024-05-01"));
+        assert_eq!(range(false, true, None).start(day("2024-05-01")).unwrap(), day("2024-05-01"));
+
+        assert_eq!(range(false, false, Some("yesterday")).start(monday).unwrap(), day("2024-05-12"));
+        assert!(range(false, false, Some("soon")).start(monday).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+use yew::prelude::*;
+
+const CHART_WIDTH: f64 = 800.0;
+const LABEL_WIDTH: f64 = 140.0;
+const VALUE_WIDTH: f64 = 80.0;
+const ROW_HEIGHT: f64 = 28.0;
+const STACK_HEIGHT: f64 = 240.0;
+const AXIS_HEIGHT: f64 = 24.0;
+
+#[derive(Clone, PartialEq)]
+pub struct Bar {
+    pub label: String,
+    pub value: i64,
+    /// Text shown next to the bar
+    pub display: String,
+    pub color: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct BarChartProps {
+    pub bars: Vec<Bar>,
+}
+
+/// Horizontal bar chart, one labelled row per bar.
+#[function_component(BarChart)]
+pub fn bar_chart(props: &BarChartProps) -> Html {
+    let max = props.bars.iter().map(|b| b.value).max().unwrap_or(0).max(1) as f64;
+    let plot_width = CHART_WIDTH - LABEL_WIDTH - VALUE_WIDTH;
+    let height = ROW_HEIGHT * props.bars.len() as f64;
+
+    html! {
+        <svg viewBox={format!("0 0 {CHART_WIDTH} {height}")} class="w-full">
+            {for props.bars.iter().enumerate().map(|(i, bar)| {
+                let y = ROW_HEIGHT * i as f64;
+                let width = plot_width * bar.value as f64 / max;
+                html! {
+                    <g>
+                        <text x={(LABEL_WIDTH - 8.0).to_string()} y={(y + ROW_HEIGHT / 2.0).to_string()}
+                            text-anchor="end" dominant-baseline="middle" font-size="13" fill="#374151">
+                            {&bar.label}
+                        </text>
+                        <rect x={LABEL_WIDTH.to_string()} y={(y + 4.0).to_string()}
+                            width={width.to_string()} height={(ROW_HEIGHT - 8.0).to_string()}
+                            rx="3" fill={bar.color.clone()}>
+                            <title>{format!("{}: {}", bar.label, bar.display)}</title>
+                        </rect>
+                        <text x={(LABEL_WIDTH + width + 6.0).to_string()} y={(y + ROW_HEIGHT / 2.0).to_string()}
+                            dominant-baseline="middle" font-size="12" fill="#6B7280">
+                            {&bar.display}
+                        </text>
+                    </g>
+                }
+            })}
+        </svg>
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct StackSegment {
+    pub value: i64,
+    pub color: String,
+    /// Tooltip for the segment
+    pub title: String,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct StackedColumn {
+    pub label: String,
+    pub segments: Vec<StackSegment>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct StackedBarChartProps {
+    pub columns: Vec<StackedColumn>,
+}
+
+/// Vertical bar chart with each column built from stacked segments. Column
+/// labels are thinned out when there are too many to fit.
+#[function_component(StackedBarChart)]
+pub fn stacked_bar_chart(props: &StackedBarChartProps) -> Html {
+    let max = props
+        .columns
+        .iter()
+        .map(|c| c.segments.iter().map(|s| s.value).sum::<i64>())
+        .max()
+        .unwrap_or(0)
+        .max(1) as f64;
+    let count = props.columns.len().max(1);
+    let slot = CHART_WIDTH / count as f64;
+    let bar_width = (slot * 0.7).max(1.0);
+    let label_every = count.div_ceil(16);
+
+    html! {
+        <svg viewBox={format!("0 0 {CHART_WIDTH} {}", STACK_HEIGHT + AXIS_HEIGHT)} class="w-full">
+            <line x1="0" y1={STACK_HEIGHT.to_string()} x2={CHART_WIDTH.to_string()} y2={STACK_HEIGHT.to_string()}
+                stroke="#D1D5DB" />
+            {for props.columns.iter().enumerate().map(|(i, column)| {
+                let x = slot * i as f64 + (slot - bar_width) / 2.0;
+                let mut top = STACK_HEIGHT;
+                html! {
+                    <g>
+                        {for column.segments.iter().map(|segment| {
+                            let height = STACK_HEIGHT * segment.value as f64 / max;
+                            top -= height;
+                            html! {
+                                <rect x={x.to_string()} y={top.to_string()}
+                                    width={bar_width.to_string()} height={height.to_string()}
+                                    fill={segment.color.clone()}>
+                                    <title>{&segment.title}</title>
+                                </rect>
+                            }
+                        })}
+                        if i % label_every == 0 {
+                            <text x={(x + bar_width / 2.0).to_string()} y={(STACK_HEIGHT + 16.0).to_string()}
+                                text-anchor="middle" font-size="11" fill="#6B7280">
+                                {&column.label}
+                            </text>
+                        }
+                    </g>
+                }
+            })}
+        </svg>
+    }
+}
@@ -0,0 +1,19 @@
+use shared::ValidationErrors;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct FieldErrorProps {
+    pub errors: ValidationErrors,
+    pub field: AttrValue,
+}
+
+/// Message shown under a form input when its field failed validation.
+#[function_component(FieldError)]
+pub fn field_error(props: &FieldErrorProps) -> Html {
+    match props.errors.get(&props.field) {
+        Some(message) => html! {
+            <p class="text-red-600 text-xs italic mt-1">{message}</p>
+        },
+        None => html! {},
+    }
+}
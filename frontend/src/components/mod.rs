@@ -0,0 +1,5 @@
+mod charts;
+mod field_error;
+
+pub use charts::{Bar, BarChart, StackSegment, StackedBarChart, StackedColumn};
+pub use field_error::FieldError;
\ No newline at end of file
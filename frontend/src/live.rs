@@ -0,0 +1,81 @@
+use std::rc::Rc;
+
+use shared::LiveEvent;
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{EventSource, EventSourceInit, MessageEvent};
+use yew::prelude::*;
+
+use crate::api;
+
+/// An open event stream, closed when dropped.
+struct Connection {
+    source: EventSource,
+    _on_change: Closure<dyn FnMut(MessageEvent)>,
+    _on_open: Closure<dyn FnMut(web_sys::Event)>,
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.source.close();
+    }
+}
+
+/// Subscribes to the current workspace's changes. The browser reconnects on
+/// its own; changes missed meanwhile are reported as `Resync`.
+fn connect(push: impl Fn(LiveEvent) + 'static) -> Option<Connection> {
+    let init = EventSourceInit::new();
+    // The development backend is on another origin and needs the login cookie
+    init.set_with_credentials(true);
+    let source = EventSource::new_with_event_source_init_dict(&api::client().events_url(), &init).ok()?;
+    let push = Rc::new(push);
+
+    let on_change = {
+        let push = push.clone();
+        Closure::<dyn FnMut(MessageEvent)>::new(move |message: MessageEvent| {
+            if let Some(event) = message.data().as_string().and_then(|data| serde_json::from_str(&data).ok()) {
+                push(event);
+            }
+        })
+    };
+    let mut opened = false;
+    let on_open = Closure::<dyn FnMut(web_sys::Event)>::new(move |_| {
+        if opened {
+            push(LiveEvent::Resync);
+        }
+        opened = true;
+    });
+    source.add_event_listener_with_callback("change", on_change.as_ref().unchecked_ref()).ok()?;
+    source.add_event_listener_with_callback("open", on_open.as_ref().unchecked_ref()).ok()?;
+
+    Some(Connection { source, _on_change: on_change, _on_open: on_open })
+}
+
+/// Applies changes made elsewhere, e.g. in another tab or from the CLI, while
+/// the component is mounted. `on_events` runs after a render with the events
+/// received since the previous one, so the state it captured is current.
+#[hook]
+pub fn use_live_events<F>(on_events: F)
+where
+    F: FnOnce(Vec<LiveEvent>) + 'static,
+{
+    let queue = use_mut_ref(Vec::new);
+    let update = use_force_update();
+
+    {
+        let queue = queue.clone();
+        use_effect_with((), move |_| {
+            let connection = connect(move |event| {
+                queue.borrow_mut().push(event);
+                update.force_update();
+            });
+            move || drop(connection)
+        });
+    }
+
+    use_effect(move || {
+        let events = std::mem::take(&mut *queue.borrow_mut());
+        if !events.is_empty() {
+            on_events(events);
+        }
+    });
+}
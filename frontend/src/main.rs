@@ -3,9 +3,12 @@ use yew_router::prelude::*;
 use uuid::Uuid;
 
 mod api;
+mod components;
+mod live;
 mod pages;
 
-use pages::{Sessions, Tags, SessionDetail};
+use shared::{User, Workspace};
+use pages::{Login, Sessions, Tags, Projects, SessionDetail, Reports, Invoices, Tokens, Webhooks, Workspaces};
 
 #[derive(Clone, Routable, PartialEq)]
 enum Route {
@@ -17,9 +20,21 @@ enum Route {
     SessionDetail { id: String },
     #[at("/tags")]
     Tags,
+    #[at("/projects")]
+    Projects,
+    #[at("/reports")]
+    Reports,
+    #[at("/invoices")]
+    Invoices,
+    #[at("/tokens")]
+    Tokens,
+    #[at("/webhooks")]
+    Webhooks,
+    #[at("/workspace")]
+    Workspace,
 }
 
-fn switch(routes: Route) -> Html {
+fn switch(routes: Route, workspace: Option<Workspace>, on_workspaces_change: Callback<Option<Uuid>>) -> Html {
     match routes {
         Route::Home => html! { <Redirect<Route> to={Route::Sessions}/> },
         Route::Sessions => html! { <Sessions /> },
@@ -31,11 +46,118 @@ fn switch(routes: Route) -> Html {
             }
         },
         Route::Tags => html! { <Tags /> },
+        Route::Projects => html! { <Projects /> },
+        Route::Reports => html! { <Reports /> },
+        Route::Invoices => html! { <Invoices /> },
+        Route::Tokens => html! { <Tokens /> },
+        Route::Webhooks => html! { <Webhooks /> },
+        Route::Workspace => match workspace {
+            Some(workspace) => html! { <Workspaces workspace={workspace} on_change={on_workspaces_change} /> },
+            None => html! { <div>{"You are not a member of any workspace"}</div> },
+        },
     }
 }
 
 #[function_component(App)]
 fn app() -> Html {
+    // `None` until the login check has finished
+    let user = use_state(|| None::<Option<User>>);
+
+    {
+        let user = user.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                user.set(Some(api::client().current_user().await.ok()));
+            });
+            || {}
+        });
+    }
+
+    let workspaces = use_state(Vec::<Workspace>::new);
+    let workspace_id = use_state(|| None::<Uuid>);
+
+    // Reloads the user's workspaces, switching to `select` if given and
+    // otherwise keeping the current one if it still exists
+    let reload_workspaces = {
+        let workspaces = workspaces.clone();
+        let workspace_id = workspace_id.clone();
+        Callback::from(move |select: Option<Uuid>| {
+            let workspaces = workspaces.clone();
+            let workspace_id = workspace_id.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Ok(list) = api::client().get_workspaces().await {
+                    let current = select.or(*workspace_id);
+                    let selected = list
+                        .iter()
+                        .find(|w| Some(w.id) == current)
+                        .or(list.first())
+                        .map(|w| w.id);
+                    api::set_workspace(selected);
+                    workspace_id.set(selected);
+                    workspaces.set(list);
+                }
+            });
+        })
+    };
+
+    {
+        let reload_workspaces = reload_workspaces.clone();
+        let user_id = user.as_ref().and_then(|u| u.as_ref()).map(|u| u.id);
+        use_effect_with(user_id, move |user_id| {
+            if user_id.is_some() {
+                reload_workspaces.emit(None);
+            }
+            || {}
+        });
+    }
+
+    let on_select_workspace = {
+        let workspace_id = workspace_id.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<web_sys::HtmlSelectElement>() {
+                if let Ok(id) = Uuid::parse_str(&select.value()) {
+                    api::set_workspace(Some(id));
+                    workspace_id.set(Some(id));
+                }
+            }
+        })
+    };
+
+    let on_login = {
+        let user = user.clone();
+        Callback::from(move |logged_in: User| user.set(Some(Some(logged_in))))
+    };
+
+    let on_logout = {
+        let user = user.clone();
+        Callback::from(move |_| {
+            let user = user.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if api::client().logout().await.is_ok() {
+                    user.set(Some(None));
+                }
+            });
+        })
+    };
+
+    let current_user = match &*user {
+        None => return html! {},
+        Some(None) => {
+            return html! {
+                <div class="min-h-screen bg-gray-50 py-10">
+                    <Login on_login={on_login} />
+                </div>
+            };
+        }
+        Some(Some(user)) => user.clone(),
+    };
+
+    let current_workspace = workspaces.iter().find(|w| Some(w.id) == *workspace_id).cloned();
+    let render = {
+        let current_workspace = current_workspace.clone();
+        move |route| switch(route, current_workspace.clone(), reload_workspaces.clone())
+    };
+
     html! {
         <BrowserRouter>
             <div class="min-h-screen bg-gray-50">
@@ -53,14 +175,46 @@ fn app() -> Html {
                                     <Link<Route> to={Route::Tags} classes="inline-flex items-center px-1 pt-1 border-b-2 border-transparent text-sm font-medium text-gray-500 hover:text-gray-700 hover:border-gray-300">
                                         {"Tags"}
                                     </Link<Route>>
+                                    <Link<Route> to={Route::Projects} classes="inline-flex items-center px-1 pt-1 border-b-2 border-transparent text-sm font-medium text-gray-500 hover:text-gray-700 hover:border-gray-300">
+                                        {"Projects"}
+                                    </Link<Route>>
+                                    <Link<Route> to={Route::Reports} classes="inline-flex items-center px-1 pt-1 border-b-2 border-transparent text-sm font-medium text-gray-500 hover:text-gray-700 hover:border-gray-300">
+                                        {"Reports"}
+                                    </Link<Route>>
+                                    <Link<Route> to={Route::Invoices} classes="inline-flex items-center px-1 pt-1 border-b-2 border-transparent text-sm font-medium text-gray-500 hover:text-gray-700 hover:border-gray-300">
+                                        {"Invoices"}
+                                    </Link<Route>>
+                                    <Link<Route> to={Route::Tokens} classes="inline-flex items-center px-1 pt-1 border-b-2 border-transparent text-sm font-medium text-gray-500 hover:text-gray-700 hover:border-gray-300">
+                                        {"API Tokens"}
+                                    </Link<Route>>
+                                    <Link<Route> to={Route::Webhooks} classes="inline-flex items-center px-1 pt-1 border-b-2 border-transparent text-sm font-medium text-gray-500 hover:text-gray-700 hover:border-gray-300">
+                                        {"Webhooks"}
+                                    </Link<Route>>
+                                    <Link<Route> to={Route::Workspace} classes="inline-flex items-center px-1 pt-1 border-b-2 border-transparent text-sm font-medium text-gray-500 hover:text-gray-700 hover:border-gray-300">
+                                        {"Workspace"}
+                                    </Link<Route>>
                                 </div>
                             </div>
+                            <div class="flex items-center space-x-4">
+                                <select onchange={on_select_workspace} class="border rounded py-1 px-2 text-sm text-gray-700">
+                                    {for workspaces.iter().map(|w| html! {
+                                        <option value={w.id.to_string()} selected={Some(w.id) == *workspace_id}>{&w.name}</option>
+                                    })}
+                                </select>
+                                <span class="text-sm text-gray-500">{&current_user.email}</span>
+                                <button onclick={on_logout} class="text-sm font-medium text-gray-500 hover:text-gray-700">
+                                    {"Log out"}
+                                </button>
+                            </div>
                         </div>
                     </div>
                 </nav>
                 
-                <main class="py-10">
-                    <Switch<Route> render={switch} />
+                // Remount the page when switching workspaces so it reloads its data
+                <main class="py-10" key={workspace_id.map(|id| id.to_string()).unwrap_or_default()}>
+                    if workspace_id.is_some() {
+                        <Switch<Route> render={render} />
+                    }
                 </main>
             </div>
         </BrowserRouter>
@@ -0,0 +1,284 @@
+use yew::prelude::*;
+use chrono::{Datelike, Local, NaiveDate};
+use uuid::Uuid;
+use shared::{Client, CreateInvoiceRequest, Invoice, InvoiceGrouping, Tag, Validate, ValidationErrors};
+use crate::api;
+use crate::components::FieldError;
+use crate::pages::reports::browser_timezone;
+
+fn format_hours(seconds: i64) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    format!("{hours}h {minutes:02}m")
+}
+
+/// Invoices generated from billable sessions, with links to their HTML and
+/// PDF renderings.
+#[function_component(Invoices)]
+pub fn invoices() -> Html {
+    let today = Local::now().date_naive();
+    let invoices = use_state(Vec::<Invoice>::new);
+    let clients = use_state(Vec::<Client>::new);
+    let tags = use_state(Vec::<Tag>::new);
+    let loading = use_state(|| false);
+    let error = use_state(|| None::<String>);
+
+    // Form states
+    let client_id = use_state(|| None::<Uuid>);
+    let tag_id = use_state(|| None::<Uuid>);
+    let from = use_state(|| today.with_day(1).unwrap_or(today));
+    let to = use_state(|| today);
+    let group_by = use_state(|| InvoiceGrouping::Tag);
+    let form_errors = use_state(ValidationErrors::default);
+
+    // Load invoices and the filter choices on component mount
+    {
+        let invoices = invoices.clone();
+        let clients = clients.clone();
+        let tags = tags.clone();
+        let loading = loading.clone();
+        let error = error.clone();
+
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                loading.set(true);
+                error.set(None);
+
+                match api::client().get_invoices().await {
+                    Ok(data) => invoices.set(data),
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+                if let Ok(data) = api::client().get_clients().await {
+                    clients.set(data);
+                }
+                if let Ok(data) = api::client().get_tags().await {
+                    tags.set(data);
+                }
+
+                loading.set(false);
+            });
+
+            || {}
+        });
+    }
+
+    let on_create = {
+        let invoices = invoices.clone();
+        let client_id = client_id.clone();
+        let tag_id = tag_id.clone();
+        let from = from.clone();
+        let to = to.clone();
+        let group_by = group_by.clone();
+        let form_errors = form_errors.clone();
+        let error = error.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let invoices = invoices.clone();
+            let form_errors = form_errors.clone();
+            let error = error.clone();
+            let req = CreateInvoiceRequest {
+                client_id: *client_id,
+                tag_id: *tag_id,
+                from: *from,
+                to: *to,
+                timezone: browser_timezone(),
+                group_by: *group_by,
+            };
+
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Err(errors) = req.validate() {
+                    form_errors.set(errors);
+                    return;
+                }
+
+                error.set(None);
+                form_errors.set(ValidationErrors::default());
+
+                match api::client().create_invoice(&req).await {
+                    Ok(invoice) => {
+                        let mut list = (*invoices).clone();
+                        list.insert(0, invoice);
+                        invoices.set(list);
+                    }
+                    Err(e) => match e.field_errors() {
+                        Some(errors) => form_errors.set(errors.clone()),
+                        None => error.set(Some(e.to_string())),
+                    },
+                }
+            });
+        })
+    };
+
+    let on_delete = {
+        let invoices = invoices.clone();
+        let error = error.clone();
+
+        Callback::from(move |id: Uuid| {
+            let invoices = invoices.clone();
+            let error = error.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                error.set(None);
+
+                match api::client().delete_invoice(id).await {
+                    Ok(()) => invoices.set(invoices.iter().filter(|i| i.id != id).cloned().collect()),
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+            });
+        })
+    };
+
+    let date_input = |value: NaiveDate, target: UseStateHandle<NaiveDate>| {
+        html! {
+            <input
+                type="date"
+                value={value.to_string()}
+                onchange={
+                    Callback::from(move |e: Event| {
+                        if let Some(input) = e.target_dyn_into::<web_sys::HtmlInputElement>() {
+                            if let Ok(date) = NaiveDate::parse_from_str(&input.value(), "%Y-%m-%d") {
+                                target.set(date);
+                            }
+                        }
+                    })
+                }
+                class="shadow appearance-none border rounded py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline"
+            />
+        }
+    };
+
+    let id_select = |value: UseStateHandle<Option<Uuid>>, placeholder: &'static str, options: Vec<(Uuid, String)>| {
+        html! {
+            <select
+                onchange={
+                    let value = value.clone();
+                    Callback::from(move |e: Event| {
+                        if let Some(select) = e.target_dyn_into::<web_sys::HtmlSelectElement>() {
+                            value.set(Uuid::parse_str(&select.value()).ok());
+                        }
+                    })
+                }
+                class="shadow border rounded py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline"
+            >
+                <option value="" selected={value.is_none()}>{placeholder}</option>
+                {for options.into_iter().map(|(id, name)| html! {
+                    <option value={id.to_string()} selected={*value == Some(id)}>{name}</option>
+                })}
+            </select>
+        }
+    };
+
+    html! {
+        <div class="container mx-auto p-4">
+            <h1 class="text-3xl font-bold mb-6">{"Invoices"}</h1>
+
+            if let Some(error_msg) = error.as_ref() {
+                <div class="bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded mb-4">
+                    {error_msg}
+                </div>
+            }
+
+            // New invoice
+            <div class="bg-white shadow-md rounded px-8 pt-6 pb-8 mb-6">
+                <h2 class="text-xl font-semibold mb-2">{"Create Invoice"}</h2>
+                <p class="text-sm text-gray-600 mb-4">
+                    {"Invoices every finished, billable session in the range that is not on an invoice yet. Invoiced sessions cannot be changed until their invoice is deleted."}
+                </p>
+                <form onsubmit={on_create} class="flex flex-wrap items-end gap-4">
+                    <div>
+                        <label class="block text-gray-700 text-sm font-bold mb-2">{"Client"}</label>
+                        {id_select(client_id.clone(), "Any client", clients.iter().map(|c| (c.id, c.name.clone())).collect())}
+                        <FieldError errors={(*form_errors).clone()} field="client_id" />
+                    </div>
+                    <div>
+                        <label class="block text-gray-700 text-sm font-bold mb-2">{"Tag"}</label>
+                        {id_select(tag_id.clone(), "Any tag", tags.iter().map(|t| (t.id, t.name.clone())).collect())}
+                        <FieldError errors={(*form_errors).clone()} field="tag_id" />
+                    </div>
+                    <div>
+                        <label class="block text-gray-700 text-sm font-bold mb-2">{"From"}</label>
+                        {date_input(*from, from.clone())}
+                    </div>
+                    <div>
+                        <label class="block text-gray-700 text-sm font-bold mb-2">{"To"}</label>
+                        {date_input(*to, to.clone())}
+                        <FieldError errors={(*form_errors).clone()} field="to" />
+                    </div>
+                    <div>
+                        <label class="block text-gray-700 text-sm font-bold mb-2">{"Lines"}</label>
+                        <select
+                            onchange={
+                                let group_by = group_by.clone();
+                                Callback::from(move |e: Event| {
+                                    if let Some(select) = e.target_dyn_into::<web_sys::HtmlSelectElement>() {
+                                        if let Some(grouping) = InvoiceGrouping::parse(&select.value()) {
+                                            group_by.set(grouping);
+                                        }
+                                    }
+                                })
+                            }
+                            class="shadow border rounded py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline"
+                        >
+                            <option value="tag" selected={*group_by == InvoiceGrouping::Tag}>{"Per tag"}</option>
+                            <option value="day" selected={*group_by == InvoiceGrouping::Day}>{"Per day"}</option>
+                        </select>
+                    </div>
+                    <button
+                        type="submit"
+                        class="bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline"
+                    >
+                        {"Create Invoice"}
+                    </button>
+                </form>
+            </div>
+
+            // Invoice list
+            <div class="bg-white shadow-md rounded">
+                <div class="px-6 py-4 border-b">
+                    <h2 class="text-xl font-semibold">{"Invoices"}</h2>
+                </div>
+
+                if invoices.is_empty() && !*loading {
+                    <div class="px-6 py-4 text-gray-500 text-center">
+                        {"No invoices yet."}
+                    </div>
+                } else {
+                    <ul class="divide-y">
+                        {for invoices.iter().map(|invoice| {
+                            let invoice_id = invoice.id;
+                            let on_delete = on_delete.clone();
+                            let seconds: i64 = invoice.lines.iter().map(|l| l.seconds).sum();
+
+                            html! {
+                                <li class="px-6 py-4 flex items-center justify-between">
+                                    <div>
+                                        <p class="font-medium text-gray-900">
+                                            {format!("#{}", invoice.number)}
+                                            if let Some(client) = &invoice.client_name {
+                                                <span class="ml-2 text-gray-700">{client}</span>
+                                            }
+                                        </p>
+                                        <p class="text-sm text-gray-600">
+                                            {format!("{} to {}, {}, {} {}", invoice.from, invoice.to, format_hours(seconds), invoice.total, invoice.currency)}
+                                        </p>
+                                    </div>
+                                    <div class="flex items-center space-x-4 text-sm">
+                                        <a href={api::client().invoice_url(invoice_id, "html")} target="_blank" class="text-blue-600 hover:text-blue-800">{"HTML"}</a>
+                                        <a href={api::client().invoice_url(invoice_id, "pdf")} target="_blank" class="text-blue-600 hover:text-blue-800">{"PDF"}</a>
+                                        <button
+                                            class="text-red-600 hover:text-red-800"
+                                            onclick={Callback::from(move |_| on_delete.emit(invoice_id))}
+                                        >
+                                            {"Delete"}
+                                        </button>
+                                    </div>
+                                </li>
+                            }
+                        })}
+                    </ul>
+                }
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,137 @@
+use yew::prelude::*;
+use shared::{LoginRequest, RegisterRequest, User, Validate, ValidationErrors};
+use crate::api;
+use crate::components::FieldError;
+
+#[derive(Properties, PartialEq)]
+pub struct LoginProps {
+    pub on_login: Callback<User>,
+}
+
+/// Login form, which can switch to creating an account instead.
+#[function_component(Login)]
+pub fn login(props: &LoginProps) -> Html {
+    let registering = use_state(|| false);
+    let email = use_state(String::new);
+    let password = use_state(String::new);
+    let loading = use_state(|| false);
+    let error = use_state(|| None::<String>);
+    let field_errors = use_state(ValidationErrors::default);
+
+    let on_submit = {
+        let registering = registering.clone();
+        let email = email.clone();
+        let password = password.clone();
+        let loading = loading.clone();
+        let error = error.clone();
+        let field_errors = field_errors.clone();
+        let on_login = props.on_login.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let registering = *registering;
+            let email = (*email).clone();
+            let password = (*password).clone();
+            let loading = loading.clone();
+            let error = error.clone();
+            let field_errors = field_errors.clone();
+            let on_login = on_login.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = if registering {
+                    let req = RegisterRequest { email, password };
+                    if let Err(errors) = req.validate() {
+                        field_errors.set(errors);
+                        return;
+                    }
+                    loading.set(true);
+                    api::client().register(&req).await
+                } else {
+                    loading.set(true);
+                    api::client().login(&LoginRequest { email, password }).await
+                };
+
+                error.set(None);
+                field_errors.set(ValidationErrors::default());
+                match result {
+                    Ok(user) => on_login.emit(user),
+                    Err(e) => match e.field_errors() {
+                        Some(errors) => field_errors.set(errors.clone()),
+                        None => error.set(Some(e.to_string())),
+                    },
+                }
+
+                loading.set(false);
+            });
+        })
+    };
+
+    let on_toggle = {
+        let registering = registering.clone();
+        let error = error.clone();
+        let field_errors = field_errors.clone();
+        Callback::from(move |_| {
+            registering.set(!*registering);
+            error.set(None);
+            field_errors.set(ValidationErrors::default());
+        })
+    };
+
+    let text_input = |id: &'static str, input_type: &'static str, value: UseStateHandle<String>| {
+        html! {
+            <input
+                id={id}
+                type={input_type}
+                value={(*value).clone()}
+                oninput={
+                    let value = value.clone();
+                    Callback::from(move |e: InputEvent| {
+                        if let Some(input) = e.target_dyn_into::<web_sys::HtmlInputElement>() {
+                            value.set(input.value());
+                        }
+                    })
+                }
+                class="shadow appearance-none border rounded w-full py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline"
+            />
+        }
+    };
+
+    html! {
+        <div class="container mx-auto p-4 max-w-md">
+            <h1 class="text-3xl font-bold mb-6">
+                {if *registering { "Create Account" } else { "Log In" }}
+            </h1>
+
+            if let Some(error_msg) = error.as_ref() {
+                <div class="bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded mb-4">
+                    {error_msg}
+                </div>
+            }
+
+            <form onsubmit={on_submit} class="bg-white shadow-md rounded px-8 pt-6 pb-8 mb-4">
+                <div class="mb-4">
+                    <label class="block text-gray-700 text-sm font-bold mb-2" for="email">{"Email"}</label>
+                    {text_input("email", "email", email.clone())}
+                    <FieldError errors={(*field_errors).clone()} field="email" />
+                </div>
+                <div class="mb-6">
+                    <label class="block text-gray-700 text-sm font-bold mb-2" for="password">{"Password"}</label>
+                    {text_input("password", "password", password.clone())}
+                    <FieldError errors={(*field_errors).clone()} field="password" />
+                </div>
+                <div class="flex items-center justify-between">
+                    <button
+                        type="submit"
+                        disabled={*loading}
+                        class="bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline disabled:opacity-50"
+                    >
+                        {if *registering { "Create Account" } else { "Log In" }}
+                    </button>
+                    <button type="button" onclick={on_toggle} class="text-sm text-blue-600 hover:text-blue-800">
+                        {if *registering { "I already have an account" } else { "Create an account" }}
+                    </button>
+                </div>
+            </form>
+        </div>
+    }
+}
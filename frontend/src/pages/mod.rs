@@ -1,7 +1,21 @@
 mod sessions;
 mod tags;
 mod session_detail;
+mod reports;
+mod login;
+mod tokens;
+mod workspaces;
+mod projects;
+mod invoices;
+mod webhooks;
 
 pub use sessions::Sessions;
 pub use tags::Tags;
-pub use session_detail::SessionDetail;
\ No newline at end of file
+pub use session_detail::SessionDetail;
+pub use reports::Reports;
+pub use login::Login;
+pub use tokens::Tokens;
+pub use workspaces::Workspaces;
+pub use projects::Projects;
+pub use invoices::Invoices;
+pub use webhooks::Webhooks;
@@ -0,0 +1,395 @@
+use std::str::FromStr;
+
+use yew::prelude::*;
+use uuid::Uuid;
+use rust_decimal::Decimal;
+use shared::{Client, CreateClientRequest, CreateProjectRequest, Project, Validate, ValidationErrors};
+use crate::api;
+use crate::components::FieldError;
+
+/// Fetches clients and projects together, since projects are listed under
+/// their client.
+async fn load(
+    clients: UseStateHandle<Vec<Client>>,
+    projects: UseStateHandle<Vec<Project>>,
+    error: UseStateHandle<Option<String>>,
+) {
+    match api::client().get_clients().await {
+        Ok(data) => clients.set(data),
+        Err(e) => return error.set(Some(e.to_string())),
+    }
+    match api::client().get_projects().await {
+        Ok(data) => projects.set(data),
+        Err(e) => error.set(Some(e.to_string())),
+    }
+}
+
+/// Parses an optional hourly rate typed into a form; blank means none.
+pub(crate) fn parse_hourly_rate(input: &str) -> Result<Option<Decimal>, ValidationErrors> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+    Decimal::from_str(input)
+        .map(Some)
+        .map_err(|_| ValidationErrors::single("hourly_rate", "must be a number"))
+}
+
+fn format_budget(budget_seconds: i32) -> String {
+    let hours = budget_seconds as f64 / 3600.0;
+    format!("{hours:.1}h budget")
+}
+
+/// Clients and the projects sessions can be booked against.
+#[function_component(Projects)]
+pub fn projects() -> Html {
+    let clients = use_state(Vec::<Client>::new);
+    let projects = use_state(Vec::<Project>::new);
+    let loading = use_state(|| false);
+    let error = use_state(|| None::<String>);
+
+    // Form states
+    let client_name = use_state(String::new);
+    let client_errors = use_state(ValidationErrors::default);
+    let project_client = use_state(|| None::<Uuid>);
+    let project_name = use_state(String::new);
+    let project_rate = use_state(String::new);
+    let project_budget = use_state(String::new);
+    let project_errors = use_state(ValidationErrors::default);
+
+    // Load clients and projects on component mount
+    {
+        let clients = clients.clone();
+        let projects = projects.clone();
+        let loading = loading.clone();
+        let error = error.clone();
+
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                loading.set(true);
+                error.set(None);
+                load(clients, projects, error).await;
+                loading.set(false);
+            });
+
+            || {}
+        });
+    }
+
+    let on_create_client = {
+        let client_name = client_name.clone();
+        let client_errors = client_errors.clone();
+        let clients = clients.clone();
+        let projects = projects.clone();
+        let error = error.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let client_name = client_name.clone();
+            let client_errors = client_errors.clone();
+            let clients = clients.clone();
+            let projects = projects.clone();
+            let error = error.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                let req = CreateClientRequest {
+                    name: (*client_name).clone(),
+                };
+                if let Err(errors) = req.validate() {
+                    client_errors.set(errors);
+                    return;
+                }
+
+                error.set(None);
+                client_errors.set(ValidationErrors::default());
+
+                match api::client().create_client(&req).await {
+                    Ok(_) => {
+                        client_name.set(String::new());
+                        load(clients, projects, error).await;
+                    }
+                    Err(api::ApiError::Conflict { message, .. }) => {
+                        client_errors.set(ValidationErrors::single("name", message));
+                    }
+                    Err(e) => match e.field_errors() {
+                        Some(errors) => client_errors.set(errors.clone()),
+                        None => error.set(Some(e.to_string())),
+                    },
+                }
+            });
+        })
+    };
+
+    let on_create_project = {
+        let project_client = project_client.clone();
+        let project_name = project_name.clone();
+        let project_rate = project_rate.clone();
+        let project_budget = project_budget.clone();
+        let project_errors = project_errors.clone();
+        let clients = clients.clone();
+        let projects = projects.clone();
+        let error = error.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let project_name = project_name.clone();
+            let project_rate = project_rate.clone();
+            let project_budget = project_budget.clone();
+            let project_errors = project_errors.clone();
+            let clients = clients.clone();
+            let projects = projects.clone();
+            let error = error.clone();
+
+            let Some(client_id) = *project_client else {
+                project_errors.set(ValidationErrors::single("client_id", "is required"));
+                return;
+            };
+            let hourly_rate = match parse_hourly_rate(&project_rate) {
+                Ok(rate) => rate,
+                Err(errors) => {
+                    project_errors.set(errors);
+                    return;
+                }
+            };
+            // Budgets are entered in hours
+            let budget = project_budget.trim();
+            let budget_seconds = match (!budget.is_empty()).then(|| budget.parse::<f64>()).transpose() {
+                Ok(hours) => hours.map(|h| (h * 3600.0).round() as i32),
+                Err(_) => {
+                    project_errors.set(ValidationErrors::single("budget_seconds", "must be a number of hours"));
+                    return;
+                }
+            };
+
+            wasm_bindgen_futures::spawn_local(async move {
+                let req = CreateProjectRequest {
+                    client_id,
+                    name: (*project_name).clone(),
+                    hourly_rate,
+                    budget_seconds,
+                };
+                if let Err(errors) = req.validate() {
+                    project_errors.set(errors);
+                    return;
+                }
+
+                error.set(None);
+                project_errors.set(ValidationErrors::default());
+
+                match api::client().create_project(&req).await {
+                    Ok(_) => {
+                        project_name.set(String::new());
+                        project_rate.set(String::new());
+                        project_budget.set(String::new());
+                        load(clients, projects, error).await;
+                    }
+                    Err(api::ApiError::Conflict { message, .. }) => {
+                        project_errors.set(ValidationErrors::single("name", message));
+                    }
+                    Err(e) => match e.field_errors() {
+                        Some(errors) => project_errors.set(errors.clone()),
+                        None => error.set(Some(e.to_string())),
+                    },
+                }
+            });
+        })
+    };
+
+    let on_delete_client = {
+        let clients = clients.clone();
+        let projects = projects.clone();
+        let error = error.clone();
+
+        Callback::from(move |id: Uuid| {
+            let clients = clients.clone();
+            let projects = projects.clone();
+            let error = error.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                error.set(None);
+
+                match api::client().delete_client(id).await {
+                    Ok(()) => load(clients, projects, error).await,
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+            });
+        })
+    };
+
+    let on_delete_project = {
+        let projects = projects.clone();
+        let error = error.clone();
+
+        Callback::from(move |id: Uuid| {
+            let projects = projects.clone();
+            let error = error.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                error.set(None);
+
+                match api::client().delete_project(id).await {
+                    Ok(()) => projects.set(projects.iter().filter(|p| p.id != id).cloned().collect()),
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+            });
+        })
+    };
+
+    let text_input = |value: UseStateHandle<String>, placeholder: &'static str| {
+        html! {
+            <input
+                type="text"
+                value={(*value).clone()}
+                oninput={
+                    let value = value.clone();
+                    Callback::from(move |e: InputEvent| {
+                        if let Some(input) = e.target_dyn_into::<web_sys::HtmlInputElement>() {
+                            value.set(input.value());
+                        }
+                    })
+                }
+                class="shadow appearance-none border rounded w-full py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline"
+                placeholder={placeholder}
+            />
+        }
+    };
+
+    html! {
+        <div class="container mx-auto p-4">
+            <h1 class="text-3xl font-bold mb-6">{"Clients & Projects"}</h1>
+
+            if let Some(error_msg) = error.as_ref() {
+                <div class="bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded mb-4">
+                    {error_msg}
+                </div>
+            }
+
+            <div class="grid md:grid-cols-2 gap-6 mb-6">
+                // New client
+                <div class="bg-white shadow-md rounded px-8 pt-6 pb-8">
+                    <h2 class="text-xl font-semibold mb-4">{"Create New Client"}</h2>
+                    <form onsubmit={on_create_client}>
+                        <div class="mb-4">
+                            {text_input(client_name.clone(), "Client name")}
+                            <FieldError errors={(*client_errors).clone()} field="name" />
+                        </div>
+                        <button
+                            type="submit"
+                            class="bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline"
+                        >
+                            {"Create Client"}
+                        </button>
+                    </form>
+                </div>
+
+                // New project
+                <div class="bg-white shadow-md rounded px-8 pt-6 pb-8">
+                    <h2 class="text-xl font-semibold mb-4">{"Create New Project"}</h2>
+                    <form onsubmit={on_create_project}>
+                        <div class="mb-4">
+                            <select
+                                onchange={
+                                    let project_client = project_client.clone();
+                                    Callback::from(move |e: Event| {
+                                        if let Some(select) = e.target_dyn_into::<web_sys::HtmlSelectElement>() {
+                                            project_client.set(Uuid::parse_str(&select.value()).ok());
+                                        }
+                                    })
+                                }
+                                class="shadow border rounded w-full py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline"
+                            >
+                                <option value="" selected={project_client.is_none()}>{"Choose a client"}</option>
+                                {for clients.iter().map(|client| html! {
+                                    <option value={client.id.to_string()} selected={*project_client == Some(client.id)}>
+                                        {&client.name}
+                                    </option>
+                                })}
+                            </select>
+                            <FieldError errors={(*project_errors).clone()} field="client_id" />
+                        </div>
+                        <div class="mb-4">
+                            {text_input(project_name.clone(), "Project name")}
+                            <FieldError errors={(*project_errors).clone()} field="name" />
+                        </div>
+                        <div class="mb-4 flex gap-4">
+                            <div class="flex-1">
+                                {text_input(project_rate.clone(), "Hourly rate (optional)")}
+                                <FieldError errors={(*project_errors).clone()} field="hourly_rate" />
+                            </div>
+                            <div class="flex-1">
+                                {text_input(project_budget.clone(), "Budget in hours (optional)")}
+                                <FieldError errors={(*project_errors).clone()} field="budget_seconds" />
+                            </div>
+                        </div>
+                        <button
+                            type="submit"
+                            class="bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline"
+                        >
+                            {"Create Project"}
+                        </button>
+                    </form>
+                </div>
+            </div>
+
+            // Clients with their projects
+            <div class="bg-white shadow-md rounded">
+                <div class="px-6 py-4 border-b">
+                    <h2 class="text-xl font-semibold">{"Clients"}</h2>
+                </div>
+
+                if clients.is_empty() && !*loading {
+                    <div class="px-6 py-4 text-gray-500 text-center">
+                        {"No clients yet."}
+                    </div>
+                } else {
+                    <ul class="divide-y">
+                        {for clients.iter().map(|client| {
+                            let client_id = client.id;
+                            let on_delete = on_delete_client.clone();
+
+                            html! {
+                                <li class="px-6 py-4">
+                                    <div class="flex items-center justify-between">
+                                        <span class="font-medium text-gray-900">{&client.name}</span>
+                                        <button
+                                            class="text-red-600 hover:text-red-800 text-sm"
+                                            onclick={Callback::from(move |_| on_delete.emit(client_id))}
+                                        >
+                                            {"Delete"}
+                                        </button>
+                                    </div>
+                                    <ul class="mt-2 ml-4 space-y-1">
+                                        {for projects.iter().filter(|p| p.client_id == client_id).map(|project| {
+                                            let project_id = project.id;
+                                            let on_delete = on_delete_project.clone();
+
+                                            html! {
+                                                <li class="flex items-center justify-between text-sm">
+                                                    <span class="text-gray-700">
+                                                        {&project.name}
+                                                        if let Some(rate) = project.hourly_rate {
+                                                            <span class="ml-2 text-gray-500">{format!("{rate}/h")}</span>
+                                                        }
+                                                        if let Some(budget) = project.budget_seconds {
+                                                            <span class="ml-2 text-gray-500">{format_budget(budget)}</span>
+                                                        }
+                                                    </span>
+                                                    <button
+                                                        class="text-red-600 hover:text-red-800"
+                                                        onclick={Callback::from(move |_| on_delete.emit(project_id))}
+                                                    >
+                                                        {"Delete"}
+                                                    </button>
+                                                </li>
+                                            }
+                                        })}
+                                    </ul>
+                                </li>
+                            }
+                        })}
+                    </ul>
+                }
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,220 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use yew::prelude::*;
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use uuid::Uuid;
+use shared::{Report, ReportPeriod, ReportQuery};
+use crate::api;
+use crate::components::{Bar, BarChart, StackSegment, StackedBarChart, StackedColumn};
+
+const UNTAGGED_COLOR: &str = "#9CA3AF";
+
+/// The browser's IANA timezone, so days are split at local midnight.
+pub(crate) fn browser_timezone() -> Option<String> {
+    let format = js_sys::Intl::DateTimeFormat::new(&js_sys::Array::new(), &js_sys::Object::new());
+    js_sys::Reflect::get(&format.resolved_options(), &"timeZone".into())
+        .ok()
+        .and_then(|tz| tz.as_string())
+}
+
+fn format_hours(seconds: i64) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    format!("{hours}h {minutes:02}m")
+}
+
+/// Per-tag totals across the whole range, largest first.
+fn tag_bars(report: &Report) -> Vec<Bar> {
+    let mut totals: BTreeMap<Option<Uuid>, Bar> = BTreeMap::new();
+    for row in &report.rows {
+        let bar = totals.entry(row.tag_id).or_insert_with(|| Bar {
+            label: row.tag_name.clone().unwrap_or_else(|| "Untagged".to_string()),
+            value: 0,
+            display: String::new(),
+            color: row.tag_color.clone().unwrap_or_else(|| UNTAGGED_COLOR.to_string()),
+        });
+        bar.value += row.total_seconds;
+    }
+
+    let mut bars: Vec<Bar> = totals.into_values().collect();
+    for bar in &mut bars {
+        bar.display = format_hours(bar.value);
+    }
+    bars.sort_by_key(|bar| Reverse(bar.value));
+    bars
+}
+
+/// One column per day in the range, stacked by tag.
+fn day_columns(report: &Report) -> Vec<StackedColumn> {
+    let mut columns = Vec::new();
+    let mut day = report.from;
+    while day <= report.to {
+        let segments = report
+            .rows
+            .iter()
+            .filter(|row| row.period_start == Some(day))
+            .map(|row| {
+                let name = row.tag_name.clone().unwrap_or_else(|| "Untagged".to_string());
+                StackSegment {
+                    value: row.total_seconds,
+                    color: row.tag_color.clone().unwrap_or_else(|| UNTAGGED_COLOR.to_string()),
+                    title: format!("{day}: {name} {}", format_hours(row.total_seconds)),
+                }
+            })
+            .collect();
+        columns.push(StackedColumn {
+            label: day.format("%m-%d").to_string(),
+            segments,
+        });
+        day += Duration::days(1);
+    }
+    columns
+}
+
+#[function_component(Reports)]
+pub fn reports() -> Html {
+    let today = Local::now().date_naive();
+    let from = use_state(|| today - Duration::days(6));
+    let to = use_state(|| today);
+    let report = use_state(|| None::<Report>);
+    let loading = use_state(|| false);
+    let error = use_state(|| None::<String>);
+
+    // Reload whenever the range changes
+    {
+        let report = report.clone();
+        let loading = loading.clone();
+        let error = error.clone();
+
+        use_effect_with((*from, *to), move |&(from, to)| {
+            let report = report.clone();
+            let loading = loading.clone();
+            let error = error.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                loading.set(true);
+                error.set(None);
+
+                let query = ReportQuery {
+                    from,
+                    to,
+                    timezone: browser_timezone(),
+                    period: Some(ReportPeriod::Day),
+                    by_tag: true,
+                };
+
+                match api::client().get_report(&query).await {
+                    Ok(data) => report.set(Some(data)),
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+
+                loading.set(false);
+            });
+
+            || {}
+        });
+    }
+
+    let set_range = {
+        let from = from.clone();
+        let to = to.clone();
+        Callback::from(move |(start, end): (NaiveDate, NaiveDate)| {
+            from.set(start);
+            to.set(end);
+        })
+    };
+
+    let date_input = |value: NaiveDate, target: UseStateHandle<NaiveDate>| {
+        html! {
+            <input
+                type="date"
+                value={value.to_string()}
+                onchange={
+                    Callback::from(move |e: Event| {
+                        if let Some(input) = e.target_dyn_into::<web_sys::HtmlInputElement>() {
+                            if let Ok(date) = NaiveDate::parse_from_str(&input.value(), "%Y-%m-%d") {
+                                target.set(date);
+                            }
+                        }
+                    })
+                }
+                class="shadow appearance-none border rounded py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline"
+            />
+        }
+    };
+
+    let presets = [
+        ("Last 7 days", today - Duration::days(6), today),
+        ("Last 30 days", today - Duration::days(29), today),
+        ("This month", today.with_day(1).unwrap_or(today), today),
+    ];
+
+    html! {
+        <div class="container mx-auto p-4">
+            <h1 class="text-3xl font-bold mb-6">{"Reports"}</h1>
+
+            if let Some(error_msg) = error.as_ref() {
+                <div class="bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded mb-4">
+                    {error_msg}
+                </div>
+            }
+
+            // Range selection
+            <div class="bg-white shadow-md rounded px-8 pt-6 pb-6 mb-6 flex flex-wrap items-end gap-4">
+                <div>
+                    <label class="block text-gray-700 text-sm font-bold mb-2">{"From"}</label>
+                    {date_input(*from, from.clone())}
+                </div>
+                <div>
+                    <label class="block text-gray-700 text-sm font-bold mb-2">{"To"}</label>
+                    {date_input(*to, to.clone())}
+                </div>
+                <div class="flex space-x-2">
+                    {for presets.iter().map(|&(label, start, end)| {
+                        let set_range = set_range.clone();
+                        html! {
+                            <button
+                                type="button"
+                                class="px-3 py-2 rounded text-sm border bg-gray-200 text-gray-700 border-gray-300 hover:bg-gray-300"
+                                onclick={Callback::from(move |_| set_range.emit((start, end)))}
+                            >
+                                {label}
+                            </button>
+                        }
+                    })}
+                </div>
+            </div>
+
+            if *loading && report.is_none() {
+                <div class="bg-white shadow-md rounded px-8 pt-6 pb-8 text-center">
+                    <p class="text-gray-600">{"Loading report..."}</p>
+                </div>
+            } else if let Some(report) = report.as_ref() {
+                <div class="bg-white shadow-md rounded px-8 pt-6 pb-8 mb-6">
+                    <h2 class="text-xl font-semibold mb-1">{"Total"}</h2>
+                    <p class="text-3xl font-bold text-blue-600">{format_hours(report.total_seconds)}</p>
+                    <p class="text-sm text-gray-600 mt-2">
+                        {format!("{} billable, earning {} {}", format_hours(report.billable_seconds), report.amount, report.currency)}
+                    </p>
+                </div>
+
+                if report.rows.is_empty() {
+                    <div class="bg-white shadow-md rounded px-6 py-4 text-gray-500 text-center">
+                        {"No sessions in this range."}
+                    </div>
+                } else {
+                    <div class="bg-white shadow-md rounded px-8 pt-6 pb-8 mb-6">
+                        <h2 class="text-xl font-semibold mb-4">{"Per Tag"}</h2>
+                        <BarChart bars={tag_bars(report)} />
+                    </div>
+
+                    <div class="bg-white shadow-md rounded px-8 pt-6 pb-8">
+                        <h2 class="text-xl font-semibold mb-4">{"Per Day"}</h2>
+                        <StackedBarChart columns={day_columns(report)} />
+                    </div>
+                }
+            }
+        </div>
+    }
+}
@@ -1,6 +1,7 @@
 use yew::prelude::*;
 use yew_router::prelude::*;
 use uuid::Uuid;
+use chrono::Utc;
 use shared::WorkSessionWithTags;
 use crate::api;
 use crate::Route;
@@ -33,9 +34,9 @@ pub fn session_detail(props: &Props) -> Html {
                 loading.set(true);
                 error.set(None);
 
-                match api::get_session(session_id).await {
+                match api::client().get_session(session_id).await {
                     Ok(data) => session.set(Some(data)),
-                    Err(e) => error.set(Some(e)),
+                    Err(e) => error.set(Some(e.to_string())),
                 }
 
                 loading.set(false);
@@ -101,6 +102,34 @@ pub fn session_detail(props: &Props) -> Html {
                                         {format_duration(session_data.duration_seconds)}
                                     </span>
                                 </div>
+                                <div>
+                                    <span class="font-medium text-gray-700">{"Started: "}</span>
+                                    <span class="text-gray-600">
+                                        {session_data.started_at.format("%Y-%m-%d %H:%M:%S").to_string()}
+                                    </span>
+                                </div>
+                                if let Some(ended_at) = session_data.ended_at {
+                                    <div>
+                                        <span class="font-medium text-gray-700">{"Ended: "}</span>
+                                        <span class="text-gray-600">
+                                            {ended_at.format("%Y-%m-%d %H:%M:%S").to_string()}
+                                        </span>
+                                    </div>
+                                } else {
+                                    <div>
+                                        <span class="font-medium text-blue-600">{"Running"}</span>
+                                    </div>
+                                }
+                                <div>
+                                    <span class="font-medium text-gray-700">{"Billing: "}</span>
+                                    <span class="text-gray-600">
+                                        {match (session_data.billable, session_data.effective_rate, session_data.amount) {
+                                            (true, Some(rate), Some(amount)) => format!("{amount} {currency} at {rate}/h", currency = session_data.currency),
+                                            (true, _, _) => "Billable, no rate set".to_string(),
+                                            (false, _, _) => "Not billable".to_string(),
+                                        }}
+                                    </span>
+                                </div>
                                 <div>
                                     <span class="font-medium text-gray-700">{"Created: "}</span>
                                     <span class="text-gray-600">
@@ -138,6 +167,34 @@ pub fn session_detail(props: &Props) -> Html {
                             }
                         </div>
                     </div>
+
+                    if !session_data.segments.is_empty() {
+                        <div class="mt-8">
+                            <h2 class="text-xl font-semibold mb-4 text-gray-800">{"Work Periods"}</h2>
+                            <table class="min-w-full divide-y divide-gray-200">
+                                <thead>
+                                    <tr class="text-left text-sm font-medium text-gray-700">
+                                        <th class="py-2">{"Started"}</th>
+                                        <th class="py-2">{"Ended"}</th>
+                                        <th class="py-2">{"Duration"}</th>
+                                    </tr>
+                                </thead>
+                                <tbody class="divide-y divide-gray-200 text-sm text-gray-600">
+                                    {for session_data.segments.iter().map(|segment| {
+                                        html! {
+                                            <tr>
+                                                <td class="py-2">{segment.started_at.format("%Y-%m-%d %H:%M:%S").to_string()}</td>
+                                                <td class="py-2">
+                                                    {segment.ended_at.map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string()).unwrap_or_else(|| "In progress".to_string())}
+                                                </td>
+                                                <td class="py-2">{format_duration(segment.duration_seconds(Utc::now()))}</td>
+                                            </tr>
+                                        }
+                                    })}
+                                </tbody>
+                            </table>
+                        </div>
+                    }
                 </div>
             } else {
                 <div class="bg-white shadow-md rounded px-8 pt-6 pb-8 text-center">
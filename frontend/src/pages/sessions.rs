@@ -1,46 +1,118 @@
 use yew::prelude::*;
 use uuid::Uuid;
-use shared::{CreateSessionRequest, WorkSessionWithTags, Tag};
+use chrono::Utc;
+use gloo_timers::callback::Interval;
+use shared::{Client, LiveEvent, Page, Project, SessionQuery, SortOrder, StartSessionRequest, Validate, ValidationErrors, WorkSessionWithTags, Tag};
 use crate::api;
+use crate::live::use_live_events;
+use crate::components::FieldError;
+
+/// Puts a new or changed session where the listing would have it, or drops
+/// it if it no longer matches. The page only filters on `search`.
+fn upsert_session(page: &mut Page<WorkSessionWithTags>, query: &SessionQuery, session: WorkSessionWithTags) {
+    page.items.retain(|s| s.id != session.id);
+
+    if let Some(search) = &query.search {
+        let description = session.description.as_deref().unwrap_or_default().to_lowercase();
+        if !description.contains(&search.to_lowercase()) {
+            return;
+        }
+    }
+
+    let position = page.items.iter().position(|s| match query.order {
+        SortOrder::Desc => s.started_at < session.started_at,
+        SortOrder::Asc => s.started_at > session.started_at,
+    });
+    match position {
+        Some(index) => page.items.insert(index, session),
+        // Past the loaded sessions, so it arrives with a later page
+        None if page.next_cursor.is_some() => {}
+        None => page.items.push(session),
+    }
+}
 
 #[function_component(Sessions)]
 pub fn sessions() -> Html {
-    let sessions = use_state(Vec::<WorkSessionWithTags>::new);
+    let sessions = use_state(Page::<WorkSessionWithTags>::default);
+    let query = use_state(SessionQuery::default);
+    // Bumped to reload everything after a `Resync`
+    let reload = use_state(|| 0u32);
     let loading = use_state(|| false);
     let error = use_state(|| None::<String>);
 
+    // Filter states
+    let search = use_state(String::new);
+
     // Form states
     let description = use_state(String::new);
+    let field_errors = use_state(ValidationErrors::default);
     let selected_tags = use_state(Vec::<Uuid>::new);
     let available_tags = use_state(Vec::<Tag>::new);
+    let selected_client = use_state(|| None::<Uuid>);
+    let selected_project = use_state(|| None::<Uuid>);
+    let billable = use_state(|| false);
+    let clients = use_state(Vec::<Client>::new);
+    let projects = use_state(Vec::<Project>::new);
+
+    // Ticks every second so running timers stay live
+    let now = use_state(Utc::now);
+    {
+        let now = now.clone();
+        use_effect_with((), move |_| {
+            let interval = Interval::new(1_000, move || now.set(Utc::now()));
+            move || drop(interval)
+        });
+    }
 
-    // Load sessions and tags on component mount
+    // Load tags, clients and projects on component mount
+    {
+        let error = error.clone();
+        let available_tags = available_tags.clone();
+        let clients = clients.clone();
+        let projects = projects.clone();
+
+        use_effect_with(*reload, move |_| {
+            let error = error.clone();
+            let available_tags = available_tags.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                match api::client().get_tags().await {
+                    Ok(data) => available_tags.set(data),
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+                match api::client().get_clients().await {
+                    Ok(data) => clients.set(data),
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+                match api::client().get_projects().await {
+                    Ok(data) => projects.set(data),
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+            });
+
+            || {}
+        });
+    }
+
+    // Load the first page of sessions whenever the filters change
     {
         let sessions = sessions.clone();
         let loading = loading.clone();
         let error = error.clone();
-        let available_tags = available_tags.clone();
 
-        use_effect_with((), move |_| {
+        use_effect_with(((*query).clone(), *reload), move |(query, _)| {
+            let query = query.clone();
             let sessions = sessions.clone();
             let loading = loading.clone();
             let error = error.clone();
-            let available_tags = available_tags.clone();
 
             wasm_bindgen_futures::spawn_local(async move {
                 loading.set(true);
                 error.set(None);
 
-                // Load sessions
-                match api::get_sessions().await {
+                match api::client().get_sessions(&query).await {
                     Ok(data) => sessions.set(data),
-                    Err(e) => error.set(Some(e)),
-                }
-
-                // Load tags
-                match api::get_tags().await {
-                    Ok(data) => available_tags.set(data),
-                    Err(e) => error.set(Some(e)),
+                    Err(e) => error.set(Some(e.to_string())),
                 }
 
                 loading.set(false);
@@ -50,52 +122,186 @@ pub fn sessions() -> Html {
         });
     }
 
-    let on_create_session = {
+    // Apply session and tag changes as they happen
+    {
+        let sessions = sessions.clone();
+        let available_tags = available_tags.clone();
+        let query = query.clone();
+        let reload = reload.clone();
+
+        use_live_events(move |events| {
+            let mut page = (*sessions).clone();
+            let mut tags = (*available_tags).clone();
+            let mut resync = false;
+
+            for event in events {
+                match event {
+                    LiveEvent::SessionCreated(session) | LiveEvent::SessionUpdated(session) => {
+                        upsert_session(&mut page, &query, session);
+                    }
+                    LiveEvent::SessionDeleted { id } => page.items.retain(|s| s.id != id),
+                    LiveEvent::TagCreated(tag) => {
+                        tags.push(tag);
+                        tags.sort_by(|a, b| a.name.cmp(&b.name));
+                    }
+                    LiveEvent::TagUpdated(tag) => {
+                        // A new rate changes what tagged sessions earn
+                        let rate_changed = tags.iter().any(|t| t.id == tag.id && t.hourly_rate != tag.hourly_rate);
+                        for session in &mut page.items {
+                            if let Some(t) = session.tags.iter_mut().find(|t| t.id == tag.id) {
+                                *t = tag.clone();
+                                resync |= rate_changed;
+                            }
+                        }
+                        tags.retain(|t| t.id != tag.id);
+                        tags.push(tag);
+                        tags.sort_by(|a, b| a.name.cmp(&b.name));
+                    }
+                    LiveEvent::TagDeleted { id } => {
+                        tags.retain(|t| t.id != id);
+                        for session in &mut page.items {
+                            session.tags.retain(|t| t.id != id);
+                        }
+                    }
+                    LiveEvent::Resync => resync = true,
+                }
+            }
+
+            if resync {
+                reload.set(*reload + 1);
+            } else {
+                sessions.set(page);
+                available_tags.set(tags);
+            }
+        });
+    }
+
+    let on_start_session = {
         let description = description.clone();
+        let field_errors = field_errors.clone();
         let selected_tags = selected_tags.clone();
+        let selected_project = selected_project.clone();
+        let billable = billable.clone();
         let sessions = sessions.clone();
+        let query = query.clone();
         let loading = loading.clone();
         let error = error.clone();
 
         Callback::from(move |e: SubmitEvent| {
             e.prevent_default();
             let description = description.clone();
+            let field_errors = field_errors.clone();
             let selected_tags = selected_tags.clone();
+            let project_id = *selected_project;
+            let billable = *billable;
             let sessions = sessions.clone();
+            let query = query.clone();
             let loading = loading.clone();
             let error = error.clone();
 
             wasm_bindgen_futures::spawn_local(async move {
                 if description.is_empty() {
-                    error.set(Some("Description is required".to_string()));
+                    field_errors.set(ValidationErrors::single("description", "is required"));
                     return;
                 }
 
-                loading.set(true);
-                error.set(None);
-
-                let req = CreateSessionRequest {
-                    duration_seconds: 0, // Will be set by backend based on actual work time
+                let req = StartSessionRequest {
                     description: Some((*description).clone()),
                     tag_ids: (*selected_tags).clone(),
+                    project_id,
+                    billable,
+                    hourly_rate: None,
                 };
+                if let Err(errors) = req.validate() {
+                    field_errors.set(errors);
+                    return;
+                }
+
+                loading.set(true);
+                error.set(None);
+                field_errors.set(ValidationErrors::default());
 
-                match api::create_session(req).await {
+                match api::client().start_session(&req).await {
                     Ok(_) => {
-                        // Session created successfully, now refresh the list
-                        match api::get_sessions().await {
+                        // Session started successfully, now refresh the list
+                        match api::client().get_sessions(&query).await {
                             Ok(data) => {
                                 sessions.set(data);
-                                // Clear the form only after successful creation and refresh
+                                // Clear the form only after successful start and refresh
                                 description.set(String::new());
                                 selected_tags.set(Vec::new());
                             }
-                            Err(e) => error.set(Some(format!("Session created but failed to refresh list: {}", e))),
+                            Err(e) => error.set(Some(format!("Session started but failed to refresh list: {e}"))),
                         }
                     }
-                    Err(e) => {
-                        error.set(Some(format!("Failed to create session: {}", e)));
-                    }
+                    Err(e) => match e.field_errors() {
+                        Some(errors) => field_errors.set(errors.clone()),
+                        None => error.set(Some(format!("Failed to start session: {e}"))),
+                    },
+                }
+
+                loading.set(false);
+            });
+        })
+    };
+
+    let on_stop_session = {
+        let sessions = sessions.clone();
+        let query = query.clone();
+        let loading = loading.clone();
+        let error = error.clone();
+
+        Callback::from(move |id: Uuid| {
+            let sessions = sessions.clone();
+            let query = query.clone();
+            let loading = loading.clone();
+            let error = error.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                loading.set(true);
+                error.set(None);
+
+                match api::client().stop_session(id).await {
+                    Ok(_) => match api::client().get_sessions(&query).await {
+                        Ok(data) => sessions.set(data),
+                        Err(e) => error.set(Some(e.to_string())),
+                    },
+                    Err(e) => error.set(Some(format!("Failed to stop session: {e}"))),
+                }
+
+                loading.set(false);
+            });
+        })
+    };
+
+    let on_toggle_pause = {
+        let sessions = sessions.clone();
+        let query = query.clone();
+        let loading = loading.clone();
+        let error = error.clone();
+
+        Callback::from(move |(id, paused): (Uuid, bool)| {
+            let sessions = sessions.clone();
+            let query = query.clone();
+            let loading = loading.clone();
+            let error = error.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                loading.set(true);
+                error.set(None);
+
+                let result = if paused {
+                    api::client().resume_session(id).await
+                } else {
+                    api::client().pause_session(id).await
+                };
+
+                match result {
+                    Ok(_) => match api::client().get_sessions(&query).await {
+                        Ok(data) => sessions.set(data),
+                        Err(e) => error.set(Some(e.to_string())),
+                    },
+                    Err(e) => error.set(Some(format!("Failed to update session: {e}"))),
                 }
 
                 loading.set(false);
@@ -105,11 +311,13 @@ pub fn sessions() -> Html {
 
     let on_delete_session = {
         let sessions = sessions.clone();
+        let query = query.clone();
         let loading = loading.clone();
         let error = error.clone();
 
         Callback::from(move |id: Uuid| {
             let sessions = sessions.clone();
+            let query = query.clone();
             let loading = loading.clone();
             let error = error.clone();
 
@@ -117,11 +325,46 @@ pub fn sessions() -> Html {
                 loading.set(true);
                 error.set(None);
 
-                if (api::delete_session(id).await).is_ok() {
-                    match api::get_sessions().await {
+                match api::client().delete_session(id).await {
+                    Ok(()) => match api::client().get_sessions(&query).await {
                         Ok(data) => sessions.set(data),
-                        Err(e) => error.set(Some(e)),
+                        Err(e) => error.set(Some(e.to_string())),
+                    },
+                    Err(e) => error.set(Some(format!("Failed to delete session: {e}"))),
+                }
+
+                loading.set(false);
+            });
+        })
+    };
+
+    let on_load_more = {
+        let sessions = sessions.clone();
+        let query = query.clone();
+        let loading = loading.clone();
+        let error = error.clone();
+
+        Callback::from(move |_| {
+            let sessions = sessions.clone();
+            let loading = loading.clone();
+            let error = error.clone();
+            let query = SessionQuery {
+                cursor: sessions.next_cursor.clone(),
+                ..(*query).clone()
+            };
+
+            wasm_bindgen_futures::spawn_local(async move {
+                loading.set(true);
+                error.set(None);
+
+                match api::client().get_sessions(&query).await {
+                    Ok(next) => {
+                        let mut page = (*sessions).clone();
+                        page.items.extend(next.items);
+                        page.next_cursor = next.next_cursor;
+                        sessions.set(page);
                     }
+                    Err(e) => error.set(Some(e.to_string())),
                 }
 
                 loading.set(false);
@@ -129,6 +372,20 @@ pub fn sessions() -> Html {
         })
     };
 
+    let on_search = {
+        let search = search.clone();
+        let query = query.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let search = search.trim();
+            query.set(SessionQuery {
+                search: (!search.is_empty()).then(|| search.to_string()),
+                ..SessionQuery::default()
+            });
+        })
+    };
+
     fn format_duration(duration_seconds: i32) -> String {
         let hours = duration_seconds / 3600;
         let minutes = (duration_seconds % 3600) / 60;
@@ -143,6 +400,16 @@ pub fn sessions() -> Html {
         }
     }
 
+    let project_label = |project_id: Uuid| {
+        let project = projects.iter().find(|p| p.id == project_id)?;
+        let client = clients.iter().find(|c| c.id == project.client_id)?;
+        Some(format!("{} / {}", client.name, project.name))
+    };
+
+    let select_class = "shadow border rounded w-full py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline";
+
+    let running_session = sessions.items.iter().find(|s| s.is_running()).cloned();
+
     html! {
         <div class="container mx-auto p-4">
             <h1 class="text-3xl font-bold mb-6">{"Work Sessions"}</h1>
@@ -153,90 +420,235 @@ pub fn sessions() -> Html {
                 </div>
             }
 
-            // Create new session form
-            <div class="bg-white shadow-md rounded px-8 pt-6 pb-8 mb-6">
-                <h2 class="text-xl font-semibold mb-4">{"Create New Session"}</h2>
-                <form onsubmit={on_create_session}>
-                    <div class="mb-4">
-                        <label class="block text-gray-700 text-sm font-bold mb-2" for="description">
-                            {"Description"}
-                        </label>
-                        <input
-                            id="description"
-                            type="text"
-                            value={(*description).clone()}
-                            oninput={
-                                let description = description.clone();
-                                Callback::from(move |e: InputEvent| {
-                                    if let Some(input) = e.target_dyn_into::<web_sys::HtmlInputElement>() {
-                                        description.set(input.value());
-                                    }
-                                })
+            if let Some(running) = running_session {
+                // Running timer
+                <div class="bg-white shadow-md rounded px-8 pt-6 pb-8 mb-6 flex justify-between items-center">
+                    <div>
+                        <h2 class="text-xl font-semibold mb-2">
+                            {running.description.as_ref().unwrap_or(&"No description".to_string())}
+                        </h2>
+                        <p class="text-3xl font-mono text-blue-600">
+                            {format_duration(running.elapsed_seconds(*now))}
+                        </p>
+                        if running.is_paused() {
+                            <p class="text-sm text-gray-500">{"Paused"}</p>
+                        }
+                    </div>
+                    <div class="flex space-x-2">
+                        <button
+                            disabled={*loading}
+                            onclick={
+                                let on_toggle = on_toggle_pause.clone();
+                                let running_id = running.id;
+                                let paused = running.is_paused();
+                                Callback::from(move |_| on_toggle.emit((running_id, paused)))
                             }
-                            class="shadow appearance-none border rounded w-full py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline"
-                            placeholder="Enter session description"
-                        />
+                            class="bg-yellow-500 hover:bg-yellow-700 text-white font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline disabled:opacity-50"
+                        >
+                            {if running.is_paused() { "Resume" } else { "Pause" }}
+                        </button>
+                        <button
+                            disabled={*loading}
+                            onclick={
+                                let on_stop = on_stop_session.clone();
+                                let running_id = running.id;
+                                Callback::from(move |_| on_stop.emit(running_id))
+                            }
+                            class="bg-red-500 hover:bg-red-700 text-white font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline disabled:opacity-50"
+                        >
+                            {if *loading { "Stopping..." } else { "Stop" }}
+                        </button>
                     </div>
+                </div>
+            } else {
+                // Start new session form
+                <div class="bg-white shadow-md rounded px-8 pt-6 pb-8 mb-6">
+                    <h2 class="text-xl font-semibold mb-4">{"Start New Session"}</h2>
+                    <form onsubmit={on_start_session}>
+                        <div class="mb-4">
+                            <label class="block text-gray-700 text-sm font-bold mb-2" for="description">
+                                {"Description"}
+                            </label>
+                            <input
+                                id="description"
+                                type="text"
+                                value={(*description).clone()}
+                                oninput={
+                                    let description = description.clone();
+                                    Callback::from(move |e: InputEvent| {
+                                        if let Some(input) = e.target_dyn_into::<web_sys::HtmlInputElement>() {
+                                            description.set(input.value());
+                                        }
+                                    })
+                                }
+                                class="shadow appearance-none border rounded w-full py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline"
+                                placeholder="Enter session description"
+                            />
+                            <FieldError errors={(*field_errors).clone()} field="description" />
+                        </div>
                     
-                    <div class="mb-4">
-                        <label class="block text-gray-700 text-sm font-bold mb-2">
-                            {"Tags"}
-                        </label>
-                        <div class="flex flex-wrap gap-2">
-                            {for available_tags.iter().map(|tag| {
-                                let tag_id = tag.id;
-                                let is_selected = selected_tags.contains(&tag_id);
-                                let selected_tags = selected_tags.clone();
+                        <div class="mb-4">
+                            <label class="block text-gray-700 text-sm font-bold mb-2">
+                                {"Tags"}
+                            </label>
+                            <div class="flex flex-wrap gap-2">
+                                {for available_tags.iter().map(|tag| {
+                                    let tag_id = tag.id;
+                                    let is_selected = selected_tags.contains(&tag_id);
+                                    let selected_tags = selected_tags.clone();
                                 
-                                html! {
-                                    <button
-                                        type="button"
-                                        class={classes!(
-                                            "px-3", "py-1", "rounded", "text-sm", "border",
-                                            if is_selected { "bg-blue-500 text-white border-blue-500" } else { "bg-gray-200 text-gray-700 border-gray-300" }
-                                        )}
-                                        onclick={
-                                            Callback::from(move |_| {
-                                                let mut tags = (*selected_tags).clone();
-                                                if let Some(pos) = tags.iter().position(|&x| x == tag_id) {
-                                                    tags.remove(pos);
-                                                } else {
-                                                    tags.push(tag_id);
+                                    html! {
+                                        <button
+                                            type="button"
+                                            class={classes!(
+                                                "px-3", "py-1", "rounded", "text-sm", "border",
+                                                if is_selected { "bg-blue-500 text-white border-blue-500" } else { "bg-gray-200 text-gray-700 border-gray-300" }
+                                            )}
+                                            onclick={
+                                                Callback::from(move |_| {
+                                                    let mut tags = (*selected_tags).clone();
+                                                    if let Some(pos) = tags.iter().position(|&x| x == tag_id) {
+                                                        tags.remove(pos);
+                                                    } else {
+                                                        tags.push(tag_id);
+                                                    }
+                                                    selected_tags.set(tags);
+                                                })
+                                            }
+                                        >
+                                            {&tag.name}
+                                        </button>
+                                    }
+                                })}
+                            </div>
+                            <FieldError errors={(*field_errors).clone()} field="tag_ids" />
+                        </div>
+
+                        if !clients.is_empty() {
+                            <div class="mb-4 flex gap-4">
+                                <div class="flex-1">
+                                    <label class="block text-gray-700 text-sm font-bold mb-2" for="client">
+                                        {"Client"}
+                                    </label>
+                                    <select
+                                        id="client"
+                                        onchange={
+                                            let selected_client = selected_client.clone();
+                                            let selected_project = selected_project.clone();
+                                            Callback::from(move |e: Event| {
+                                                if let Some(select) = e.target_dyn_into::<web_sys::HtmlSelectElement>() {
+                                                    selected_client.set(Uuid::parse_str(&select.value()).ok());
+                                                    selected_project.set(None);
                                                 }
-                                                selected_tags.set(tags);
                                             })
                                         }
+                                        class={select_class}
                                     >
-                                        {&tag.name}
-                                    </button>
-                                }
-                            })}
-                        </div>
-                    </div>
+                                        <option value="" selected={selected_client.is_none()}>{"No client"}</option>
+                                        {for clients.iter().map(|client| html! {
+                                            <option value={client.id.to_string()} selected={*selected_client == Some(client.id)}>
+                                                {&client.name}
+                                            </option>
+                                        })}
+                                    </select>
+                                </div>
+                                <div class="flex-1">
+                                    <label class="block text-gray-700 text-sm font-bold mb-2" for="project">
+                                        {"Project"}
+                                    </label>
+                                    <select
+                                        id="project"
+                                        disabled={selected_client.is_none()}
+                                        onchange={
+                                            let selected_project = selected_project.clone();
+                                            Callback::from(move |e: Event| {
+                                                if let Some(select) = e.target_dyn_into::<web_sys::HtmlSelectElement>() {
+                                                    selected_project.set(Uuid::parse_str(&select.value()).ok());
+                                                }
+                                            })
+                                        }
+                                        class={classes!(select_class, "disabled:bg-gray-100")}
+                                    >
+                                        <option value="" selected={selected_project.is_none()}>{"No project"}</option>
+                                        {for projects.iter().filter(|p| Some(p.client_id) == *selected_client).map(|project| html! {
+                                            <option value={project.id.to_string()} selected={*selected_project == Some(project.id)}>
+                                                {&project.name}
+                                            </option>
+                                        })}
+                                    </select>
+                                    <FieldError errors={(*field_errors).clone()} field="project_id" />
+                                </div>
+                            </div>
+                        }
                     
-                    <button
-                        type="submit"
-                        disabled={*loading}
-                        class="bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline disabled:opacity-50"
-                    >
-                        {if *loading { "Creating..." } else { "Create Session" }}
-                    </button>
-                </form>
-            </div>
+                        <div class="mb-4">
+                            <label class="inline-flex items-center text-gray-700 text-sm">
+                                <input
+                                    type="checkbox"
+                                    checked={*billable}
+                                    onchange={
+                                        let billable = billable.clone();
+                                        Callback::from(move |_| billable.set(!*billable))
+                                    }
+                                    class="mr-2"
+                                />
+                                {"Billable"}
+                            </label>
+                        </div>
+
+                        <button
+                            type="submit"
+                            disabled={*loading}
+                            class="bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline disabled:opacity-50"
+                        >
+                            {if *loading { "Starting..." } else { "Start Session" }}
+                        </button>
+                    </form>
+                </div>
+            }
 
             // Sessions list
             <div class="bg-white shadow-md rounded">
-                <div class="px-6 py-4 border-b">
+                <div class="px-6 py-4 border-b flex justify-between items-center">
                     <h2 class="text-xl font-semibold">{"Sessions"}</h2>
+                    <form onsubmit={on_search} class="flex space-x-2">
+                        <input
+                            type="search"
+                            value={(*search).clone()}
+                            oninput={
+                                let search = search.clone();
+                                Callback::from(move |e: InputEvent| {
+                                    if let Some(input) = e.target_dyn_into::<web_sys::HtmlInputElement>() {
+                                        search.set(input.value());
+                                    }
+                                })
+                            }
+                            class="shadow appearance-none border rounded py-1 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline"
+                            placeholder="Search descriptions"
+                        />
+                        <button
+                            type="submit"
+                            class="bg-gray-500 hover:bg-gray-700 text-white font-bold py-1 px-3 rounded focus:outline-none focus:shadow-outline"
+                        >
+                            {"Search"}
+                        </button>
+                        <a
+                            href={api::client().export_sessions_url(&query)}
+                            download="sessions.csv"
+                            class="bg-green-600 hover:bg-green-700 text-white font-bold py-1 px-3 rounded focus:outline-none focus:shadow-outline"
+                        >
+                            {"Export"}
+                        </a>
+                    </form>
                 </div>
                 
-                if sessions.is_empty() && !*loading {
+                if sessions.items.is_empty() && !*loading {
                     <div class="px-6 py-4 text-gray-500 text-center">
-                        {"No sessions found. Create your first session above!"}
+                        {if query.search.is_some() { "No sessions match your search." } else { "No sessions found. Start your first session above!" }}
                     </div>
                 } else {
                     <div class="divide-y divide-gray-200">
-                        {for sessions.iter().map(|session| {
+                        {for sessions.items.iter().map(|session| {
                             let session_id = session.id;
                             let on_delete = on_delete_session.clone();
                             
@@ -248,8 +660,23 @@ pub fn sessions() -> Html {
                                                 {session.description.as_ref().unwrap_or(&"No description".to_string())}
                                             </h3>
                                             <div class="text-sm text-gray-600 space-y-1">
-                                                <p>{"Duration: "}{format_duration(session.duration_seconds)}</p>
-                                                <p>{"Created: "}{session.created_at.format("%Y-%m-%d %H:%M:%S").to_string()}</p>
+                                                if session.is_running() {
+                                                    <p class="text-blue-600 font-semibold">{"Running: "}{format_duration(session.elapsed_seconds(*now))}</p>
+                                                } else {
+                                                    <p>{"Duration: "}{format_duration(session.duration_seconds)}</p>
+                                                }
+                                                if let Some(label) = session.project_id.and_then(project_label) {
+                                                    <p>{"Project: "}{label}</p>
+                                                }
+                                                if let Some(amount) = session.amount {
+                                                    <p>{"Amount: "}{format!("{amount} {}", session.currency)}</p>
+                                                } else if session.billable {
+                                                    <p>{"Billable, no rate set"}</p>
+                                                }
+                                                <p>{"Started: "}{session.started_at.format("%Y-%m-%d %H:%M:%S").to_string()}</p>
+                                                if let Some(ended_at) = session.ended_at {
+                                                    <p>{"Ended: "}{ended_at.format("%Y-%m-%d %H:%M:%S").to_string()}</p>
+                                                }
                                                 <p>{"Updated: "}{session.updated_at.format("%Y-%m-%d %H:%M:%S").to_string()}</p>
                                             </div>
                                             if !session.tags.is_empty() {
@@ -288,6 +715,17 @@ pub fn sessions() -> Html {
                             }
                         })}
                     </div>
+                    if sessions.next_cursor.is_some() {
+                        <div class="px-6 py-4 border-t text-center">
+                            <button
+                                disabled={*loading}
+                                onclick={on_load_more}
+                                class="text-blue-600 hover:text-blue-800 disabled:opacity-50"
+                            >
+                                {if *loading { "Loading..." } else { "Load more" }}
+                            </button>
+                        </div>
+                    }
                 }
             </div>
         </div>
@@ -1,11 +1,16 @@
 use yew::prelude::*;
 use uuid::Uuid;
-use shared::{CreateTagRequest, UpdateTagRequest, Tag};
+use shared::{CreateTagRequest, FieldUpdate, LiveEvent, UpdateTagRequest, Tag, Validate, ValidationErrors};
 use crate::api;
+use crate::live::use_live_events;
+use crate::components::FieldError;
+use super::projects::parse_hourly_rate;
 
 #[function_component(Tags)]
 pub fn tags() -> Html {
     let tags = use_state(Vec::<Tag>::new);
+    // Bumped to reload the tags after a `Resync`
+    let reload = use_state(|| 0u32);
     let loading = use_state(|| false);
     let error = use_state(|| None::<String>);
     let editing_tag = use_state(|| None::<Uuid>);
@@ -13,6 +18,10 @@ pub fn tags() -> Html {
     // Form states
     let tag_name = use_state(String::new);
     let tag_color = use_state(String::new);
+    let tag_rate = use_state(String::new);
+    let field_errors = use_state(ValidationErrors::default);
+    // Existing tag whose name the form clashed with
+    let conflicting_tag = use_state(|| None::<Uuid>);
 
     // Load tags on component mount
     {
@@ -20,7 +29,7 @@ pub fn tags() -> Html {
         let loading = loading.clone();
         let error = error.clone();
 
-        use_effect_with((), move |_| {
+        use_effect_with(*reload, move |_| {
             let tags = tags.clone();
             let loading = loading.clone();
             let error = error.clone();
@@ -29,9 +38,9 @@ pub fn tags() -> Html {
                 loading.set(true);
                 error.set(None);
 
-                match api::get_tags().await {
+                match api::client().get_tags().await {
                     Ok(data) => tags.set(data),
-                    Err(e) => error.set(Some(e)),
+                    Err(e) => error.set(Some(e.to_string())),
                 }
 
                 loading.set(false);
@@ -41,9 +50,35 @@ pub fn tags() -> Html {
         });
     }
 
+    // Apply tag changes as they happen
+    {
+        let tags = tags.clone();
+        let reload = reload.clone();
+
+        use_live_events(move |events| {
+            let mut list = (*tags).clone();
+            for event in events {
+                match event {
+                    LiveEvent::TagCreated(tag) | LiveEvent::TagUpdated(tag) => {
+                        list.retain(|t| t.id != tag.id);
+                        list.push(tag);
+                    }
+                    LiveEvent::TagDeleted { id } => list.retain(|t| t.id != id),
+                    LiveEvent::Resync => return reload.set(*reload + 1),
+                    _ => {}
+                }
+            }
+            list.sort_by(|a, b| a.name.cmp(&b.name));
+            tags.set(list);
+        });
+    }
+
     let on_create_tag = {
         let tag_name = tag_name.clone();
         let tag_color = tag_color.clone();
+        let tag_rate = tag_rate.clone();
+        let field_errors = field_errors.clone();
+        let conflicting_tag = conflicting_tag.clone();
         let tags = tags.clone();
         let loading = loading.clone();
         let error = error.clone();
@@ -52,19 +87,21 @@ pub fn tags() -> Html {
             e.prevent_default();
             let tag_name = tag_name.clone();
             let tag_color = tag_color.clone();
+        let tag_rate = tag_rate.clone();
+            let field_errors = field_errors.clone();
+            let conflicting_tag = conflicting_tag.clone();
             let tags = tags.clone();
             let loading = loading.clone();
             let error = error.clone();
 
             wasm_bindgen_futures::spawn_local(async move {
-                if tag_name.is_empty() {
-                    error.set(Some("Tag name is required".to_string()));
-                    return;
-                }
-
-                loading.set(true);
-                error.set(None);
-
+                let hourly_rate = match parse_hourly_rate(&tag_rate) {
+                    Ok(rate) => rate,
+                    Err(errors) => {
+                        field_errors.set(errors);
+                        return;
+                    }
+                };
                 let req = CreateTagRequest {
                     name: (*tag_name).clone(),
                     color: if tag_color.is_empty() { 
@@ -72,17 +109,36 @@ pub fn tags() -> Html {
                     } else { 
                         Some((*tag_color).clone()) 
                     },
+                    hourly_rate,
                 };
+                if let Err(errors) = req.validate() {
+                    field_errors.set(errors);
+                    return;
+                }
 
-                if (api::create_tag(req).await).is_ok() {
-                    match api::get_tags().await {
+                loading.set(true);
+                error.set(None);
+                field_errors.set(ValidationErrors::default());
+                conflicting_tag.set(None);
+
+                match api::client().create_tag(&req).await {
+                    Ok(_) => match api::client().get_tags().await {
                         Ok(data) => {
                             tags.set(data);
                             tag_name.set(String::new());
                             tag_color.set(String::new());
+                            tag_rate.set(String::new());
                         }
-                        Err(e) => error.set(Some(e)),
+                        Err(e) => error.set(Some(e.to_string())),
+                    },
+                    Err(api::ApiError::Conflict { message, existing_id }) => {
+                        field_errors.set(ValidationErrors::single("name", message));
+                        conflicting_tag.set(existing_id);
                     }
+                    Err(e) => match e.field_errors() {
+                        Some(errors) => field_errors.set(errors.clone()),
+                        None => error.set(Some(e.to_string())),
+                    },
                 }
 
                 loading.set(false);
@@ -94,6 +150,9 @@ pub fn tags() -> Html {
         let editing_tag = editing_tag.clone();
         let tag_name = tag_name.clone();
         let tag_color = tag_color.clone();
+        let tag_rate = tag_rate.clone();
+        let field_errors = field_errors.clone();
+        let conflicting_tag = conflicting_tag.clone();
         let tags = tags.clone();
         let loading = loading.clone();
         let error = error.clone();
@@ -103,39 +162,61 @@ pub fn tags() -> Html {
             if let Some(tag_id) = *editing_tag {
                 let tag_name = tag_name.clone();
                 let tag_color = tag_color.clone();
+        let tag_rate = tag_rate.clone();
+                let field_errors = field_errors.clone();
+                let conflicting_tag = conflicting_tag.clone();
                 let tags = tags.clone();
                 let loading = loading.clone();
                 let error = error.clone();
                 let editing_tag = editing_tag.clone();
 
                 wasm_bindgen_futures::spawn_local(async move {
-                    if tag_name.is_empty() {
-                        error.set(Some("Tag name is required".to_string()));
-                        return;
-                    }
-
-                    loading.set(true);
-                    error.set(None);
-
+                    let hourly_rate = match parse_hourly_rate(&tag_rate) {
+                        Ok(rate) => rate,
+                        Err(errors) => {
+                            field_errors.set(errors);
+                            return;
+                        }
+                    };
                     let req = UpdateTagRequest {
-                        name: Some((*tag_name).clone()),
+                        name: FieldUpdate::Set((*tag_name).clone()),
                         color: if tag_color.is_empty() { 
-                            Some("#3B82F6".to_string()) 
+                            FieldUpdate::Set("#3B82F6".to_string()) 
                         } else { 
-                            Some((*tag_color).clone()) 
+                            FieldUpdate::Set((*tag_color).clone()) 
                         },
+                        hourly_rate: hourly_rate.into(),
                     };
+                    if let Err(errors) = req.validate() {
+                        field_errors.set(errors);
+                        return;
+                    }
 
-                    if (api::update_tag(tag_id, req).await).is_ok() {
-                        match api::get_tags().await {
+                    loading.set(true);
+                    error.set(None);
+                    field_errors.set(ValidationErrors::default());
+                    conflicting_tag.set(None);
+
+                    match api::client().update_tag(tag_id, &req).await {
+                        Ok(_) => match api::client().get_tags().await {
                             Ok(data) => {
                                 tags.set(data);
                                 tag_name.set(String::new());
                                 tag_color.set(String::new());
+                            tag_rate.set(String::new());
+                                tag_rate.set(String::new());
                                 editing_tag.set(None);
                             }
-                            Err(e) => error.set(Some(e)),
+                            Err(e) => error.set(Some(e.to_string())),
+                        },
+                        Err(api::ApiError::Conflict { message, existing_id }) => {
+                            field_errors.set(ValidationErrors::single("name", message));
+                            conflicting_tag.set(existing_id);
                         }
+                        Err(e) => match e.field_errors() {
+                            Some(errors) => field_errors.set(errors.clone()),
+                            None => error.set(Some(e.to_string())),
+                        },
                     }
 
                     loading.set(false);
@@ -158,10 +239,10 @@ pub fn tags() -> Html {
                 loading.set(true);
                 error.set(None);
 
-                if (api::delete_tag(id).await).is_ok() {
-                    match api::get_tags().await {
+                if (api::client().delete_tag(id).await).is_ok() {
+                    match api::client().get_tags().await {
                         Ok(data) => tags.set(data),
-                        Err(e) => error.set(Some(e)),
+                        Err(e) => error.set(Some(e.to_string())),
                     }
                 }
 
@@ -174,13 +255,19 @@ pub fn tags() -> Html {
         let editing_tag = editing_tag.clone();
         let tag_name = tag_name.clone();
         let tag_color = tag_color.clone();
+        let tag_rate = tag_rate.clone();
+        let field_errors = field_errors.clone();
+        let conflicting_tag = conflicting_tag.clone();
         let tags = tags.clone();
 
         Callback::from(move |id: Uuid| {
             if let Some(tag) = tags.iter().find(|t| t.id == id) {
                 editing_tag.set(Some(id));
+                field_errors.set(ValidationErrors::default());
+                conflicting_tag.set(None);
                 tag_name.set(tag.name.clone());
                 tag_color.set(tag.color.as_ref().unwrap_or(&"#3B82F6".to_string()).clone());
+                tag_rate.set(tag.hourly_rate.map(|r| r.to_string()).unwrap_or_default());
             }
         })
     };
@@ -189,11 +276,17 @@ pub fn tags() -> Html {
         let editing_tag = editing_tag.clone();
         let tag_name = tag_name.clone();
         let tag_color = tag_color.clone();
+        let tag_rate = tag_rate.clone();
+        let field_errors = field_errors.clone();
+        let conflicting_tag = conflicting_tag.clone();
 
         Callback::from(move |_| {
             editing_tag.set(None);
+            field_errors.set(ValidationErrors::default());
+            conflicting_tag.set(None);
             tag_name.set(String::new());
             tag_color.set(String::new());
+            tag_rate.set(String::new());
         })
     };
 
@@ -213,7 +306,7 @@ pub fn tags() -> Html {
                     {if editing_tag.is_some() { "Edit Tag" } else { "Create New Tag" }}
                 </h2>
                 <form onsubmit={if editing_tag.is_some() { on_update_tag } else { on_create_tag }}>
-                    <div class="grid grid-cols-1 md:grid-cols-2 gap-4 mb-4">
+                    <div class="grid grid-cols-1 md:grid-cols-3 gap-4 mb-4">
                         <div>
                             <label class="block text-gray-700 text-sm font-bold mb-2" for="tag-name">
                                 {"Tag Name"}
@@ -233,6 +326,19 @@ pub fn tags() -> Html {
                                 class="shadow appearance-none border rounded w-full py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline"
                                 placeholder="Enter tag name"
                             />
+                            <FieldError errors={(*field_errors).clone()} field="name" />
+                            if let Some(existing_id) = *conflicting_tag {
+                                <button
+                                    type="button"
+                                    class="text-blue-600 hover:text-blue-800 text-xs mt-1"
+                                    onclick={
+                                        let on_edit_tag = on_edit_tag.clone();
+                                        Callback::from(move |_| on_edit_tag.emit(existing_id))
+                                    }
+                                >
+                                    {"Edit the existing tag instead"}
+                                </button>
+                            }
                         </div>
                         <div>
                             <label class="block text-gray-700 text-sm font-bold mb-2" for="tag-color">
@@ -252,6 +358,28 @@ pub fn tags() -> Html {
                                 }
                                 class="shadow appearance-none border rounded w-full h-10 py-1 px-2 text-gray-700 leading-tight focus:outline-none focus:shadow-outline"
                             />
+                            <FieldError errors={(*field_errors).clone()} field="color" />
+                        </div>
+                        <div>
+                            <label class="block text-gray-700 text-sm font-bold mb-2" for="tag-rate">
+                                {"Hourly Rate"}
+                            </label>
+                            <input
+                                id="tag-rate"
+                                type="text"
+                                value={(*tag_rate).clone()}
+                                oninput={
+                                    let tag_rate = tag_rate.clone();
+                                    Callback::from(move |e: InputEvent| {
+                                        if let Some(input) = e.target_dyn_into::<web_sys::HtmlInputElement>() {
+                                            tag_rate.set(input.value());
+                                        }
+                                    })
+                                }
+                                class="shadow appearance-none border rounded w-full py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline"
+                                placeholder="Optional, e.g. 85.00"
+                            />
+                            <FieldError errors={(*field_errors).clone()} field="hourly_rate" />
                         </div>
                     </div>
                     
@@ -295,11 +423,15 @@ pub fn tags() -> Html {
                             let tag_id = tag.id;
                             let on_delete = on_delete_tag.clone();
                             let on_edit = on_edit_tag.clone();
+                            let is_conflicting = *conflicting_tag == Some(tag_id);
                             let default_color = "#6B7280".to_string();
                             let color = tag.color.as_ref().unwrap_or(&default_color);
                             
                             html! {
-                                <div class="border rounded-lg p-4 hover:shadow-md transition-shadow">
+                                <div class={classes!(
+                                    "border", "rounded-lg", "p-4", "hover:shadow-md", "transition-shadow",
+                                    is_conflicting.then_some("ring-2 ring-red-400")
+                                )}>
                                     <div class="flex items-center justify-between mb-2">
                                         <div class="flex items-center space-x-2">
                                             <div 
@@ -336,6 +468,9 @@ pub fn tags() -> Html {
                                     <div class="text-sm text-gray-600">
                                         {"Color: "}{tag.color.as_ref().unwrap_or(&"Default".to_string())}
                                     </div>
+                                    if let Some(rate) = tag.hourly_rate {
+                                        <div class="text-sm text-gray-600">{format!("Rate: {rate}/h")}</div>
+                                    }
                                 </div>
                             }
                         })}
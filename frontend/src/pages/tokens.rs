@@ -0,0 +1,239 @@
+use yew::prelude::*;
+use uuid::Uuid;
+use shared::{ApiToken, CreateApiTokenRequest, Validate, ValidationErrors};
+use crate::api;
+use crate::components::FieldError;
+
+/// Personal access tokens for scripts and the CLI.
+#[function_component(Tokens)]
+pub fn tokens() -> Html {
+    let tokens = use_state(Vec::<ApiToken>::new);
+    let loading = use_state(|| false);
+    let error = use_state(|| None::<String>);
+
+    // Form states
+    let token_name = use_state(String::new);
+    let read_only = use_state(|| false);
+    let field_errors = use_state(ValidationErrors::default);
+    // The token just created; it cannot be fetched again
+    let created_token = use_state(|| None::<String>);
+
+    // Load tokens on component mount
+    {
+        let tokens = tokens.clone();
+        let loading = loading.clone();
+        let error = error.clone();
+
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                loading.set(true);
+                error.set(None);
+
+                match api::client().get_api_tokens().await {
+                    Ok(data) => tokens.set(data),
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+
+                loading.set(false);
+            });
+
+            || {}
+        });
+    }
+
+    let on_create_token = {
+        let token_name = token_name.clone();
+        let read_only = read_only.clone();
+        let field_errors = field_errors.clone();
+        let created_token = created_token.clone();
+        let tokens = tokens.clone();
+        let loading = loading.clone();
+        let error = error.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let token_name = token_name.clone();
+            let read_only = read_only.clone();
+            let field_errors = field_errors.clone();
+            let created_token = created_token.clone();
+            let tokens = tokens.clone();
+            let loading = loading.clone();
+            let error = error.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                let req = CreateApiTokenRequest {
+                    name: (*token_name).clone(),
+                    read_only: *read_only,
+                };
+                if let Err(errors) = req.validate() {
+                    field_errors.set(errors);
+                    return;
+                }
+
+                loading.set(true);
+                error.set(None);
+                field_errors.set(ValidationErrors::default());
+
+                match api::client().create_api_token(&req).await {
+                    Ok(created) => {
+                        created_token.set(Some(created.token));
+                        token_name.set(String::new());
+                        read_only.set(false);
+                        match api::client().get_api_tokens().await {
+                            Ok(data) => tokens.set(data),
+                            Err(e) => error.set(Some(e.to_string())),
+                        }
+                    }
+                    Err(e) => match e.field_errors() {
+                        Some(errors) => field_errors.set(errors.clone()),
+                        None => error.set(Some(e.to_string())),
+                    },
+                }
+
+                loading.set(false);
+            });
+        })
+    };
+
+    let on_revoke_token = {
+        let tokens = tokens.clone();
+        let loading = loading.clone();
+        let error = error.clone();
+
+        Callback::from(move |id: Uuid| {
+            let tokens = tokens.clone();
+            let loading = loading.clone();
+            let error = error.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                loading.set(true);
+                error.set(None);
+
+                match api::client().delete_api_token(id).await {
+                    Ok(()) => match api::client().get_api_tokens().await {
+                        Ok(data) => tokens.set(data),
+                        Err(e) => error.set(Some(e.to_string())),
+                    },
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+
+                loading.set(false);
+            });
+        })
+    };
+
+    html! {
+        <div class="container mx-auto p-4">
+            <h1 class="text-3xl font-bold mb-6">{"API Tokens"}</h1>
+
+            if let Some(error_msg) = error.as_ref() {
+                <div class="bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded mb-4">
+                    {error_msg}
+                </div>
+            }
+
+            if let Some(token) = created_token.as_ref() {
+                <div class="bg-green-100 border border-green-400 text-green-800 px-4 py-3 rounded mb-4">
+                    <p class="font-semibold mb-1">{"Copy your new token now. It will not be shown again."}</p>
+                    <code class="block bg-white rounded px-2 py-1 break-all">{token}</code>
+                </div>
+            }
+
+            // Create token form
+            <div class="bg-white shadow-md rounded px-8 pt-6 pb-8 mb-6">
+                <h2 class="text-xl font-semibold mb-4">{"Create New Token"}</h2>
+                <form onsubmit={on_create_token}>
+                    <div class="mb-4">
+                        <label class="block text-gray-700 text-sm font-bold mb-2" for="token-name">
+                            {"Name"}
+                        </label>
+                        <input
+                            id="token-name"
+                            type="text"
+                            value={(*token_name).clone()}
+                            oninput={
+                                let token_name = token_name.clone();
+                                Callback::from(move |e: InputEvent| {
+                                    if let Some(input) = e.target_dyn_into::<web_sys::HtmlInputElement>() {
+                                        token_name.set(input.value());
+                                    }
+                                })
+                            }
+                            class="shadow appearance-none border rounded w-full py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline"
+                            placeholder="e.g. Editor plugin"
+                        />
+                        <FieldError errors={(*field_errors).clone()} field="name" />
+                    </div>
+                    <div class="mb-4">
+                        <label class="inline-flex items-center text-gray-700 text-sm">
+                            <input
+                                type="checkbox"
+                                checked={*read_only}
+                                onchange={
+                                    let read_only = read_only.clone();
+                                    Callback::from(move |_| read_only.set(!*read_only))
+                                }
+                                class="mr-2"
+                            />
+                            {"Read-only (can only view sessions, tags and reports)"}
+                        </label>
+                    </div>
+                    <button
+                        type="submit"
+                        disabled={*loading}
+                        class="bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline disabled:opacity-50"
+                    >
+                        {if *loading { "Creating..." } else { "Create Token" }}
+                    </button>
+                </form>
+            </div>
+
+            // Tokens list
+            <div class="bg-white shadow-md rounded">
+                <div class="px-6 py-4 border-b">
+                    <h2 class="text-xl font-semibold">{"Your Tokens"}</h2>
+                </div>
+
+                if tokens.is_empty() && !*loading {
+                    <div class="px-6 py-4 text-gray-500 text-center">
+                        {"No tokens yet."}
+                    </div>
+                } else {
+                    <ul class="divide-y">
+                        {for tokens.iter().map(|token| {
+                            let token_id = token.id;
+                            let on_revoke = on_revoke_token.clone();
+
+                            html! {
+                                <li class="px-6 py-4 flex items-center justify-between">
+                                    <div>
+                                        <div class="font-medium text-gray-900">
+                                            {&token.name}
+                                            if token.read_only {
+                                                <span class="ml-2 text-xs bg-gray-200 text-gray-700 rounded px-2 py-0.5">{"read-only"}</span>
+                                            }
+                                        </div>
+                                        <div class="text-sm text-gray-500">
+                                            {format!("Created {}", token.created_at.format("%Y-%m-%d %H:%M"))}
+                                            {" · "}
+                                            {match token.last_used_at {
+                                                Some(used) => format!("Last used {}", used.format("%Y-%m-%d %H:%M")),
+                                                None => "Never used".to_string(),
+                                            }}
+                                        </div>
+                                    </div>
+                                    <button
+                                        class="text-red-600 hover:text-red-800 text-sm"
+                                        onclick={Callback::from(move |_| on_revoke.emit(token_id))}
+                                    >
+                                        {"Revoke"}
+                                    </button>
+                                </li>
+                            }
+                        })}
+                    </ul>
+                }
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,337 @@
+use yew::prelude::*;
+use uuid::Uuid;
+use shared::{CreateWebhookRequest, DeliveryStatus, Validate, ValidationErrors, Webhook, WebhookDelivery, WebhookEvent};
+use crate::api;
+use crate::components::FieldError;
+
+/// Endpoints that session events are posted to, with their delivery logs.
+#[function_component(Webhooks)]
+pub fn webhooks() -> Html {
+    let webhooks = use_state(Vec::<Webhook>::new);
+    let loading = use_state(|| false);
+    let error = use_state(|| None::<String>);
+
+    // Form states
+    let url = use_state(String::new);
+    let events = use_state(|| WebhookEvent::SUBSCRIBABLE.to_vec());
+    let field_errors = use_state(ValidationErrors::default);
+    // The secret of the webhook just created; it cannot be fetched again
+    let created_secret = use_state(|| None::<String>);
+
+    // Webhook whose delivery log is open
+    let open_log = use_state(|| None::<(Uuid, Vec<WebhookDelivery>)>);
+
+    // Load webhooks on component mount
+    {
+        let webhooks = webhooks.clone();
+        let loading = loading.clone();
+        let error = error.clone();
+
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                loading.set(true);
+                error.set(None);
+
+                match api::client().get_webhooks().await {
+                    Ok(data) => webhooks.set(data),
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+
+                loading.set(false);
+            });
+
+            || {}
+        });
+    }
+
+    let on_create_webhook = {
+        let url = url.clone();
+        let events = events.clone();
+        let field_errors = field_errors.clone();
+        let created_secret = created_secret.clone();
+        let webhooks = webhooks.clone();
+        let loading = loading.clone();
+        let error = error.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let url = url.clone();
+            let field_errors = field_errors.clone();
+            let created_secret = created_secret.clone();
+            let webhooks = webhooks.clone();
+            let loading = loading.clone();
+            let error = error.clone();
+            let req = CreateWebhookRequest {
+                url: url.trim().to_string(),
+                events: (*events).clone(),
+                secret: None,
+            };
+
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Err(errors) = req.validate() {
+                    field_errors.set(errors);
+                    return;
+                }
+
+                loading.set(true);
+                error.set(None);
+                field_errors.set(ValidationErrors::default());
+
+                match api::client().create_webhook(&req).await {
+                    Ok(created) => {
+                        created_secret.set(Some(created.secret));
+                        url.set(String::new());
+                        let mut list = (*webhooks).clone();
+                        list.push(created.webhook);
+                        webhooks.set(list);
+                    }
+                    Err(e) => match e.field_errors() {
+                        Some(errors) => field_errors.set(errors.clone()),
+                        None => error.set(Some(e.to_string())),
+                    },
+                }
+
+                loading.set(false);
+            });
+        })
+    };
+
+    let on_delete_webhook = {
+        let webhooks = webhooks.clone();
+        let open_log = open_log.clone();
+        let error = error.clone();
+
+        Callback::from(move |id: Uuid| {
+            let webhooks = webhooks.clone();
+            let open_log = open_log.clone();
+            let error = error.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                error.set(None);
+
+                match api::client().delete_webhook(id).await {
+                    Ok(()) => {
+                        webhooks.set(webhooks.iter().filter(|w| w.id != id).cloned().collect());
+                        if open_log.as_ref().is_some_and(|(log_id, _)| *log_id == id) {
+                            open_log.set(None);
+                        }
+                    }
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+            });
+        })
+    };
+
+    // Opens the delivery log, or refreshes it if it is already open
+    let on_show_log = {
+        let open_log = open_log.clone();
+        let error = error.clone();
+
+        Callback::from(move |id: Uuid| {
+            let open_log = open_log.clone();
+            let error = error.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                match api::client().get_webhook_deliveries(id).await {
+                    Ok(deliveries) => open_log.set(Some((id, deliveries))),
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+            });
+        })
+    };
+
+    let on_ping_webhook = {
+        let on_show_log = on_show_log.clone();
+        let error = error.clone();
+
+        Callback::from(move |id: Uuid| {
+            let on_show_log = on_show_log.clone();
+            let error = error.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                error.set(None);
+
+                match api::client().ping_webhook(id).await {
+                    Ok(_) => on_show_log.emit(id),
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+            });
+        })
+    };
+
+    let status_badge = |status: DeliveryStatus| {
+        let (label, class) = match status {
+            DeliveryStatus::Pending => ("pending", "bg-yellow-100 text-yellow-800"),
+            DeliveryStatus::Succeeded => ("succeeded", "bg-green-100 text-green-800"),
+            DeliveryStatus::Failed => ("failed", "bg-red-100 text-red-800"),
+        };
+        html! { <span class={classes!("text-xs", "rounded", "px-2", "py-0.5", class)}>{label}</span> }
+    };
+
+    html! {
+        <div class="container mx-auto p-4">
+            <h1 class="text-3xl font-bold mb-6">{"Webhooks"}</h1>
+
+            if let Some(error_msg) = error.as_ref() {
+                <div class="bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded mb-4">
+                    {error_msg}
+                </div>
+            }
+
+            if let Some(secret) = created_secret.as_ref() {
+                <div class="bg-green-100 border border-green-400 text-green-800 px-4 py-3 rounded mb-4">
+                    <p class="font-semibold mb-1">{"Copy the signing secret now. It will not be shown again."}</p>
+                    <code class="block bg-white rounded px-2 py-1 break-all">{secret}</code>
+                </div>
+            }
+
+            // Create webhook form
+            <div class="bg-white shadow-md rounded px-8 pt-6 pb-8 mb-6">
+                <h2 class="text-xl font-semibold mb-2">{"Add Webhook"}</h2>
+                <p class="text-sm text-gray-600 mb-4">
+                    {"Session events are posted as JSON, signed with HMAC-SHA256 in the X-Wst-Signature header. Failed deliveries are retried for about an hour."}
+                </p>
+                <form onsubmit={on_create_webhook}>
+                    <div class="mb-4">
+                        <label class="block text-gray-700 text-sm font-bold mb-2" for="webhook-url">
+                            {"URL"}
+                        </label>
+                        <input
+                            id="webhook-url"
+                            type="url"
+                            value={(*url).clone()}
+                            oninput={
+                                let url = url.clone();
+                                Callback::from(move |e: InputEvent| {
+                                    if let Some(input) = e.target_dyn_into::<web_sys::HtmlInputElement>() {
+                                        url.set(input.value());
+                                    }
+                                })
+                            }
+                            class="shadow appearance-none border rounded w-full py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline"
+                            placeholder="https://example.com/hooks/sessions"
+                        />
+                        <FieldError errors={(*field_errors).clone()} field="url" />
+                    </div>
+                    <div class="mb-4">
+                        <label class="block text-gray-700 text-sm font-bold mb-2">{"Events"}</label>
+                        <div class="flex flex-wrap gap-4">
+                            {for WebhookEvent::SUBSCRIBABLE.into_iter().map(|event| {
+                                let checked = events.contains(&event);
+                                let events = events.clone();
+                                html! {
+                                    <label class="inline-flex items-center text-gray-700 text-sm">
+                                        <input
+                                            type="checkbox"
+                                            checked={checked}
+                                            onchange={Callback::from(move |_| {
+                                                let mut list = (*events).clone();
+                                                if checked {
+                                                    list.retain(|e| *e != event);
+                                                } else {
+                                                    list.push(event);
+                                                }
+                                                events.set(list);
+                                            })}
+                                            class="mr-2"
+                                        />
+                                        {event.as_str()}
+                                    </label>
+                                }
+                            })}
+                        </div>
+                        <FieldError errors={(*field_errors).clone()} field="events" />
+                    </div>
+                    <button
+                        type="submit"
+                        disabled={*loading}
+                        class="bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline disabled:opacity-50"
+                    >
+                        {if *loading { "Adding..." } else { "Add Webhook" }}
+                    </button>
+                </form>
+            </div>
+
+            // Webhooks list
+            <div class="bg-white shadow-md rounded">
+                <div class="px-6 py-4 border-b">
+                    <h2 class="text-xl font-semibold">{"Webhooks"}</h2>
+                </div>
+
+                if webhooks.is_empty() && !*loading {
+                    <div class="px-6 py-4 text-gray-500 text-center">
+                        {"No webhooks yet."}
+                    </div>
+                } else {
+                    <ul class="divide-y">
+                        {for webhooks.iter().map(|webhook| {
+                            let webhook_id = webhook.id;
+                            let on_delete = on_delete_webhook.clone();
+                            let on_ping = on_ping_webhook.clone();
+                            let on_show_log = on_show_log.clone();
+                            let log = open_log.as_ref().filter(|(id, _)| *id == webhook_id).map(|(_, log)| log);
+
+                            html! {
+                                <li class="px-6 py-4">
+                                    <div class="flex items-center justify-between">
+                                        <div>
+                                            <div class="font-medium text-gray-900 break-all">{&webhook.url}</div>
+                                            <div class="text-sm text-gray-500">
+                                                {webhook.events.iter().map(WebhookEvent::as_str).collect::<Vec<_>>().join(", ")}
+                                            </div>
+                                        </div>
+                                        <div class="flex items-center space-x-4 text-sm">
+                                            <button
+                                                class="text-blue-600 hover:text-blue-800"
+                                                onclick={Callback::from(move |_| on_show_log.emit(webhook_id))}
+                                            >
+                                                {"Deliveries"}
+                                            </button>
+                                            <button
+                                                class="text-blue-600 hover:text-blue-800"
+                                                onclick={Callback::from(move |_| on_ping.emit(webhook_id))}
+                                            >
+                                                {"Ping"}
+                                            </button>
+                                            <button
+                                                class="text-red-600 hover:text-red-800"
+                                                onclick={Callback::from(move |_| on_delete.emit(webhook_id))}
+                                            >
+                                                {"Delete"}
+                                            </button>
+                                        </div>
+                                    </div>
+
+                                    if let Some(log) = log {
+                                        if log.is_empty() {
+                                            <p class="mt-3 text-sm text-gray-500">{"Nothing sent yet."}</p>
+                                        } else {
+                                            <table class="mt-3 w-full text-sm">
+                                                <tbody class="divide-y">
+                                                    {for log.iter().map(|delivery| html! {
+                                                        <tr>
+                                                            <td class="py-1 pr-4 text-gray-600">{delivery.created_at.format("%Y-%m-%d %H:%M:%S").to_string()}</td>
+                                                            <td class="py-1 pr-4">{delivery.event.as_str()}</td>
+                                                            <td class="py-1 pr-4">{status_badge(delivery.status)}</td>
+                                                            <td class="py-1 pr-4 text-gray-600">
+                                                                {format!("{} attempt{}", delivery.attempts, if delivery.attempts == 1 { "" } else { "s" })}
+                                                                if let Some(status) = delivery.response_status {
+                                                                    {format!(", HTTP {status}")}
+                                                                }
+                                                            </td>
+                                                            <td class="py-1 text-gray-500 break-all">{delivery.last_error.clone().unwrap_or_default()}</td>
+                                                        </tr>
+                                                    })}
+                                                </tbody>
+                                            </table>
+                                        }
+                                    }
+                                </li>
+                            }
+                        })}
+                    </ul>
+                }
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,446 @@
+use yew::prelude::*;
+use uuid::Uuid;
+use shared::{AddMemberRequest, UpdateMemberRequest, Validate, ValidationErrors, Workspace, WorkspaceMember, WorkspaceRequest, WorkspaceRole, DEFAULT_CURRENCY};
+use crate::api;
+use crate::components::FieldError;
+
+const ROLES: [WorkspaceRole; 4] = [
+    WorkspaceRole::Viewer,
+    WorkspaceRole::Member,
+    WorkspaceRole::Admin,
+    WorkspaceRole::Owner,
+];
+
+#[derive(Properties, PartialEq)]
+pub struct WorkspacesProps {
+    /// The workspace being managed
+    pub workspace: Workspace,
+    /// Asks the app to reload its workspaces, switching to the given one
+    pub on_change: Callback<Option<Uuid>>,
+}
+
+/// Manages the current workspace and its members, and creates new workspaces.
+#[function_component(Workspaces)]
+pub fn workspaces(props: &WorkspacesProps) -> Html {
+    let members = use_state(Vec::<WorkspaceMember>::new);
+    let loading = use_state(|| false);
+    let error = use_state(|| None::<String>);
+
+    // Form states
+    let settings_name = use_state(|| props.workspace.name.clone());
+    let settings_currency = use_state(|| props.workspace.currency.clone());
+    let settings_errors = use_state(ValidationErrors::default);
+    let new_workspace_name = use_state(String::new);
+    let workspace_errors = use_state(ValidationErrors::default);
+    let member_email = use_state(String::new);
+    let member_role = use_state(|| WorkspaceRole::Member);
+    let member_errors = use_state(ValidationErrors::default);
+
+    let workspace_id = props.workspace.id;
+    let role = props.workspace.role;
+
+    // Load members whenever the workspace changes
+    {
+        let members = members.clone();
+        let loading = loading.clone();
+        let error = error.clone();
+
+        use_effect_with(workspace_id, move |&workspace_id| {
+            wasm_bindgen_futures::spawn_local(async move {
+                loading.set(true);
+                error.set(None);
+
+                match api::client().get_members(workspace_id).await {
+                    Ok(data) => members.set(data),
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+
+                loading.set(false);
+            });
+
+            || {}
+        });
+    }
+
+    let on_create_workspace = {
+        let new_workspace_name = new_workspace_name.clone();
+        let workspace_errors = workspace_errors.clone();
+        let error = error.clone();
+        let on_change = props.on_change.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let new_workspace_name = new_workspace_name.clone();
+            let workspace_errors = workspace_errors.clone();
+            let error = error.clone();
+            let on_change = on_change.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                let req = WorkspaceRequest {
+                    name: (*new_workspace_name).clone(),
+                    currency: DEFAULT_CURRENCY.to_string(),
+                };
+                if let Err(errors) = req.validate() {
+                    workspace_errors.set(errors);
+                    return;
+                }
+
+                error.set(None);
+                workspace_errors.set(ValidationErrors::default());
+
+                match api::client().create_workspace(&req).await {
+                    Ok(workspace) => {
+                        new_workspace_name.set(String::new());
+                        on_change.emit(Some(workspace.id));
+                    }
+                    Err(e) => match e.field_errors() {
+                        Some(errors) => workspace_errors.set(errors.clone()),
+                        None => error.set(Some(e.to_string())),
+                    },
+                }
+            });
+        })
+    };
+
+    let on_save_settings = {
+        let settings_name = settings_name.clone();
+        let settings_currency = settings_currency.clone();
+        let settings_errors = settings_errors.clone();
+        let error = error.clone();
+        let on_change = props.on_change.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let settings_errors = settings_errors.clone();
+            let error = error.clone();
+            let on_change = on_change.clone();
+            let req = WorkspaceRequest {
+                name: (*settings_name).clone(),
+                currency: settings_currency.trim().to_uppercase(),
+            };
+
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Err(errors) = req.validate() {
+                    settings_errors.set(errors);
+                    return;
+                }
+
+                error.set(None);
+                settings_errors.set(ValidationErrors::default());
+
+                match api::client().replace_workspace(workspace_id, &req).await {
+                    Ok(_) => on_change.emit(Some(workspace_id)),
+                    Err(e) => match e.field_errors() {
+                        Some(errors) => settings_errors.set(errors.clone()),
+                        None => error.set(Some(e.to_string())),
+                    },
+                }
+            });
+        })
+    };
+
+    let on_delete_workspace = {
+        let error = error.clone();
+        let on_change = props.on_change.clone();
+
+        Callback::from(move |_| {
+            let error = error.clone();
+            let on_change = on_change.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                match api::client().delete_workspace(workspace_id).await {
+                    Ok(()) => on_change.emit(None),
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+            });
+        })
+    };
+
+    let on_add_member = {
+        let member_email = member_email.clone();
+        let member_role = member_role.clone();
+        let member_errors = member_errors.clone();
+        let members = members.clone();
+        let error = error.clone();
+
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let member_email = member_email.clone();
+            let member_errors = member_errors.clone();
+            let members = members.clone();
+            let error = error.clone();
+            let req = AddMemberRequest {
+                email: (*member_email).clone(),
+                role: *member_role,
+            };
+
+            wasm_bindgen_futures::spawn_local(async move {
+                error.set(None);
+                member_errors.set(ValidationErrors::default());
+
+                match api::client().add_member(workspace_id, &req).await {
+                    Ok(member) => {
+                        member_email.set(String::new());
+                        let mut updated = (*members).clone();
+                        updated.push(member);
+                        members.set(updated);
+                    }
+                    Err(api::ApiError::Conflict { message, .. }) => {
+                        member_errors.set(ValidationErrors::single("email", message));
+                    }
+                    Err(e) => match e.field_errors() {
+                        Some(errors) => member_errors.set(errors.clone()),
+                        None => error.set(Some(e.to_string())),
+                    },
+                }
+            });
+        })
+    };
+
+    let on_change_role = {
+        let members = members.clone();
+        let error = error.clone();
+
+        Callback::from(move |(user_id, role): (Uuid, WorkspaceRole)| {
+            let members = members.clone();
+            let error = error.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                error.set(None);
+
+                match api::client().update_member(workspace_id, user_id, &UpdateMemberRequest { role }).await {
+                    Ok(updated) => members.set(
+                        members
+                            .iter()
+                            .map(|m| if m.user_id == user_id { updated.clone() } else { m.clone() })
+                            .collect(),
+                    ),
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+            });
+        })
+    };
+
+    let on_remove_member = {
+        let members = members.clone();
+        let error = error.clone();
+
+        Callback::from(move |user_id: Uuid| {
+            let members = members.clone();
+            let error = error.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                error.set(None);
+
+                match api::client().remove_member(workspace_id, user_id).await {
+                    Ok(()) => members.set(members.iter().filter(|m| m.user_id != user_id).cloned().collect()),
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+            });
+        })
+    };
+
+    let role_select = |selected: WorkspaceRole, disabled: bool, onchange: Callback<WorkspaceRole>| {
+        html! {
+            <select
+                disabled={disabled}
+                onchange={Callback::from(move |e: Event| {
+                    if let Some(select) = e.target_dyn_into::<web_sys::HtmlSelectElement>() {
+                        if let Some(role) = WorkspaceRole::parse(&select.value()) {
+                            onchange.emit(role);
+                        }
+                    }
+                })}
+                class="shadow border rounded py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline disabled:bg-gray-100"
+            >
+                // Nobody can grant a role above their own
+                {for ROLES.iter().filter(|&&r| r <= role || r == selected).map(|r| html! {
+                    <option value={r.as_str()} selected={*r == selected}>{r.as_str()}</option>
+                })}
+            </select>
+        }
+    };
+
+    html! {
+        <div class="container mx-auto p-4">
+            <h1 class="text-3xl font-bold mb-6">{&props.workspace.name}</h1>
+
+            if let Some(error_msg) = error.as_ref() {
+                <div class="bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded mb-4">
+                    {error_msg}
+                </div>
+            }
+
+            // Members
+            <div class="bg-white shadow-md rounded mb-6">
+                <div class="px-6 py-4 border-b flex items-center justify-between">
+                    <h2 class="text-xl font-semibold">{"Members"}</h2>
+                    <span class="text-sm text-gray-500">{format!("Your role: {}", role.as_str())}</span>
+                </div>
+
+                if *loading && members.is_empty() {
+                    <div class="px-6 py-4 text-gray-500 text-center">{"Loading members..."}</div>
+                } else {
+                    <ul class="divide-y">
+                        {for members.iter().map(|member| {
+                            let user_id = member.user_id;
+                            let manageable = role >= WorkspaceRole::Admin && member.role <= role;
+                            let on_change_role = on_change_role.clone();
+                            let on_remove = on_remove_member.clone();
+
+                            html! {
+                                <li class="px-6 py-3 flex items-center justify-between">
+                                    <span class="text-gray-900">{&member.email}</span>
+                                    <div class="flex items-center space-x-4">
+                                        {role_select(
+                                            member.role,
+                                            !manageable,
+                                            Callback::from(move |role| on_change_role.emit((user_id, role))),
+                                        )}
+                                        if manageable {
+                                            <button
+                                                class="text-red-600 hover:text-red-800 text-sm"
+                                                onclick={Callback::from(move |_| on_remove.emit(user_id))}
+                                            >
+                                                {"Remove"}
+                                            </button>
+                                        }
+                                    </div>
+                                </li>
+                            }
+                        })}
+                    </ul>
+                }
+
+                if role >= WorkspaceRole::Admin {
+                    <form onsubmit={on_add_member} class="px-6 py-4 border-t flex flex-wrap items-start gap-4">
+                        <div class="flex-1">
+                            <input
+                                type="email"
+                                value={(*member_email).clone()}
+                                oninput={
+                                    let member_email = member_email.clone();
+                                    Callback::from(move |e: InputEvent| {
+                                        if let Some(input) = e.target_dyn_into::<web_sys::HtmlInputElement>() {
+                                            member_email.set(input.value());
+                                        }
+                                    })
+                                }
+                                class="shadow appearance-none border rounded w-full py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline"
+                                placeholder="Email of an existing account"
+                            />
+                            <FieldError errors={(*member_errors).clone()} field="email" />
+                        </div>
+                        {role_select(*member_role, false, {
+                            let member_role = member_role.clone();
+                            Callback::from(move |role| member_role.set(role))
+                        })}
+                        <button
+                            type="submit"
+                            class="bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline"
+                        >
+                            {"Add Member"}
+                        </button>
+                    </form>
+                }
+            </div>
+
+            if role >= WorkspaceRole::Admin {
+                <div class="bg-white shadow-md rounded px-8 pt-6 pb-8 mb-6">
+                    <h2 class="text-xl font-semibold mb-4">{"Settings"}</h2>
+                    <form onsubmit={on_save_settings} class="flex flex-wrap items-start gap-4">
+                        <div class="flex-1">
+                            <label class="block text-gray-700 text-sm font-bold mb-2" for="workspace-name">{"Name"}</label>
+                            <input
+                                id="workspace-name"
+                                type="text"
+                                value={(*settings_name).clone()}
+                                oninput={
+                                    let settings_name = settings_name.clone();
+                                    Callback::from(move |e: InputEvent| {
+                                        if let Some(input) = e.target_dyn_into::<web_sys::HtmlInputElement>() {
+                                            settings_name.set(input.value());
+                                        }
+                                    })
+                                }
+                                class="shadow appearance-none border rounded w-full py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline"
+                            />
+                            <FieldError errors={(*settings_errors).clone()} field="name" />
+                        </div>
+                        <div>
+                            <label class="block text-gray-700 text-sm font-bold mb-2" for="workspace-currency">{"Currency"}</label>
+                            <input
+                                id="workspace-currency"
+                                type="text"
+                                maxlength="3"
+                                value={(*settings_currency).clone()}
+                                oninput={
+                                    let settings_currency = settings_currency.clone();
+                                    Callback::from(move |e: InputEvent| {
+                                        if let Some(input) = e.target_dyn_into::<web_sys::HtmlInputElement>() {
+                                            settings_currency.set(input.value());
+                                        }
+                                    })
+                                }
+                                class="shadow appearance-none border rounded w-24 py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline"
+                                placeholder="USD"
+                            />
+                            <FieldError errors={(*settings_errors).clone()} field="currency" />
+                        </div>
+                        <button
+                            type="submit"
+                            class="self-end bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline"
+                        >
+                            {"Save"}
+                        </button>
+                    </form>
+                </div>
+            }
+
+            // New workspace
+            <div class="bg-white shadow-md rounded px-8 pt-6 pb-8 mb-6">
+                <h2 class="text-xl font-semibold mb-4">{"Create New Workspace"}</h2>
+                <form onsubmit={on_create_workspace} class="flex items-start gap-4">
+                    <div class="flex-1">
+                        <input
+                            type="text"
+                            value={(*new_workspace_name).clone()}
+                            oninput={
+                                let new_workspace_name = new_workspace_name.clone();
+                                Callback::from(move |e: InputEvent| {
+                                    if let Some(input) = e.target_dyn_into::<web_sys::HtmlInputElement>() {
+                                        new_workspace_name.set(input.value());
+                                    }
+                                })
+                            }
+                            class="shadow appearance-none border rounded w-full py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline"
+                            placeholder="Workspace name"
+                        />
+                        <FieldError errors={(*workspace_errors).clone()} field="name" />
+                    </div>
+                    <button
+                        type="submit"
+                        class="bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline"
+                    >
+                        {"Create Workspace"}
+                    </button>
+                </form>
+            </div>
+
+            if role == WorkspaceRole::Owner {
+                <div class="bg-white shadow-md rounded px-8 pt-6 pb-8">
+                    <h2 class="text-xl font-semibold mb-2">{"Delete Workspace"}</h2>
+                    <p class="text-sm text-gray-600 mb-4">
+                        {"Deletes this workspace with all of its sessions and tags."}
+                    </p>
+                    <button
+                        onclick={on_delete_workspace}
+                        class="bg-red-600 hover:bg-red-800 text-white font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline"
+                    >
+                        {"Delete Workspace"}
+                    </button>
+                </div>
+            }
+        </div>
+    }
+}
@@ -1,21 +1,64 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+mod validation;
+
+pub use validation::*;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct WorkSession {
     pub id: Uuid,
     pub duration_seconds: i32,
     pub description: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub project_id: Option<Uuid>,
+    pub billable: bool,
+    /// Rate set on the session itself, overriding its project and tags
+    pub hourly_rate: Option<Decimal>,
+}
+
+impl WorkSession {
+    /// A session is running until it has been given an end time.
+    pub fn is_running(&self) -> bool {
+        self.ended_at.is_none()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Tag {
     pub id: Uuid,
     pub name: String,
     pub color: Option<String>,
+    pub hourly_rate: Option<Decimal>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A customer that projects are billed to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Client {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Project {
+    pub id: Uuid,
+    pub client_id: Uuid,
+    pub name: String,
+    /// Amount charged per hour, sent as a decimal string such as `"85.50"`
+    pub hourly_rate: Option<Decimal>,
+    /// Time budgeted for the project
+    pub budget_seconds: Option<i32>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -25,49 +68,958 @@ pub struct SessionTag {
     pub tag_id: Uuid,
 }
 
+/// A period of uninterrupted work within a session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SessionSegment {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+impl SessionSegment {
+    pub fn duration_seconds(&self, now: DateTime<Utc>) -> i32 {
+        (self.ended_at.unwrap_or(now) - self.started_at).num_seconds().max(0) as i32
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct WorkSessionWithTags {
     pub id: Uuid,
     pub duration_seconds: i32,
     pub description: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// The user who tracked the session
+    pub user_id: Uuid,
+    pub project_id: Option<Uuid>,
+    pub billable: bool,
+    /// Rate set on the session itself, overriding its project and tags
+    pub hourly_rate: Option<Decimal>,
+    /// The rate that applies: the session's own, else its project's, else
+    /// the highest of its tags' rates
+    pub effective_rate: Option<Decimal>,
+    /// What the session earns, if it is billable and has a rate
+    pub amount: Option<Decimal>,
+    /// Currency of `amount` and the rates, as an ISO 4217 code
+    pub currency: String,
     pub tags: Vec<Tag>,
+    pub segments: Vec<SessionSegment>,
+}
+
+impl WorkSessionWithTags {
+    pub fn is_running(&self) -> bool {
+        self.ended_at.is_none()
+    }
+
+    /// A running session is paused when none of its segments is in progress.
+    pub fn is_paused(&self) -> bool {
+        self.is_running() && self.segments.iter().all(|s| s.ended_at.is_some())
+    }
+
+    /// Seconds worked so far. While a session is running its stored duration
+    /// covers the finished segments, so the open segment is added on top.
+    pub fn elapsed_seconds(&self, now: DateTime<Utc>) -> i32 {
+        let open_segment = self
+            .segments
+            .iter()
+            .filter(|s| s.ended_at.is_none())
+            .map(|s| s.duration_seconds(now))
+            .sum::<i32>();
+
+        match self.ended_at {
+            None => self.duration_seconds + open_segment,
+            Some(_) => self.duration_seconds,
+        }
+    }
 }
 
 // DTOs for API requests
+
+/// Records a finished session. Give `started_at` and `ended_at`, or either one
+/// with `duration_seconds`; a bare `duration_seconds` is taken to end now.
+/// `duration_seconds` defaults to the span between the timestamps and may be
+/// shorter than it when the session included breaks.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CreateSessionRequest {
-    pub duration_seconds: i32,
+    pub duration_seconds: Option<i32>,
     pub description: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
     pub tag_ids: Vec<Uuid>,
+    pub project_id: Option<Uuid>,
+    #[serde(default)]
+    pub billable: bool,
+    pub hourly_rate: Option<Decimal>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct UpdateSessionRequest {
-    pub duration_seconds: Option<i32>,
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct StartSessionRequest {
     pub description: Option<String>,
-    pub tag_ids: Option<Vec<Uuid>>,
+    pub tag_ids: Vec<Uuid>,
+    pub project_id: Option<Uuid>,
+    #[serde(default)]
+    pub billable: bool,
+    pub hourly_rate: Option<Decimal>,
+}
+
+/// A nullable field in an update request: left as is when the field is
+/// absent, cleared when it is `null`, and set otherwise. Fields using it must
+/// be marked `#[serde(default, skip_serializing_if = "FieldUpdate::is_unchanged")]`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum FieldUpdate<T> {
+    #[default]
+    Unchanged,
+    Clear,
+    Set(T),
+}
+
+impl<T> FieldUpdate<T> {
+    pub fn is_unchanged(&self) -> bool {
+        matches!(self, FieldUpdate::Unchanged)
+    }
+
+    /// The value to store, `None` when clearing or unchanged.
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            FieldUpdate::Set(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl<T> From<Option<T>> for FieldUpdate<T> {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => FieldUpdate::Set(value),
+            None => FieldUpdate::Clear,
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for FieldUpdate<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value().serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for FieldUpdate<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::<T>::deserialize(deserializer).map(FieldUpdate::from)
+    }
+}
+
+/// A JSON Merge Patch (RFC 7396) for a session, sent with `PATCH`. Fields
+/// left out are kept and `null` clears `description`, `tag_ids`,
+/// `project_id` or `hourly_rate`; the other fields cannot be null. Time
+/// changes follow the rules of [`CreateSessionRequest`], keeping whichever
+/// bound is not given.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UpdateSessionRequest {
+    #[serde(default, skip_serializing_if = "FieldUpdate::is_unchanged")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<i32>))]
+    pub duration_seconds: FieldUpdate<i32>,
+    #[serde(default, skip_serializing_if = "FieldUpdate::is_unchanged")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
+    pub description: FieldUpdate<String>,
+    #[serde(default, skip_serializing_if = "FieldUpdate::is_unchanged")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<DateTime<Utc>>))]
+    pub started_at: FieldUpdate<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "FieldUpdate::is_unchanged")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<DateTime<Utc>>))]
+    pub ended_at: FieldUpdate<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "FieldUpdate::is_unchanged")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Vec<Uuid>>))]
+    pub tag_ids: FieldUpdate<Vec<Uuid>>,
+    #[serde(default, skip_serializing_if = "FieldUpdate::is_unchanged")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Uuid>))]
+    pub project_id: FieldUpdate<Uuid>,
+    #[serde(default, skip_serializing_if = "FieldUpdate::is_unchanged")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<bool>))]
+    pub billable: FieldUpdate<bool>,
+    #[serde(default, skip_serializing_if = "FieldUpdate::is_unchanged")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
+    pub hourly_rate: FieldUpdate<Decimal>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CreateTagRequest {
     pub name: String,
     pub color: Option<String>,
+    pub hourly_rate: Option<Decimal>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// A JSON Merge Patch (RFC 7396) for a tag, sent with `PATCH`. Fields left
+/// out are kept and `null` clears `color` or `hourly_rate`; `name` cannot be
+/// null.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct UpdateTagRequest {
-    pub name: Option<String>,
-    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "FieldUpdate::is_unchanged")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
+    pub name: FieldUpdate<String>,
+    #[serde(default, skip_serializing_if = "FieldUpdate::is_unchanged")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
+    pub color: FieldUpdate<String>,
+    #[serde(default, skip_serializing_if = "FieldUpdate::is_unchanged")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
+    pub hourly_rate: FieldUpdate<Decimal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateClientRequest {
+    pub name: String,
+}
+
+/// A JSON Merge Patch (RFC 7396) for a client, sent with `PATCH`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UpdateClientRequest {
+    #[serde(default, skip_serializing_if = "FieldUpdate::is_unchanged")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
+    pub name: FieldUpdate<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateProjectRequest {
+    pub client_id: Uuid,
+    pub name: String,
+    pub hourly_rate: Option<Decimal>,
+    pub budget_seconds: Option<i32>,
+}
+
+/// A JSON Merge Patch (RFC 7396) for a project, sent with `PATCH`. `null`
+/// clears `hourly_rate` or `budget_seconds`; the other fields cannot be null.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UpdateProjectRequest {
+    #[serde(default, skip_serializing_if = "FieldUpdate::is_unchanged")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Uuid>))]
+    pub client_id: FieldUpdate<Uuid>,
+    #[serde(default, skip_serializing_if = "FieldUpdate::is_unchanged")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
+    pub name: FieldUpdate<String>,
+    #[serde(default, skip_serializing_if = "FieldUpdate::is_unchanged")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
+    pub hourly_rate: FieldUpdate<Decimal>,
+    #[serde(default, skip_serializing_if = "FieldUpdate::is_unchanged")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<i32>))]
+    pub budget_seconds: FieldUpdate<i32>,
+}
+
+/// A session to import, as a JSON array element or CSV row. Times follow the
+/// rules of [`CreateSessionRequest`]; tags are given by name and created if
+/// they do not exist. CSV files use the export layout, with tag names
+/// separated by `;`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImportSessionRecord {
+    pub started_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub duration_seconds: Option<i32>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct ImportOptions {
+    /// Validate and report without saving anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImportRowError {
+    /// 1-based position of the record, not counting a CSV header
+    pub row: usize,
+    pub message: String,
+}
+
+/// Outcome of an import. Nothing is saved unless `committed` is set, which
+/// requires a real run without row errors.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub committed: bool,
+    pub imported: usize,
+    /// Tags created for the import, or that would be if it were committed
+    pub created_tags: Vec<String>,
+    pub errors: Vec<ImportRowError>,
+}
+
+/// Sort order for session listings, by start time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Desc,
+    Asc,
+}
+
+/// Whether a session must carry any or all of the requested tags.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum TagMatch {
+    #[default]
+    Any,
+    All,
+}
+
+/// Filters and pagination for `GET /api/sessions`, read from the query string.
+/// `tag_ids` is a comma-separated list there.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct SessionQuery {
+    /// Sessions started at or after this time
+    pub from: Option<DateTime<Utc>>,
+    /// Sessions started before this time
+    pub to: Option<DateTime<Utc>>,
+    /// Comma-separated tag ids
+    #[serde(default, with = "comma_separated")]
+    #[cfg_attr(feature = "openapi", param(value_type = Option<String>))]
+    pub tag_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub tag_match: TagMatch,
+    /// Case-insensitive substring of the description
+    pub search: Option<String>,
+    pub min_duration: Option<i32>,
+    pub max_duration: Option<i32>,
+    /// Only running (`true`) or only stopped (`false`) sessions
+    pub running: Option<bool>,
+    #[serde(default)]
+    pub order: SortOrder,
+    pub limit: Option<i64>,
+    /// `next_cursor` from the previous page
+    pub cursor: Option<String>,
+}
+
+impl SessionQuery {
+    pub const DEFAULT_LIMIT: i64 = 50;
+    pub const MAX_LIMIT: i64 = 200;
+
+    /// The query as URL parameters, leaving out anything unset.
+    pub fn to_query_pairs(&self) -> Vec<(&'static str, String)> {
+        let mut pairs = Vec::new();
+        if let Some(from) = self.from {
+            pairs.push(("from", from.to_rfc3339()));
+        }
+        if let Some(to) = self.to {
+            pairs.push(("to", to.to_rfc3339()));
+        }
+        if !self.tag_ids.is_empty() {
+            pairs.push(("tag_ids", comma_separated::join(&self.tag_ids)));
+            if self.tag_match == TagMatch::All {
+                pairs.push(("tag_match", "all".to_string()));
+            }
+        }
+        if let Some(search) = &self.search {
+            pairs.push(("search", search.clone()));
+        }
+        if let Some(min_duration) = self.min_duration {
+            pairs.push(("min_duration", min_duration.to_string()));
+        }
+        if let Some(max_duration) = self.max_duration {
+            pairs.push(("max_duration", max_duration.to_string()));
+        }
+        if let Some(running) = self.running {
+            pairs.push(("running", running.to_string()));
+        }
+        if self.order == SortOrder::Asc {
+            pairs.push(("order", "asc".to_string()));
+        }
+        if let Some(limit) = self.limit {
+            pairs.push(("limit", limit.to_string()));
+        }
+        if let Some(cursor) = &self.cursor {
+            pairs.push(("cursor", cursor.clone()));
+        }
+        pairs
+    }
+}
+
+/// Position in a session listing, just past the last session returned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SessionCursor {
+    pub started_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl SessionCursor {
+    pub fn encode(&self) -> String {
+        format!("{}_{}", self.started_at.timestamp_micros(), self.id)
+    }
+
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let (micros, id) = cursor.split_once('_')?;
+        Some(Self {
+            started_at: DateTime::from_timestamp_micros(micros.parse().ok()?)?,
+            id: Uuid::parse_str(id).ok()?,
+        })
+    }
+}
+
+/// One page of a listing. `next_cursor` is absent on the last page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Default for Page<T> {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            next_cursor: None,
+        }
+    }
+}
+
+mod comma_separated {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use uuid::Uuid;
+
+    pub fn join(ids: &[Uuid]) -> String {
+        ids.iter().map(Uuid::to_string).collect::<Vec<_>>().join(",")
+    }
+
+    pub fn serialize<S: Serializer>(ids: &[Uuid], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&join(ids))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Uuid>, D::Error> {
+        String::deserialize(deserializer)?
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| Uuid::parse_str(id).map_err(D::Error::custom))
+            .collect()
+    }
+}
+
+/// Calendar unit that report totals are grouped into. Weeks are ISO weeks,
+/// starting on Monday.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ReportPeriod {
+    Day,
+    Week,
+    Month,
+}
+
+impl ReportPeriod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportPeriod::Day => "day",
+            ReportPeriod::Week => "week",
+            ReportPeriod::Month => "month",
+        }
+    }
+}
+
+/// Parameters for `GET /api/reports`. `from` and `to` are inclusive calendar
+/// dates in `timezone` (an IANA name such as `Europe/Berlin`, UTC if absent).
+/// Totals are grouped by `period`, by tag, by both, or neither.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct ReportQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub timezone: Option<String>,
+    pub period: Option<ReportPeriod>,
+    #[serde(default)]
+    pub by_tag: bool,
+}
+
+impl ReportQuery {
+    pub fn to_query_pairs(&self) -> Vec<(&'static str, String)> {
+        let mut pairs = vec![("from", self.from.to_string()), ("to", self.to.to_string())];
+        if let Some(timezone) = &self.timezone {
+            pairs.push(("timezone", timezone.clone()));
+        }
+        if let Some(period) = self.period {
+            pairs.push(("period", period.as_str().to_string()));
+        }
+        if self.by_tag {
+            pairs.push(("by_tag", "true".to_string()));
+        }
+        pairs
+    }
+}
+
+/// Total time for one group. `period_start` is set when grouping by period
+/// and the tag fields when grouping by tag; untagged sessions have no tag.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ReportRow {
+    pub period_start: Option<NaiveDate>,
+    pub tag_id: Option<Uuid>,
+    pub tag_name: Option<String>,
+    pub tag_color: Option<String>,
+    pub total_seconds: i64,
+    pub session_count: i64,
+    pub billable_seconds: i64,
+    /// Earnings of the group's billable sessions
+    pub amount: Decimal,
+}
+
+/// Time totals for a date range. Finished sessions count towards the period
+/// they started in; running ones are left out until they stop. When grouping
+/// by tag a session counts once for each of its tags, so `total_seconds` is
+/// the figure to use for the overall total.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Report {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub timezone: String,
+    pub period: Option<ReportPeriod>,
+    pub by_tag: bool,
+    pub total_seconds: i64,
+    pub billable_seconds: i64,
+    pub amount: Decimal,
+    /// Currency of the amounts, as an ISO 4217 code
+    pub currency: String,
+    pub rows: Vec<ReportRow>,
+}
+
+// Accounts
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct User {
+    pub id: Uuid,
+    pub email: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RegisterRequest {
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LoginRequest {
+    pub email: String,
+    pub password: String,
+}
+
+/// A personal access token, sent as `Authorization: Bearer <token>`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub name: String,
+    /// Read-only tokens may only make `GET` requests
+    pub read_only: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateApiTokenRequest {
+    pub name: String,
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// A newly created token. `token` is only ever returned here.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreatedApiToken {
+    #[serde(flatten)]
+    pub api_token: ApiToken,
+    pub token: String,
+}
+
+// Workspaces
+
+/// A member's role in a workspace, from least to most privileged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceRole {
+    /// Sees the workspace's sessions, tags and reports
+    Viewer,
+    /// Also tracks their own sessions and creates tags
+    Member,
+    /// Also edits anyone's sessions, renames and deletes tags and manages members
+    Admin,
+    /// Also renames and deletes the workspace
+    Owner,
+}
+
+impl WorkspaceRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WorkspaceRole::Viewer => "viewer",
+            WorkspaceRole::Member => "member",
+            WorkspaceRole::Admin => "admin",
+            WorkspaceRole::Owner => "owner",
+        }
+    }
+
+    pub fn parse(role: &str) -> Option<Self> {
+        match role {
+            "viewer" => Some(WorkspaceRole::Viewer),
+            "member" => Some(WorkspaceRole::Member),
+            "admin" => Some(WorkspaceRole::Admin),
+            "owner" => Some(WorkspaceRole::Owner),
+            _ => None,
+        }
+    }
+}
+
+/// A workspace as seen by one of its members.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Workspace {
+    pub id: Uuid,
+    pub name: String,
+    /// The caller's role
+    pub role: WorkspaceRole,
+    /// Currency that rates and amounts are in, as an ISO 4217 code
+    pub currency: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WorkspaceMember {
+    pub user_id: Uuid,
+    pub email: String,
+    pub role: WorkspaceRole,
+    pub joined_at: DateTime<Utc>,
+}
+
+/// Body of `POST /api/workspaces` and `PUT /api/workspaces/:id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WorkspaceRequest {
+    pub name: String,
+    #[serde(default = "default_currency")]
+    pub currency: String,
+}
+
+/// Currency of workspaces that do not choose one.
+pub const DEFAULT_CURRENCY: &str = "USD";
+
+fn default_currency() -> String {
+    DEFAULT_CURRENCY.to_string()
+}
+
+/// Adds an existing account to a workspace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AddMemberRequest {
+    pub email: String,
+    pub role: WorkspaceRole,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UpdateMemberRequest {
+    pub role: WorkspaceRole,
+}
+
+/// Selects the workspace a request applies to. Without it, requests use the
+/// first workspace the caller joined, normally the one created with their
+/// account.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct WorkspaceParam {
+    pub workspace_id: Option<Uuid>,
+}
+
+// Invoices
+
+/// How an invoice's sessions are grouped into line items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum InvoiceGrouping {
+    /// One line per tag. A session with several tags counts under the one
+    /// with the highest rate.
+    Tag,
+    /// One line per day the sessions started on
+    Day,
+}
+
+impl InvoiceGrouping {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InvoiceGrouping::Tag => "tag",
+            InvoiceGrouping::Day => "day",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "tag" => Some(InvoiceGrouping::Tag),
+            "day" => Some(InvoiceGrouping::Day),
+            _ => None,
+        }
+    }
+}
+
+/// Invoices the billable, not yet invoiced sessions that started between
+/// `from` and `to` (inclusive dates in `timezone`, UTC if absent), optionally
+/// only those of one client's projects or with one tag.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateInvoiceRequest {
+    pub client_id: Option<Uuid>,
+    pub tag_id: Option<Uuid>,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub timezone: Option<String>,
+    pub group_by: InvoiceGrouping,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct InvoiceLine {
+    pub description: String,
+    pub seconds: i64,
+    pub session_count: i64,
+    pub amount: Decimal,
+}
+
+/// A snapshot of invoiced sessions. Its sessions cannot be edited or deleted
+/// until the invoice is deleted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Invoice {
+    pub id: Uuid,
+    /// Sequential within the workspace, starting at 1
+    pub number: i32,
+    pub client_id: Option<Uuid>,
+    /// The client's name when the invoice was created
+    pub client_name: Option<String>,
+    pub tag_id: Option<Uuid>,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub group_by: InvoiceGrouping,
+    pub currency: String,
+    pub total: Decimal,
+    pub created_at: DateTime<Utc>,
+    pub lines: Vec<InvoiceLine>,
+}
+
+// Webhooks
+
+/// What a webhook delivery reports. Sent as the `event` field and the
+/// `X-Wst-Event` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum WebhookEvent {
+    /// A finished session was added, by hand or by an import
+    #[serde(rename = "session.created")]
+    SessionCreated,
+    #[serde(rename = "session.started")]
+    SessionStarted,
+    #[serde(rename = "session.paused")]
+    SessionPaused,
+    #[serde(rename = "session.resumed")]
+    SessionResumed,
+    #[serde(rename = "session.stopped")]
+    SessionStopped,
+    /// The session was edited
+    #[serde(rename = "session.updated")]
+    SessionUpdated,
+    #[serde(rename = "session.deleted")]
+    SessionDeleted,
+    /// Sent on request to check a webhook, whatever events it subscribes to
+    #[serde(rename = "ping")]
+    Ping,
+}
+
+impl WebhookEvent {
+    /// Every event a webhook can subscribe to.
+    pub const SUBSCRIBABLE: [WebhookEvent; 7] = [
+        WebhookEvent::SessionCreated,
+        WebhookEvent::SessionStarted,
+        WebhookEvent::SessionPaused,
+        WebhookEvent::SessionResumed,
+        WebhookEvent::SessionStopped,
+        WebhookEvent::SessionUpdated,
+        WebhookEvent::SessionDeleted,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::SessionCreated => "session.created",
+            WebhookEvent::SessionStarted => "session.started",
+            WebhookEvent::SessionPaused => "session.paused",
+            WebhookEvent::SessionResumed => "session.resumed",
+            WebhookEvent::SessionStopped => "session.stopped",
+            WebhookEvent::SessionUpdated => "session.updated",
+            WebhookEvent::SessionDeleted => "session.deleted",
+            WebhookEvent::Ping => "ping",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        WebhookEvent::SUBSCRIBABLE
+            .into_iter()
+            .chain([WebhookEvent::Ping])
+            .find(|event| event.as_str() == value)
+    }
+}
+
+/// An endpoint that session events are posted to. Its signing secret is
+/// only returned when it is created.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Webhook {
+    pub id: Uuid,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateWebhookRequest {
+    /// `http` or `https` URL that deliveries are posted to
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    /// Key for the `X-Wst-Signature` HMAC. Generated if absent.
+    pub secret: Option<String>,
+}
+
+/// A newly created webhook. `secret` is only ever returned here.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreatedWebhook {
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    pub secret: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    /// Waiting for its first attempt or a retry
+    Pending,
+    Succeeded,
+    /// Every attempt failed
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Succeeded => "succeeded",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(DeliveryStatus::Pending),
+            "succeeded" => Some(DeliveryStatus::Succeeded),
+            "failed" => Some(DeliveryStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// One event sent, or to be sent, to a webhook.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub event: WebhookEvent,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    /// When the next attempt is due, while `pending`
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    /// HTTP status of the last attempt, if the endpoint answered
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+// Live updates
+
+/// A change pushed to everyone watching a workspace over
+/// `GET /api/events`, sent as the SSE `data` of a `change` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum LiveEvent {
+    SessionCreated(WorkSessionWithTags),
+    SessionUpdated(WorkSessionWithTags),
+    SessionDeleted { id: Uuid },
+    TagCreated(Tag),
+    TagUpdated(Tag),
+    TagDeleted { id: Uuid },
+    /// Too much changed to describe, e.g. after an import or when the
+    /// subscriber fell behind. Reload everything.
+    Resync,
 }
 
 // API Response types
+
+/// Machine-readable reason for a failed request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// Not logged in, or the login has expired
+    Unauthorized,
+    /// Logged in, but not allowed to make this request
+    Forbidden,
+    NotFound,
+    Validation,
+    Conflict,
+    Internal,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ApiResponse<T> {
     pub success: bool,
     pub data: Option<T>,
     pub message: Option<String>,
+    /// Set on failed responses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<ErrorCode>,
+    /// Per-field messages for `ErrorCode::Validation`, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field_errors: Option<ValidationErrors>,
+    /// For `ErrorCode::Conflict`, the id of the resource the request clashed with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub existing_id: Option<Uuid>,
 }
 
 impl<T> ApiResponse<T> {
@@ -76,14 +1028,97 @@ impl<T> ApiResponse<T> {
             success: true,
             data: Some(data),
             message: None,
+            error_code: None,
+            field_errors: None,
+            existing_id: None,
         }
     }
 
-    pub fn error(message: String) -> Self {
+    pub fn error(code: ErrorCode, message: String) -> Self {
         Self {
             success: false,
             data: None,
             message: Some(message),
+            error_code: Some(code),
+            field_errors: None,
+            existing_id: None,
+        }
+    }
+
+    pub fn invalid(errors: ValidationErrors) -> Self {
+        Self {
+            success: false,
+            data: None,
+            message: Some(errors.to_string()),
+            error_code: Some(ErrorCode::Validation),
+            field_errors: Some(errors),
+            existing_id: None,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    /// Deserializes `body`, checks that it serializes back unchanged, and
+    /// returns the parsed request.
+    fn round_trip<T: Serialize + for<'de> Deserialize<'de>>(body: Value) -> T {
+        let parsed: T = serde_json::from_value(body.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), body);
+        parsed
+    }
+
+    #[test]
+    fn session_update_fields_can_be_absent_null_or_set() {
+        let req: UpdateSessionRequest = round_trip(json!({}));
+        assert_eq!(req.description, FieldUpdate::Unchanged);
+        assert_eq!(req.tag_ids, FieldUpdate::Unchanged);
+        assert_eq!(req.project_id, FieldUpdate::Unchanged);
+        assert_eq!(req.hourly_rate, FieldUpdate::Unchanged);
+
+        let req: UpdateSessionRequest = round_trip(json!({
+            "description": null,
+            "tag_ids": null,
+            "project_id": null,
+            "hourly_rate": null,
+        }));
+        assert_eq!(req.description, FieldUpdate::Clear);
+        assert_eq!(req.tag_ids, FieldUpdate::Clear);
+        assert_eq!(req.project_id, FieldUpdate::Clear);
+        assert_eq!(req.hourly_rate, FieldUpdate::Clear);
+        assert_eq!(req.duration_seconds, FieldUpdate::Unchanged);
+
+        let tag_id = Uuid::new_v4();
+        let req: UpdateSessionRequest = round_trip(json!({
+            "description": "Review",
+            "tag_ids": [tag_id],
+            "duration_seconds": 1800,
+            "hourly_rate": "85.50",
+        }));
+        assert_eq!(req.description, FieldUpdate::Set("Review".to_string()));
+        assert_eq!(req.tag_ids, FieldUpdate::Set(vec![tag_id]));
+        assert_eq!(req.duration_seconds, FieldUpdate::Set(1800));
+        assert_eq!(req.hourly_rate, FieldUpdate::Set(Decimal::new(8550, 2)));
+        assert_eq!(req.project_id, FieldUpdate::Unchanged);
+    }
+
+    #[test]
+    fn tag_update_fields_can_be_absent_null_or_set() {
+        let req: UpdateTagRequest = round_trip(json!({}));
+        assert_eq!(req.name, FieldUpdate::Unchanged);
+        assert_eq!(req.color, FieldUpdate::Unchanged);
+        assert_eq!(req.hourly_rate, FieldUpdate::Unchanged);
+
+        let req: UpdateTagRequest = round_trip(json!({ "color": null, "hourly_rate": null }));
+        assert_eq!(req.name, FieldUpdate::Unchanged);
+        assert_eq!(req.color, FieldUpdate::Clear);
+        assert_eq!(req.hourly_rate, FieldUpdate::Clear);
+
+        let req: UpdateTagRequest = round_trip(json!({ "name": "Backend", "color": "#3B82F6", "hourly_rate": "40" }));
+        assert_eq!(req.name, FieldUpdate::Set("Backend".to_string()));
+        assert_eq!(req.color, FieldUpdate::Set("#3B82F6".to_string()));
+        assert_eq!(req.hourly_rate, FieldUpdate::Set(Decimal::new(40, 0)));
+    }
+}
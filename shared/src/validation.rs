@@ -0,0 +1,545 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{CreateApiTokenRequest, CreateClientRequest, CreateInvoiceRequest, CreateProjectRequest, CreateSessionRequest, CreateTagRequest, CreateWebhookRequest, FieldUpdate, RegisterRequest, StartSessionRequest, UpdateClientRequest, UpdateProjectRequest, UpdateSessionRequest, UpdateTagRequest, WebhookEvent, WorkspaceRequest};
+
+pub const MAX_DESCRIPTION_LENGTH: usize = 10_000;
+pub const MAX_TAG_NAME_LENGTH: usize = 100;
+pub const MAX_CLIENT_NAME_LENGTH: usize = 100;
+pub const MAX_PROJECT_NAME_LENGTH: usize = 100;
+/// Largest hourly rate the database's `NUMERIC(12, 2)` column can hold
+pub const MAX_HOURLY_RATE: Decimal = Decimal::from_parts(3_567_587_327, 232, 0, false, 2);
+pub const MAX_TOKEN_NAME_LENGTH: usize = 100;
+pub const MAX_WORKSPACE_NAME_LENGTH: usize = 100;
+pub const MAX_EMAIL_LENGTH: usize = 255;
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+pub const MAX_PASSWORD_LENGTH: usize = 128;
+pub const MAX_WEBHOOK_URL_LENGTH: usize = 2000;
+pub const MIN_WEBHOOK_SECRET_LENGTH: usize = 16;
+pub const MAX_WEBHOOK_SECRET_LENGTH: usize = 200;
+
+/// Validation failures keyed by request field name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(transparent)]
+pub struct ValidationErrors(BTreeMap<String, Vec<String>>);
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn single(field: &str, message: impl Into<String>) -> Self {
+        let mut errors = Self::new();
+        errors.add(field, message);
+        errors
+    }
+
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        self.0.entry(field.to_string()).or_default().push(message.into());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The first message for `field`, if it failed validation.
+    pub fn get(&self, field: &str) -> Option<&str> {
+        self.0.get(field).and_then(|messages| messages.first()).map(String::as_str)
+    }
+
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for (field, messages) in &self.0 {
+            for message in messages {
+                if !first {
+                    f.write_str("; ")?;
+                }
+                write!(f, "{field}: {message}")?;
+                first = false;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+/// Checks a request before it is sent or applied. Rules that need the
+/// database, such as whether tag ids exist, are left to the backend.
+pub trait Validate {
+    fn validate(&self) -> Result<(), ValidationErrors>;
+}
+
+/// Whether `color` is a `#RRGGBB` hex code.
+pub fn is_hex_color(color: &str) -> bool {
+    color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn check_not_cleared<T>(errors: &mut ValidationErrors, field: &str, update: &FieldUpdate<T>) {
+    if matches!(update, FieldUpdate::Clear) {
+        errors.add(field, "must not be null");
+    }
+}
+
+fn check_description(errors: &mut ValidationErrors, description: Option<&str>) {
+    if description.is_some_and(|d| d.chars().count() > MAX_DESCRIPTION_LENGTH) {
+        errors.add(
+            "description",
+            format!("must be at most {MAX_DESCRIPTION_LENGTH} characters"),
+        );
+    }
+}
+
+fn check_duration(errors: &mut ValidationErrors, duration_seconds: Option<i32>) {
+    if duration_seconds.is_some_and(|d| d < 0) {
+        errors.add("duration_seconds", "must not be negative");
+    }
+}
+
+fn check_bounds(
+    errors: &mut ValidationErrors,
+    started_at: Option<DateTime<Utc>>,
+    ended_at: Option<DateTime<Utc>>,
+) {
+    if let (Some(started_at), Some(ended_at)) = (started_at, ended_at) {
+        if ended_at < started_at {
+            errors.add("ended_at", "must not be before started_at");
+        }
+    }
+}
+
+fn check_tag_ids(errors: &mut ValidationErrors, tag_ids: &[Uuid]) {
+    let mut seen = HashSet::new();
+    if !tag_ids.iter().all(|id| seen.insert(id)) {
+        errors.add("tag_ids", "must not contain duplicates");
+    }
+}
+
+fn check_tag_name(errors: &mut ValidationErrors, name: &str) {
+    if name.trim().is_empty() {
+        errors.add("name", "is required");
+    } else if name.chars().count() > MAX_TAG_NAME_LENGTH {
+        errors.add("name", format!("must be at most {MAX_TAG_NAME_LENGTH} characters"));
+    }
+}
+
+/// Checks a required name, as for clients and projects.
+fn check_name(errors: &mut ValidationErrors, name: &str, max_length: usize) {
+    if name.trim().is_empty() {
+        errors.add("name", "is required");
+    } else if name.chars().count() > max_length {
+        errors.add("name", format!("must be at most {max_length} characters"));
+    }
+}
+
+fn check_hourly_rate(errors: &mut ValidationErrors, hourly_rate: Option<&Decimal>) {
+    let Some(rate) = hourly_rate else {
+        return;
+    };
+    if rate.is_sign_negative() {
+        errors.add("hourly_rate", "must not be negative");
+    } else if rate.scale() > 2 && rate.normalize().scale() > 2 {
+        errors.add("hourly_rate", "must have at most 2 decimal places");
+    } else if *rate > MAX_HOURLY_RATE {
+        errors.add("hourly_rate", format!("must be at most {MAX_HOURLY_RATE}"));
+    }
+}
+
+fn check_budget(errors: &mut ValidationErrors, budget_seconds: Option<i32>) {
+    if budget_seconds.is_some_and(|b| b < 0) {
+        errors.add("budget_seconds", "must not be negative");
+    }
+}
+
+fn check_color(errors: &mut ValidationErrors, color: Option<&str>) {
+    if color.is_some_and(|c| !is_hex_color(c)) {
+        errors.add("color", "must be a hex color like #3B82F6");
+    }
+}
+
+impl Validate for CreateSessionRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        check_duration(&mut errors, self.duration_seconds);
+        check_description(&mut errors, self.description.as_deref());
+        check_bounds(&mut errors, self.started_at, self.ended_at);
+        check_tag_ids(&mut errors, &self.tag_ids);
+        check_hourly_rate(&mut errors, self.hourly_rate.as_ref());
+        errors.into_result()
+    }
+}
+
+impl Validate for StartSessionRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        check_description(&mut errors, self.description.as_deref());
+        check_tag_ids(&mut errors, &self.tag_ids);
+        check_hourly_rate(&mut errors, self.hourly_rate.as_ref());
+        errors.into_result()
+    }
+}
+
+impl Validate for UpdateSessionRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        check_not_cleared(&mut errors, "duration_seconds", &self.duration_seconds);
+        check_not_cleared(&mut errors, "started_at", &self.started_at);
+        check_not_cleared(&mut errors, "ended_at", &self.ended_at);
+        check_not_cleared(&mut errors, "billable", &self.billable);
+        check_duration(&mut errors, self.duration_seconds.value().copied());
+        check_description(&mut errors, self.description.value().map(String::as_str));
+        check_bounds(&mut errors, self.started_at.value().copied(), self.ended_at.value().copied());
+        if let Some(tag_ids) = self.tag_ids.value() {
+            check_tag_ids(&mut errors, tag_ids);
+        }
+        check_hourly_rate(&mut errors, self.hourly_rate.value());
+        errors.into_result()
+    }
+}
+
+impl Validate for CreateTagRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        check_tag_name(&mut errors, &self.name);
+        check_color(&mut errors, self.color.as_deref());
+        check_hourly_rate(&mut errors, self.hourly_rate.as_ref());
+        errors.into_result()
+    }
+}
+
+impl Validate for UpdateTagRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        check_not_cleared(&mut errors, "name", &self.name);
+        if let Some(name) = self.name.value() {
+            check_tag_name(&mut errors, name);
+        }
+        check_color(&mut errors, self.color.value().map(String::as_str));
+        check_hourly_rate(&mut errors, self.hourly_rate.value());
+        errors.into_result()
+    }
+}
+
+impl Validate for RegisterRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        let email = self.email.trim();
+        if email.is_empty() {
+            errors.add("email", "is required");
+        } else if email.chars().count() > MAX_EMAIL_LENGTH {
+            errors.add("email", format!("must be at most {MAX_EMAIL_LENGTH} characters"));
+        } else if !email.contains('@') {
+            errors.add("email", "must be an email address");
+        }
+
+        let password_length = self.password.chars().count();
+        if password_length < MIN_PASSWORD_LENGTH {
+            errors.add("password", format!("must be at least {MIN_PASSWORD_LENGTH} characters"));
+        } else if password_length > MAX_PASSWORD_LENGTH {
+            errors.add("password", format!("must be at most {MAX_PASSWORD_LENGTH} characters"));
+        }
+        errors.into_result()
+    }
+}
+
+impl Validate for CreateApiTokenRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if self.name.trim().is_empty() {
+            errors.add("name", "is required");
+        } else if self.name.chars().count() > MAX_TOKEN_NAME_LENGTH {
+            errors.add("name", format!("must be at most {MAX_TOKEN_NAME_LENGTH} characters"));
+        }
+        errors.into_result()
+    }
+}
+
+impl Validate for WorkspaceRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if self.name.trim().is_empty() {
+            errors.add("name", "is required");
+        } else if self.name.chars().count() > MAX_WORKSPACE_NAME_LENGTH {
+            errors.add("name", format!("must be at most {MAX_WORKSPACE_NAME_LENGTH} characters"));
+        }
+        if self.currency.len() != 3 || !self.currency.bytes().all(|b| b.is_ascii_uppercase()) {
+            errors.add("currency", "must be a three-letter ISO 4217 code such as USD");
+        }
+        errors.into_result()
+    }
+}
+
+impl Validate for CreateClientRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        check_name(&mut errors, &self.name, MAX_CLIENT_NAME_LENGTH);
+        errors.into_result()
+    }
+}
+
+impl Validate for UpdateClientRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        check_not_cleared(&mut errors, "name", &self.name);
+        if let Some(name) = self.name.value() {
+            check_name(&mut errors, name, MAX_CLIENT_NAME_LENGTH);
+        }
+        errors.into_result()
+    }
+}
+
+impl Validate for CreateProjectRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        check_name(&mut errors, &self.name, MAX_PROJECT_NAME_LENGTH);
+        check_hourly_rate(&mut errors, self.hourly_rate.as_ref());
+        check_budget(&mut errors, self.budget_seconds);
+        errors.into_result()
+    }
+}
+
+impl Validate for UpdateProjectRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        check_not_cleared(&mut errors, "client_id", &self.client_id);
+        check_not_cleared(&mut errors, "name", &self.name);
+        if let Some(name) = self.name.value() {
+            check_name(&mut errors, name, MAX_PROJECT_NAME_LENGTH);
+        }
+        check_hourly_rate(&mut errors, self.hourly_rate.value());
+        check_budget(&mut errors, self.budget_seconds.value().copied());
+        errors.into_result()
+    }
+}
+
+impl Validate for CreateInvoiceRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if self.to < self.from {
+            errors.add("to", "must not be before from");
+        }
+        errors.into_result()
+    }
+}
+
+impl Validate for CreateWebhookRequest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        let host = self
+            .url
+            .strip_prefix("https://")
+            .or_else(|| self.url.strip_prefix("http://"))
+            .and_then(|rest| rest.split(['/', '?', '#']).next());
+        if self.url.trim().is_empty() {
+            errors.add("url", "is required");
+        } else if self.url.len() > MAX_WEBHOOK_URL_LENGTH {
+            errors.add("url", format!("must be at most {MAX_WEBHOOK_URL_LENGTH} characters"));
+        } else if host.is_none_or(str::is_empty) || self.url.contains(char::is_whitespace) {
+            errors.add("url", "must be an http or https URL");
+        }
+
+        if self.events.is_empty() {
+            errors.add("events", "must not be empty");
+        } else if self.events.contains(&WebhookEvent::Ping) {
+            errors.add("events", "ping is sent on request and cannot be subscribed to");
+        }
+
+        if let Some(secret) = &self.secret {
+            let length = secret.chars().count();
+            if length < MIN_WEBHOOK_SECRET_LENGTH {
+                errors.add("secret", format!("must be at least {MIN_WEBHOOK_SECRET_LENGTH} characters"));
+            } else if length > MAX_WEBHOOK_SECRET_LENGTH {
+                errors.add("secret", format!("must be at most {MAX_WEBHOOK_SECRET_LENGTH} characters"));
+            }
+        }
+        errors.into_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn session() -> CreateSessionRequest {
+        CreateSessionRequest {
+            duration_seconds: None,
+            description: None,
+            started_at: None,
+            ended_at: None,
+            tag_ids: Vec::new(),
+            project_id: None,
+            billable: false,
+            hourly_rate: None,
+        }
+    }
+
+    fn tag(color: Option<&str>, hourly_rate: Option<&str>) -> CreateTagRequest {
+        CreateTagRequest {
+            name: "Design".to_string(),
+            color: color.map(str::to_string),
+            hourly_rate: hourly_rate.map(|rate| rate.parse().unwrap()),
+        }
+    }
+
+    fn webhook(url: &str) -> CreateWebhookRequest {
+        CreateWebhookRequest {
+            url: url.to_string(),
+            events: vec![WebhookEvent::SessionCreated],
+            secret: None,
+        }
+    }
+
+    /// The first error reported for `field`, if any.
+    fn error(result: Result<(), ValidationErrors>, field: &str) -> Option<String> {
+        result.err().and_then(|errors| errors.get(field).map(str::to_string))
+    }
+
+    #[test]
+    fn durations_must_not_be_negative() {
+        let req = CreateSessionRequest { duration_seconds: Some(-1), ..session() };
+        assert_eq!(error(req.validate(), "duration_seconds").as_deref(), Some("must not be negative"));
+        let req = CreateSessionRequest { duration_seconds: Some(0), ..session() };
+        assert_eq!(req.validate(), Ok(()));
+    }
+
+    #[test]
+    fn sessions_must_not_end_before_they_start() {
+        let started_at = Utc::now();
+        let req = CreateSessionRequest {
+            started_at: Some(started_at),
+            ended_at: Some(started_at - Duration::seconds(1)),
+            ..session()
+        };
+        assert_eq!(error(req.validate(), "ended_at").as_deref(), Some("must not be before started_at"));
+
+        let req = UpdateSessionRequest {
+            started_at: FieldUpdate::Set(started_at),
+            ended_at: FieldUpdate::Set(started_at - Duration::seconds(1)),
+            ..Default::default()
+        };
+        assert_eq!(error(req.validate(), "ended_at").as_deref(), Some("must not be before started_at"));
+
+        let req = CreateSessionRequest {
+            started_at: Some(started_at),
+            ended_at: Some(started_at),
+            ..session()
+        };
+        assert_eq!(req.validate(), Ok(()));
+    }
+
+    #[test]
+    fn tag_ids_must_not_repeat() {
+        let id = Uuid::new_v4();
+        let req = CreateSessionRequest { tag_ids: vec![id, Uuid::new_v4(), id], ..session() };
+        assert_eq!(error(req.validate(), "tag_ids").as_deref(), Some("must not contain duplicates"));
+
+        let req = UpdateSessionRequest { tag_ids: FieldUpdate::Set(vec![id, id]), ..Default::default() };
+        assert_eq!(error(req.validate(), "tag_ids").as_deref(), Some("must not contain duplicates"));
+    }
+
+    #[test]
+    fn required_session_fields_cannot_be_cleared() {
+        let req = UpdateSessionRequest {
+            billable: FieldUpdate::Clear,
+            started_at: FieldUpdate::Clear,
+            ..Default::default()
+        };
+        let errors = req.validate().unwrap_err();
+        assert_eq!(errors.get("billable"), Some("must not be null"));
+        assert_eq!(errors.get("started_at"), Some("must not be null"));
+        assert_eq!(errors.get("description"), None);
+
+        // Optional fields may be
+        let req = UpdateSessionRequest {
+            description: FieldUpdate::Clear,
+            hourly_rate: FieldUpdate::Clear,
+            ..Default::default()
+        };
+        assert_eq!(req.validate(), Ok(()));
+    }
+
+    #[test]
+    fn hourly_rates_fit_the_database_column() {
+        assert_eq!(MAX_HOURLY_RATE, "9999999999.99".parse::<Decimal>().unwrap());
+
+        for rate in ["0", "12.5", "12.50", "12.500", "9999999999.99"] {
+            assert_eq!(tag(None, Some(rate)).validate(), Ok(()), "{rate}");
+        }
+        assert_eq!(
+            error(tag(None, Some("12.345")).validate(), "hourly_rate").as_deref(),
+            Some("must have at most 2 decimal places")
+        );
+        assert_eq!(
+            error(tag(None, Some("10000000000")).validate(), "hourly_rate").as_deref(),
+            Some("must be at most 9999999999.99")
+        );
+        assert_eq!(
+            error(tag(None, Some("-1")).validate(), "hourly_rate").as_deref(),
+            Some("must not be negative")
+        );
+    }
+
+    #[test]
+    fn colors_must_be_six_digit_hex_codes() {
+        for color in ["#3B82F6", "#3b82f6", "#000000"] {
+            assert_eq!(tag(Some(color), None).validate(), Ok(()), "{color}");
+        }
+        for color in ["3B82F6", "#3B82F", "#3B82F6F", "#GGGGGG", "blue"] {
+            assert_eq!(
+                error(tag(Some(color), None).validate(), "color").as_deref(),
+                Some("must be a hex color like #3B82F6"),
+                "{color}"
+            );
+        }
+    }
+
+    #[test]
+    fn webhook_urls_need_an_http_scheme_and_a_host() {
+        for url in ["https://example.com", "http://localhost:3000/hooks", "https://example.com?token=1"] {
+            assert_eq!(webhook(url).validate(), Ok(()), "{url}");
+        }
+        for url in ["example.com", "ftp://example.com", "https://", "https:///hooks", "https://?x", "https://exa mple.com"] {
+            assert_eq!(
+                error(webhook(url).validate(), "url").as_deref(),
+                Some("must be an http or https URL"),
+                "{url}"
+            );
+        }
+        assert_eq!(error(webhook(" ").validate(), "url").as_deref(), Some("is required"));
+    }
+
+    #[test]
+    fn currencies_must_be_iso_codes() {
+        let workspace = |currency: &str| WorkspaceRequest {
+            name: "Studio".to_string(),
+            currency: currency.to_string(),
+        };
+        assert_eq!(workspace("EUR").validate(), Ok(()));
+        for currency in ["eur", "EU", "EURO", "E1R", ""] {
+            assert_eq!(
+                error(workspace(currency).validate(), "currency").as_deref(),
+                Some("must be a three-letter ISO 4217 code such as USD"),
+                "{currency}"
+            );
+        }
+    }
+}